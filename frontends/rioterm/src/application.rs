@@ -4,10 +4,11 @@ use crate::renderer::utils::update_colors_based_on_theme;
 use crate::router::{routes::RoutePath, Router};
 use crate::scheduler::{Scheduler, TimerId, Topic};
 use crate::screen::touch::on_touch;
-use crate::watcher::configuration_file_updates;
+use crate::watcher::{configuration_file_updates, ConfigWatcher};
 use raw_window_handle::HasDisplayHandle;
 use rio_backend::clipboard::{Clipboard, ClipboardType};
 use rio_backend::config::colors::ColorRgb;
+use rio_backend::error::RioError;
 use rio_window::application::ApplicationHandler;
 use rio_window::event::{
     ElementState, Hook, Ime, MouseButton, MouseScrollDelta, StartCause, TouchPhase,
@@ -21,7 +22,7 @@ use rio_window::platform::macos::ActiveEventLoopExtMacOS;
 #[cfg(target_os = "macos")]
 use rio_window::platform::macos::WindowExtMacOS;
 use rio_window::window::WindowId;
-use rio_window::window::{CursorIcon, Fullscreen};
+use rio_window::window::{CursorIcon, Fullscreen, UserAttentionType};
 use std::error::Error;
 use std::time::{Duration, Instant};
 
@@ -30,6 +31,7 @@ pub struct Application<'a> {
     event_proxy: EventProxy,
     router: Router<'a>,
     scheduler: Scheduler,
+    config_watcher: Option<ConfigWatcher>,
 }
 
 impl Application<'_> {
@@ -46,14 +48,21 @@ impl Application<'_> {
         let mut router = Router::new(config.fonts.to_owned(), clipboard);
         if let Some(error) = config_error {
             router.propagate_error_to_next_route(error.into());
+        } else if let Some(warnings) = RioError::configuration_warnings(&config.warnings)
+        {
+            router.propagate_error_to_next_route(warnings);
         }
 
         let proxy = event_loop.create_proxy();
         let event_proxy = EventProxy::new(proxy.clone());
-        let _ = configuration_file_updates(
+        let mut config_watcher = configuration_file_updates(
             rio_backend::config::config_dir_path(),
             event_proxy.clone(),
-        );
+        )
+        .ok();
+        if let Some(config_watcher) = &mut config_watcher {
+            config_watcher.watch_files(&config_files(&config));
+        }
         let scheduler = Scheduler::new(proxy);
         event_loop.listen_device_events(DeviceEvents::Never);
 
@@ -65,6 +74,7 @@ impl Application<'_> {
             event_proxy,
             router,
             scheduler,
+            config_watcher,
         }
     }
 
@@ -200,23 +210,8 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
             }
             RioEventType::Rio(RioEvent::UpdateGraphicLibrary) => {
                 if let Some(route) = self.router.routes.get_mut(&window_id) {
-                    let mut terminal = route
-                        .window
-                        .screen
-                        .context_manager
-                        .current_mut()
-                        .terminal
-                        .lock();
-                    let graphics = terminal.graphics_take_queues();
-                    drop(terminal);
-                    if let Some(graphic_queues) = graphics {
-                        for graphic_data in graphic_queues.pending {
-                            route.window.screen.sugarloaf.graphics.insert(graphic_data);
-                        }
-
-                        for graphic_data in graphic_queues.remove_queue {
-                            route.window.screen.sugarloaf.graphics.remove(&graphic_data);
-                        }
+                    if route.window.screen.update_graphic_library() {
+                        route.request_redraw();
                     }
                 }
             }
@@ -232,6 +227,13 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                     Err(error) => (rio_backend::config::Config::default(), Some(error)),
                 };
 
+                // Keep watching the previous imports until the config is fixed.
+                if let (Some(config_watcher), None) =
+                    (&mut self.config_watcher, &config_error)
+                {
+                    config_watcher.watch_files(&config_files(&config));
+                }
+
                 let has_font_updates = self.config.fonts != config.fonts;
 
                 let font_library_errors = if has_font_updates {
@@ -267,6 +269,10 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
 
                     if let Some(error) = &config_error {
                         route.report_error(&error.to_owned().into());
+                    } else if let Some(warnings) =
+                        RioError::configuration_warnings(&self.config.warnings)
+                    {
+                        route.report_error(&warnings);
                     } else {
                         route.clear_errors();
                     }
@@ -378,6 +384,32 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                     route.set_window_subtitle(&subtitle);
                 }
             }
+            RioEventType::Rio(RioEvent::TabColor(route_id, color)) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    if route
+                        .window
+                        .screen
+                        .context_manager
+                        .set_tab_color_by_route(route_id, color)
+                    {
+                        route.request_redraw();
+                    }
+                }
+            }
+            RioEventType::Rio(RioEvent::Bell(route_id, urgent)) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    if route.window.screen.ring_bell(route_id) {
+                        route.request_redraw();
+                    }
+
+                    if urgent && !route.window.is_focused {
+                        route
+                            .window
+                            .winit_window
+                            .request_user_attention(Some(UserAttentionType::Critical));
+                    }
+                }
+            }
             RioEventType::Rio(RioEvent::MouseCursorDirty) => {
                 if let Some(route) = self.router.routes.get_mut(&window_id) {
                     route.window.screen.reset_mouse();
@@ -437,6 +469,18 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                         .send_bytes(text.into_bytes());
                 }
             }
+            RioEventType::Rio(RioEvent::PtyWriteOnRoute(route_id, text)) => {
+                if let Some(route) = self.router.routes.get(&window_id) {
+                    if let Some(context) = route
+                        .window
+                        .screen
+                        .context_manager
+                        .context_by_route(route_id)
+                    {
+                        context.messenger.send_write(text.into_bytes());
+                    }
+                }
+            }
             RioEventType::Rio(RioEvent::TextAreaSizeRequest(format)) => {
                 if let Some(route) = self.router.routes.get_mut(&window_id) {
                     let dimension =
@@ -506,6 +550,47 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                     );
                 }
             }
+            #[cfg(target_os = "macos")]
+            RioEventType::Rio(RioEvent::CreateNativeTabWithProfile(profile)) => {
+                if let Some(route) = self.router.routes.get(&window_id) {
+                    let config = match self.config.profile(&profile) {
+                        Some(profile) => profile.apply(&self.config),
+                        None => return,
+                    };
+
+                    self.router.create_native_tab(
+                        event_loop,
+                        self.event_proxy.clone(),
+                        &config,
+                        Some(&route.window.winit_window.tabbing_identifier()),
+                        None,
+                    );
+                }
+            }
+            RioEventType::Rio(RioEvent::ConfirmPaste(message)) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    route.confirm_paste(message);
+                    route.request_redraw();
+                }
+            }
+            RioEventType::Rio(RioEvent::ConfirmDownload(name, data)) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    route.confirm_download(name, data);
+                    route.request_redraw();
+                }
+            }
+            RioEventType::Rio(RioEvent::OpenProfilePicker) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    route.open_profile_picker();
+                    route.request_redraw();
+                }
+            }
+            RioEventType::Rio(RioEvent::OpenThemePicker) => {
+                if let Some(route) = self.router.routes.get_mut(&window_id) {
+                    route.open_theme_picker(&self.config);
+                    route.request_redraw();
+                }
+            }
             RioEventType::Rio(RioEvent::CreateConfigEditor) => {
                 if self.config.navigation.open_config_with_split {
                     self.router.open_config_split(&self.config);
@@ -703,17 +788,27 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
 
                 match state {
                     ElementState::Pressed => {
+                        if button == MouseButton::Left
+                            && route.window.screen.select_tab_based_on_mouse()
+                        {
+                            return;
+                        }
+
                         // In case need to switch grid current
                         route.window.screen.select_current_based_on_mouse();
 
+                        if button == MouseButton::Left
+                            && route.window.screen.start_scrollbar_drag()
+                        {
+                            return;
+                        }
+
                         if route.window.screen.trigger_hyperlink() {
                             return;
                         }
 
                         // Process mouse press before bindings to update the `click_state`.
-                        if !route.window.screen.modifiers.state().shift_key()
-                            && route.window.screen.mouse_mode()
-                        {
+                        if route.window.screen.mouse_captured() {
                             route.window.screen.mouse.click_state = ClickState::None;
 
                             let code = match button {
@@ -772,9 +867,16 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                         route.window.screen.process_mouse_bindings(button);
                     }
                     ElementState::Released => {
-                        if !route.window.screen.modifiers.state().shift_key()
-                            && route.window.screen.mouse_mode()
-                        {
+                        if route.window.screen.mouse.dragging_tab {
+                            route.window.screen.mouse.dragging_tab = false;
+                            return;
+                        }
+
+                        if route.window.screen.end_scrollbar_drag() {
+                            return;
+                        }
+
+                        if route.window.screen.mouse_captured() {
                             let code = match button {
                                 MouseButton::Left => 0,
                                 MouseButton::Middle => 1,
@@ -842,6 +944,8 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
 
                 let display_offset = route.window.screen.display_offset();
                 let old_point = route.window.screen.mouse_position(display_offset);
+                let old_pixel =
+                    (route.window.screen.mouse.x, route.window.screen.mouse.y);
 
                 let layout = route.window.screen.sugarloaf.window_size();
 
@@ -850,9 +954,20 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                 route.window.screen.mouse.x = x;
                 route.window.screen.mouse.y = y;
 
+                if route.window.screen.drag_tab_based_on_mouse() {
+                    return;
+                }
+
+                if route.window.screen.update_scrollbar_drag() {
+                    return;
+                }
+
                 let point = route.window.screen.mouse_position(display_offset);
 
-                let square_changed = old_point != point;
+                // SGR-Pixels reports every movement, not only cell changes.
+                let square_changed = old_point != point
+                    || (old_pixel != (x, y)
+                        && route.window.screen.has_sgr_pixels_mouse());
 
                 let inside_text_area = route.window.screen.contains_point(x, y);
                 let square_side = route.window.screen.side_by_pos(x);
@@ -869,14 +984,11 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                     route.window.winit_window.set_cursor(CursorIcon::Pointer);
                     route.window.screen.context_manager.request_render();
                 } else {
-                    let cursor_icon =
-                        if !route.window.screen.modifiers.state().shift_key()
-                            && route.window.screen.mouse_mode()
-                        {
-                            CursorIcon::Default
-                        } else {
-                            CursorIcon::Text
-                        };
+                    let cursor_icon = if route.window.screen.mouse_captured() {
+                        CursorIcon::Default
+                    } else {
+                        CursorIcon::Text
+                    };
 
                     route.window.winit_window.set_cursor(cursor_icon);
 
@@ -901,10 +1013,7 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                 route.window.screen.mouse.inside_text_area = inside_text_area;
                 route.window.screen.mouse.square_side = square_side;
 
-                if (lmb_pressed || rmb_pressed)
-                    && (route.window.screen.modifiers.state().shift_key()
-                        || !route.window.screen.mouse_mode())
-                {
+                if (lmb_pressed || rmb_pressed) && !route.window.screen.mouse_captured() {
                     route.window.screen.update_selection(point, square_side);
                     route.window.screen.context_manager.request_render();
                 } else if square_changed
@@ -1060,6 +1169,7 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                 route.window.is_focused = focused;
 
                 if has_regained_focus {
+                    route.window.winit_window.request_user_attention(None);
                     route.request_redraw();
                 }
 
@@ -1085,8 +1195,15 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                     return;
                 }
 
-                let path: String = path.to_string_lossy().into();
-                route.window.screen.paste(&(path + " "), true);
+                route.window.screen.drop_file(path);
+            }
+
+            WindowEvent::DroppedText(text) => {
+                if route.path == RoutePath::Assistant {
+                    return;
+                }
+
+                route.window.screen.paste(&text, true);
             }
 
             WindowEvent::Resized(new_size) => {
@@ -1126,8 +1243,30 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
                         route
                             .window
                             .screen
-                            .render_dialog("Do you want to leave Rio?");
+                            .render_dialog("Do you want to leave Rio?", "quit");
                     }
+                    RoutePath::ConfirmPaste => {
+                        route
+                            .window
+                            .screen
+                            .render_dialog(&route.paste_warning, "paste");
+                    }
+                    RoutePath::ConfirmDownload => {
+                        route
+                            .window
+                            .screen
+                            .render_dialog(&route.download_warning, "download");
+                    }
+                    RoutePath::ProfilePicker => {
+                        route
+                            .window
+                            .screen
+                            .render_profile_picker(route.selected_profile);
+                    }
+                    RoutePath::ThemePicker => match &route.theme_picker {
+                        Some(picker) => route.window.screen.render_theme_picker(picker),
+                        None => route.window.screen.render(),
+                    },
                 }
                 // println!("Time elapsed in render() is: {:?}", duration);
                 // }
@@ -1146,6 +1285,11 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // Files dropped together arrive as separate events.
+        for route in self.router.routes.values_mut() {
+            route.window.screen.flush_dropped_files();
+        }
+
         let control_flow = match self.scheduler.update() {
             Some(instant) => ControlFlow::WaitUntil(instant),
             None => {
@@ -1231,3 +1375,11 @@ impl ApplicationHandler<EventPayload> for Application<'_> {
         std::process::exit(0);
     }
 }
+
+/// Config file and its imports, which might live outside of the config
+/// directory.
+fn config_files(config: &rio_backend::config::Config) -> Vec<std::path::PathBuf> {
+    let mut files = vec![rio_backend::config::config_file_path()];
+    files.extend(config.imported_files.iter().cloned());
+    files
+}
@@ -56,6 +56,8 @@ pub enum SearchAction {
     SearchHistoryPrevious,
     /// Go to the next regex in the search history.
     SearchHistoryNext,
+    /// Replace the search regex with the next preset from the config.
+    SearchNextPreset,
 }
 
 impl From<SearchAction> for Action {
@@ -208,60 +210,230 @@ impl Program {
     }
 }
 
+/// Actions without arguments by their lowercase config name, with the
+/// title the command palette lists them under, in palette order.
+pub const NAMED_ACTIONS: [(&str, Action, Option<&str>); 55] = [
+    ("createtab", Action::TabCreateNew, Some("Create Tab")),
+    ("closetab", Action::TabCloseCurrent, Some("Close Tab")),
+    (
+        "closecurrenttaborsplit",
+        Action::CloseCurrentSplitOrTab,
+        Some("Close Split or Tab"),
+    ),
+    (
+        "closeunfocusedtabs",
+        Action::TabCloseUnfocused,
+        Some("Close Unfocused Tabs"),
+    ),
+    (
+        "selectnexttab",
+        Action::SelectNextTab,
+        Some("Select Next Tab"),
+    ),
+    (
+        "selectprevtab",
+        Action::SelectPrevTab,
+        Some("Select Previous Tab"),
+    ),
+    (
+        "selectlasttab",
+        Action::SelectLastTab,
+        Some("Select Last Tab"),
+    ),
+    (
+        "movecurrenttabtoprev",
+        Action::MoveCurrentTabToPrev,
+        Some("Move Tab to Previous"),
+    ),
+    (
+        "movecurrenttabtonext",
+        Action::MoveCurrentTabToNext,
+        Some("Move Tab to Next"),
+    ),
+    ("renametab", Action::RenameTab, Some("Rename Tab")),
+    (
+        "resettabcolor",
+        Action::ResetTabColor,
+        Some("Reset Tab Color"),
+    ),
+    ("splitright", Action::SplitRight, Some("Split Right")),
+    ("splitdown", Action::SplitDown, Some("Split Down")),
+    (
+        "selectnextsplit",
+        Action::SelectNextSplit,
+        Some("Select Next Split"),
+    ),
+    (
+        "selectprevsplit",
+        Action::SelectPrevSplit,
+        Some("Select Previous Split"),
+    ),
+    (
+        "togglebroadcastinput",
+        Action::ToggleBroadcastInput,
+        Some("Toggle Broadcast Input"),
+    ),
+    (
+        "togglebroadcastinputsplit",
+        Action::ToggleBroadcastInputSplit,
+        Some("Toggle Broadcast Input for Split"),
+    ),
+    (
+        "togglereadonly",
+        Action::ToggleReadOnly,
+        Some("Toggle Read-Only"),
+    ),
+    (
+        "toggleanimations",
+        Action::ToggleAnimations,
+        Some("Toggle Image Animations"),
+    ),
+    (
+        "createwindow",
+        Action::WindowCreateNew,
+        Some("Create Window"),
+    ),
+    (
+        "openconfigeditor",
+        Action::ConfigEditor,
+        Some("Open Config Editor"),
+    ),
+    (
+        "openprofilepicker",
+        Action::OpenProfilePicker,
+        Some("Open Profile Picker"),
+    ),
+    (
+        "openthemepicker",
+        Action::OpenThemePicker,
+        Some("Open Theme Picker"),
+    ),
+    ("copy", Action::Copy, Some("Copy")),
+    ("paste", Action::Paste, Some("Paste")),
+    (
+        "pasteselection",
+        Action::PasteSelection,
+        Some("Paste Selection"),
+    ),
+    (
+        "clearselection",
+        Action::ClearSelection,
+        Some("Clear Selection"),
+    ),
+    (
+        "searchforward",
+        Action::SearchForward,
+        Some("Search Forward"),
+    ),
+    (
+        "searchbackward",
+        Action::SearchBackward,
+        Some("Search Backward"),
+    ),
+    ("togglevimode", Action::ToggleViMode, Some("Toggle Vi Mode")),
+    (
+        "togglefullscreen",
+        Action::ToggleFullscreen,
+        Some("Toggle Fullscreen"),
+    ),
+    (
+        "increasefontsize",
+        Action::IncreaseFontSize,
+        Some("Increase Font Size"),
+    ),
+    (
+        "decreasefontsize",
+        Action::DecreaseFontSize,
+        Some("Decrease Font Size"),
+    ),
+    (
+        "resetfontsize",
+        Action::ResetFontSize,
+        Some("Reset Font Size"),
+    ),
+    ("scrollpageup", Action::ScrollPageUp, Some("Scroll Page Up")),
+    (
+        "scrollpagedown",
+        Action::ScrollPageDown,
+        Some("Scroll Page Down"),
+    ),
+    (
+        "scrollhalfpageup",
+        Action::ScrollHalfPageUp,
+        Some("Scroll Half Page Up"),
+    ),
+    (
+        "scrollhalfpagedown",
+        Action::ScrollHalfPageDown,
+        Some("Scroll Half Page Down"),
+    ),
+    ("scrolltotop", Action::ScrollToTop, Some("Scroll to Top")),
+    (
+        "scrolltobottom",
+        Action::ScrollToBottom,
+        Some("Scroll to Bottom"),
+    ),
+    ("clearhistory", Action::ClearHistory, Some("Clear History")),
+    ("minimize", Action::Minimize, Some("Minimize")),
+    ("quit", Action::Quit, Some("Quit")),
+    ("opencommandpalette", Action::OpenCommandPalette, None),
+    ("receivechar", Action::ReceiveChar, None),
+    ("none", Action::None, None),
+    (
+        "searchconfirm",
+        Action::Search(SearchAction::SearchConfirm),
+        None,
+    ),
+    (
+        "searchcancel",
+        Action::Search(SearchAction::SearchCancel),
+        None,
+    ),
+    (
+        "searchclear",
+        Action::Search(SearchAction::SearchClear),
+        None,
+    ),
+    (
+        "searchfocusnext",
+        Action::Search(SearchAction::SearchFocusNext),
+        None,
+    ),
+    (
+        "searchfocusprevious",
+        Action::Search(SearchAction::SearchFocusPrevious),
+        None,
+    ),
+    (
+        "searchdeleteword",
+        Action::Search(SearchAction::SearchDeleteWord),
+        None,
+    ),
+    (
+        "searchhistorynext",
+        Action::Search(SearchAction::SearchHistoryNext),
+        None,
+    ),
+    (
+        "searchhistoryprevious",
+        Action::Search(SearchAction::SearchHistoryPrevious),
+        None,
+    ),
+    (
+        "searchnextpreset",
+        Action::Search(SearchAction::SearchNextPreset),
+        None,
+    ),
+];
+
 impl From<String> for Action {
     fn from(action: String) -> Action {
         let action = action.to_lowercase();
 
-        let action_from_string = match action.as_str() {
-            "paste" => Some(Action::Paste),
-            "quit" => Some(Action::Quit),
-            "copy" => Some(Action::Copy),
-            "searchforward" => Some(Action::SearchForward),
-            "searchbackward" => Some(Action::SearchBackward),
-            "searchconfirm" => Some(Action::Search(SearchAction::SearchConfirm)),
-            "searchcancel" => Some(Action::Search(SearchAction::SearchCancel)),
-            "searchclear" => Some(Action::Search(SearchAction::SearchClear)),
-            "searchfocusnext" => Some(Action::Search(SearchAction::SearchFocusNext)),
-            "searchfocusprevious" => {
-                Some(Action::Search(SearchAction::SearchFocusPrevious))
-            }
-            "searchdeleteword" => Some(Action::Search(SearchAction::SearchDeleteWord)),
-            "searchhistorynext" => Some(Action::Search(SearchAction::SearchHistoryNext)),
-            "searchhistoryprevious" => {
-                Some(Action::Search(SearchAction::SearchHistoryPrevious))
-            }
-            "clearhistory" => Some(Action::ClearHistory),
-            "resetfontsize" => Some(Action::ResetFontSize),
-            "increasefontsize" => Some(Action::IncreaseFontSize),
-            "decreasefontsize" => Some(Action::DecreaseFontSize),
-            "createwindow" => Some(Action::WindowCreateNew),
-            "createtab" => Some(Action::TabCreateNew),
-            "movecurrenttabtoprev" => Some(Action::MoveCurrentTabToPrev),
-            "movecurrenttabtonext" => Some(Action::MoveCurrentTabToNext),
-            "closetab" => Some(Action::TabCloseCurrent),
-            "closecurrenttaborsplit" => Some(Action::CloseCurrentSplitOrTab),
-            "closeunfocusedtabs" => Some(Action::TabCloseUnfocused),
-            "openconfigeditor" => Some(Action::ConfigEditor),
-            "selectprevtab" => Some(Action::SelectPrevTab),
-            "selectnexttab" => Some(Action::SelectNextTab),
-            "selectlasttab" => Some(Action::SelectLastTab),
-            "receivechar" => Some(Action::ReceiveChar),
-            "scrollhalfpageup" => Some(Action::ScrollHalfPageUp),
-            "scrollhalfpagedown" => Some(Action::ScrollHalfPageDown),
-            "scrolltotop" => Some(Action::ScrollToTop),
-            "scrolltobottom" => Some(Action::ScrollToBottom),
-            "splitright" => Some(Action::SplitRight),
-            "splitdown" => Some(Action::SplitDown),
-            "selectnextsplit" => Some(Action::SelectNextSplit),
-            "selectprevsplit" => Some(Action::SelectPrevSplit),
-            "togglevimode" => Some(Action::ToggleViMode),
-            "togglefullscreen" => Some(Action::ToggleFullscreen),
-            "none" => Some(Action::None),
-            _ => None,
-        };
-
-        if action_from_string.is_some() {
-            return action_from_string.unwrap_or(Action::None);
+        if let Some((_, named, _)) =
+            NAMED_ACTIONS.iter().find(|(name, _, _)| *name == action)
+        {
+            return named.clone();
         }
 
         let re = regex::Regex::new(r"selecttab\(([^()]+)\)").unwrap();
@@ -306,6 +478,27 @@ impl From<String> for Action {
             }
         }
 
+        let re =
+            regex::Regex::new(r"^(createtab|splitright|splitdown)\(([^()]+)\)$").unwrap();
+        if let Some(capture) = re.captures(&action) {
+            let profile = capture[2].trim().to_string();
+            return match &capture[1] {
+                "createtab" => Action::TabCreateNewWithProfile(profile),
+                "splitright" => Action::SplitRightWithProfile(profile),
+                _ => Action::SplitDownWithProfile(profile),
+            };
+        }
+
+        let re = regex::Regex::new(r"^searchpreset\(([^()]+)\)$").unwrap();
+        if let Some(capture) = re.captures(&action) {
+            return Action::SearchPreset(capture[1].trim().to_string());
+        }
+
+        let re = regex::Regex::new(r"^settabcolor\((#?[0-9a-f]{6})\)$").unwrap();
+        if let Some(capture) = re.captures(&action) {
+            return Action::SetTabColor(capture[1].to_string());
+        }
+
         Action::None
     }
 }
@@ -409,12 +602,33 @@ pub enum Action {
     /// Create a new Rio tab.
     TabCreateNew,
 
+    /// Create a new Rio tab using a profile.
+    TabCreateNewWithProfile(String),
+
+    /// List profiles to open one of them in a new tab.
+    OpenProfilePicker,
+
+    /// List themes, previewing the selected one.
+    OpenThemePicker,
+
+    /// Fuzzy search actions, tabs and splits.
+    OpenCommandPalette,
+
     /// Move current tab to previous slot.
     MoveCurrentTabToPrev,
 
     /// Move current tab to next slot.
     MoveCurrentTabToNext,
 
+    /// Prompt for a title to pin on the current tab.
+    RenameTab,
+
+    /// Set the current tab color, e.g. `SetTabColor(#ff0000)`.
+    SetTabColor(String),
+
+    /// Reset the current tab color.
+    ResetTabColor,
+
     /// Switch to next tab.
     SelectNextTab,
 
@@ -459,15 +673,36 @@ pub enum Action {
     /// Start a backward buffer search.
     SearchBackward,
 
+    /// Search with the `search.presets` entry of the given name.
+    SearchPreset(String),
+
     /// Split horizontally
     SplitRight,
 
     /// Split vertically
     SplitDown,
 
+    /// Split horizontally using a profile.
+    SplitRightWithProfile(String),
+
+    /// Split vertically using a profile.
+    SplitDownWithProfile(String),
+
     SelectNextSplit,
     SelectPrevSplit,
 
+    /// Send input to every split of the current tab.
+    ToggleBroadcastInput,
+
+    /// Add or remove the current split from the broadcast group.
+    ToggleBroadcastInputSplit,
+
+    /// Drop input written to the current split.
+    ToggleReadOnly,
+
+    /// Pause or resume every animated image.
+    ToggleAnimations,
+
     /// Allow receiving char input.
     ReceiveChar,
 
@@ -919,6 +1154,7 @@ pub fn platform_key_bindings(
         "q", ModifiersState::SUPER; Action::Quit;
         "n", ModifiersState::SUPER; Action::WindowCreateNew;
         ",", ModifiersState::SUPER; Action::ConfigEditor;
+        "p", ModifiersState::SUPER | ModifiersState::SHIFT; Action::OpenCommandPalette;
 
         // Search
         "f", ModifiersState::SUPER, ~BindingMode::SEARCH; Action::SearchForward;
@@ -930,6 +1166,7 @@ pub fn platform_key_bindings(
         "n", ModifiersState::CONTROL,  +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
         Key::Named(ArrowUp), +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
         Key::Named(ArrowDown), +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        Key::Named(Tab), +BindingMode::SEARCH; SearchAction::SearchNextPreset;
     );
 
     if use_navigation_key_bindings {
@@ -998,6 +1235,7 @@ pub fn platform_key_bindings(
         "-", ModifiersState::CONTROL;  Action::DecreaseFontSize;
         "n", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::WindowCreateNew;
         ",", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::ConfigEditor;
+        "p", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::OpenCommandPalette;
 
         // Search
         "f", ModifiersState::CONTROL | ModifiersState::SHIFT, ~BindingMode::SEARCH; Action::SearchForward;
@@ -1009,6 +1247,7 @@ pub fn platform_key_bindings(
         "n", ModifiersState::CONTROL,  +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
         Key::Named(ArrowUp), +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
         Key::Named(ArrowDown), +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        Key::Named(Tab), +BindingMode::SEARCH; SearchAction::SearchNextPreset;
     );
 
     if use_navigation_key_bindings {
@@ -1058,6 +1297,7 @@ pub fn platform_key_bindings(
         Key::Named(Enter), ModifiersState::ALT; Action::ToggleFullscreen;
         "n", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::WindowCreateNew;
         ",", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::ConfigEditor;
+        "p", ModifiersState::CONTROL | ModifiersState::SHIFT; Action::OpenCommandPalette;
         // This is actually a Windows Powershell shortcut
         // https://github.com/alacritty/alacritty/issues/2930
         // https://github.com/raphamorim/rio/issues/220#issuecomment-1761651339
@@ -1074,6 +1314,7 @@ pub fn platform_key_bindings(
         "n", ModifiersState::CONTROL,  +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
         Key::Named(ArrowUp), +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
         Key::Named(ArrowDown), +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        Key::Named(Tab), +BindingMode::SEARCH; SearchAction::SearchNextPreset;
     );
 
     if use_navigation_key_bindings {
@@ -1382,4 +1623,78 @@ mod tests {
         assert_eq!(new_bindings.len(), 2);
         assert_eq!(new_bindings[1].action, Action::ReceiveChar);
     }
+
+    #[test]
+    fn profile_actions_from_string() {
+        assert_eq!(
+            Action::from(String::from("CreateTab(Prod)")),
+            Action::TabCreateNewWithProfile(String::from("prod"))
+        );
+        assert_eq!(
+            Action::from(String::from("SplitRight(staging db)")),
+            Action::SplitRightWithProfile(String::from("staging db"))
+        );
+        assert_eq!(
+            Action::from(String::from("SplitDown(prod)")),
+            Action::SplitDownWithProfile(String::from("prod"))
+        );
+        assert_eq!(
+            Action::from(String::from("OpenProfilePicker")),
+            Action::OpenProfilePicker
+        );
+        assert_eq!(
+            Action::from(String::from("OpenThemePicker")),
+            Action::OpenThemePicker
+        );
+        assert_eq!(
+            Action::from(String::from("OpenCommandPalette")),
+            Action::OpenCommandPalette
+        );
+        assert_eq!(
+            Action::from(String::from("CreateTab")),
+            Action::TabCreateNew
+        );
+    }
+
+    #[test]
+    fn tab_actions_from_string() {
+        assert_eq!(Action::from(String::from("RenameTab")), Action::RenameTab);
+        assert_eq!(
+            Action::from(String::from("SetTabColor(#FF00AA)")),
+            Action::SetTabColor(String::from("#ff00aa"))
+        );
+        assert_eq!(Action::from(String::from("SetTabColor(red)")), Action::None);
+        assert_eq!(
+            Action::from(String::from("ResetTabColor")),
+            Action::ResetTabColor
+        );
+    }
+
+    #[test]
+    fn named_actions_from_string() {
+        for (name, action, _) in NAMED_ACTIONS.iter() {
+            assert_eq!(&Action::from(name.to_string()), action, "{name}");
+        }
+        assert_eq!(
+            Action::from(String::from("PasteSelection")),
+            Action::PasteSelection
+        );
+    }
+
+    #[test]
+    fn search_preset_from_string() {
+        assert_eq!(
+            Action::from(String::from("SearchPreset(IPv4)")),
+            Action::SearchPreset(String::from("ipv4"))
+        );
+        assert_eq!(
+            Action::from(String::from("SearchPreset( error lines )")),
+            Action::SearchPreset(String::from("error lines"))
+        );
+        assert_eq!(
+            Action::from(String::from("SearchPreset(Ärger)")),
+            Action::SearchPreset(String::from("ärger"))
+        );
+        assert_eq!(Action::from(String::from("SearchPreset()")), Action::None);
+    }
 }
@@ -1,7 +1,7 @@
 // cli.rs was retired originally from https://github.com/alacritty/alacritty/blob/e35e5ad14fce8456afdd89f2b392b9924bb27471/alacritty/src/cli.rs
 // which is licensed under Apache 2.0 license.
 
-use clap::{Args, Parser, ValueHint};
+use clap::{Args, Parser, Subcommand, ValueHint};
 use rio_backend::config::Shell;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -9,9 +9,49 @@ use std::path::PathBuf;
 #[derive(Parser, Default, Debug)]
 #[clap(author, about, version)]
 pub struct Cli {
+    /// Load the configuration from the specified file.
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub config_file: Option<PathBuf>,
+
+    /// Override configuration file options [example: -o window.opacity=0.8].
+    #[clap(short = 'o', long = "option", value_name = "KEY=VALUE", num_args = 1)]
+    pub option: Vec<String>,
+
+    /// Check the configuration file for errors and exit.
+    #[clap(long)]
+    pub check_config: bool,
+
     /// Options which can be passed via IPC.
     #[clap(flatten)]
     pub window_options: WindowOptions,
+
+    #[clap(subcommand)]
+    pub subcommand: Option<Subcommands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Manage themes.
+    #[clap(subcommand)]
+    Theme(ThemeSubcommands),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ThemeSubcommands {
+    /// Convert a theme of another terminal into a Rio theme.
+    Import {
+        /// iTerm2, Alacritty, kitty, Windows Terminal, base16 or Ghostty theme.
+        #[clap(value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// Name of the Rio theme, defaults to the file name.
+        #[clap(long)]
+        name: Option<String>,
+
+        /// Overwrite the theme when it already exists.
+        #[clap(long)]
+        force: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize, Args, Default, Clone, Debug, PartialEq, Eq)]
@@ -1,5 +1,6 @@
 use crate::context::Context;
 use crate::mouse::Mouse;
+use rio_backend::config::colors::defaults;
 use rio_backend::crosswords::grid::Dimensions;
 use rio_backend::event::EventListener;
 use rio_backend::sugarloaf::{
@@ -64,6 +65,12 @@ pub struct ContextGrid<T: EventListener> {
     pub current: usize,
     pub margin: Delta<f32>,
     border_color: [f32; 4],
+    active_border_color: Option<[f32; 4]>,
+    active_border_width: f32,
+    broadcast_border_color: [f32; 4],
+    read_only_border_color: [f32; 4],
+    /// Bell rang while the tab was in the background.
+    pub has_bell: bool,
     inner: Vec<ContextGridItem<T>>,
 }
 
@@ -85,7 +92,6 @@ impl<T: rio_backend::event::EventListener> ContextGridItem<T> {
 
 impl<T: rio_backend::event::EventListener> ContextGridItem<T> {
     #[inline]
-    #[allow(unused)]
     pub fn context(&self) -> &Context<T> {
         &self.val
     }
@@ -108,9 +114,114 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
             width,
             height,
             border_color,
+            active_border_color: None,
+            active_border_width: 1.0,
+            broadcast_border_color: defaults::split_broadcast(),
+            read_only_border_color: defaults::split_read_only(),
+            has_bell: false,
         }
     }
 
+    /// Border drawn around the focused split when there is more than one.
+    #[inline]
+    pub fn set_active_border(&mut self, color: Option<[f32; 4]>, width: f32) {
+        self.active_border_color = color;
+        self.active_border_width = width;
+    }
+
+    /// Borders drawn around broadcasting and read-only splits.
+    #[inline]
+    pub fn set_state_borders(&mut self, broadcast: [f32; 4], read_only: [f32; 4]) {
+        self.broadcast_border_color = broadcast;
+        self.read_only_border_color = read_only;
+    }
+
+    /// Broadcast input to every split of the grid, or stop broadcasting
+    /// if all of them already are.
+    pub fn toggle_broadcast(&mut self) {
+        let broadcast = !self.inner.iter().all(|item| item.val.broadcast);
+        for item in self.inner.iter_mut() {
+            item.val.broadcast = broadcast;
+            item.val.renderable_content.mark_pending_updates();
+        }
+    }
+
+    /// Add or remove the current split from the broadcast group.
+    pub fn toggle_current_broadcast(&mut self) {
+        let current = &mut self.inner[self.current].val;
+        current.broadcast = !current.broadcast;
+        current.renderable_content.mark_pending_updates();
+    }
+
+    pub fn toggle_current_read_only(&mut self) {
+        let current = &mut self.inner[self.current].val;
+        current.read_only = !current.read_only;
+        current.renderable_content.mark_pending_updates();
+    }
+
+    /// Indexes of the splits that receive input typed into the current one.
+    pub fn input_targets(&self) -> Vec<usize> {
+        if self.inner[self.current].val.broadcast {
+            self.inner
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.val.broadcast && !item.val.read_only)
+                .map(|(index, _)| index)
+                .collect()
+        } else if self.inner[self.current].val.read_only {
+            vec![]
+        } else {
+            vec![self.current]
+        }
+    }
+
+    /// Write user input to the current split and to the broadcast group.
+    pub fn write_input(&mut self, bytes: Vec<u8>) {
+        let targets = self.input_targets();
+        if let Some((last, rest)) = targets.split_last() {
+            for index in rest {
+                self.inner[*index].val.messenger.send_bytes(bytes.clone());
+            }
+            self.inner[*last].val.messenger.send_bytes(bytes);
+        }
+    }
+
+    fn state_border_color(&self, context: &Context<T>) -> Option<[f32; 4]> {
+        if context.read_only {
+            Some(self.read_only_border_color)
+        } else if context.broadcast {
+            Some(self.broadcast_border_color)
+        } else {
+            None
+        }
+    }
+
+    fn push_frame(
+        &self,
+        objects: &mut Vec<Object>,
+        color: [f32; 4],
+        context: &Context<T>,
+        margin: Delta<f32>,
+    ) {
+        let width = context.dimension.width / context.dimension.dimension.scale;
+        let height = context.dimension.height / context.dimension.dimension.scale;
+        let border = self.active_border_width;
+        let (x, y) = (margin.x, margin.top_y);
+
+        objects.push(create_border(color, [x, y], [width, border]));
+        objects.push(create_border(
+            color,
+            [x, y + height - border],
+            [width, border],
+        ));
+        objects.push(create_border(color, [x, y], [border, height]));
+        objects.push(create_border(
+            color,
+            [x + width - border, y],
+            [border, height],
+        ));
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -122,8 +233,7 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
     }
 
     #[inline]
-    #[allow(unused)]
-    pub fn contexts(&mut self) -> &Vec<ContextGridItem<T>> {
+    pub fn contexts(&self) -> &Vec<ContextGridItem<T>> {
         &self.inner
     }
 
@@ -169,6 +279,34 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
         &mut self.inner[self.current].val
     }
 
+    /// Tab color of the first split that has one.
+    #[inline]
+    pub fn tab_color(&self) -> Option<[f32; 4]> {
+        self.inner.iter().find_map(|item| item.val.tab_color)
+    }
+
+    #[inline]
+    pub fn set_tab_color(&mut self, color: Option<[f32; 4]>) {
+        for item in self.inner.iter_mut() {
+            item.val.tab_color = color;
+        }
+    }
+
+    /// Title pinned by the first split that has one.
+    #[inline]
+    pub fn pinned_title(&self) -> Option<&String> {
+        self.inner
+            .iter()
+            .find_map(|item| item.val.pinned_title.as_ref())
+    }
+
+    #[inline]
+    pub fn set_pinned_title(&mut self, title: Option<String>) {
+        for item in self.inner.iter_mut() {
+            item.val.pinned_title = title.clone();
+        }
+    }
+
     #[inline]
     pub fn objects(&self) -> Vec<Object> {
         let len = self.inner.len();
@@ -185,6 +323,9 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
                     id: item.val.rich_text_id,
                     position: [self.margin.x, self.margin.top_y],
                 }));
+                if let Some(color) = self.state_border_color(&item.val) {
+                    self.push_frame(&mut objects, color, &item.val, self.margin);
+                }
             }
         } else {
             self.plot_objects(&mut objects, 0, self.margin);
@@ -192,6 +333,25 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
         objects
     }
 
+    /// Index, position and size of every split, clamped to the grid.
+    pub fn rich_text_bounds(&self) -> Vec<(usize, [f32; 2], [f32; 2])> {
+        self.objects()
+            .into_iter()
+            .filter_map(|object| match object {
+                Object::RichText(rich_text) => {
+                    let index = self.find_by_rich_text_id(rich_text.id)?;
+                    let dimension = &self.inner[index].val.dimension;
+                    let scale = dimension.dimension.scale;
+                    let [x, y] = rich_text.position;
+                    let width = (dimension.width / scale).min(self.width / scale - x);
+                    let height = (dimension.height / scale).min(self.height / scale - y);
+                    Some((index, rich_text.position, [width, height]))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn current_context_with_computed_dimension(&self) -> (&Context<T>, Delta<f32>) {
         let len = self.inner.len();
         if len <= 1 {
@@ -299,6 +459,15 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
                 position: [margin.x, margin.top_y],
             }));
 
+            let active_color = if index == self.current {
+                self.active_border_color
+            } else {
+                None
+            };
+            if let Some(color) = self.state_border_color(&item.val).or(active_color) {
+                self.push_frame(objects, color, &item.val, margin);
+            }
+
             let scale = self.inner[self.current].val.dimension.dimension.scale;
             let scaled_padding = PADDING * scale;
 
@@ -370,6 +539,29 @@ impl<T: rio_backend::event::EventListener> ContextGrid<T> {
         }
     }
 
+    /// Font size of the split `rich_text_id` changed, it keeps its place in
+    /// the grid and only its columns and lines follow the new cell size.
+    pub fn update_context_dimension(
+        &mut self,
+        rich_text_id: usize,
+        dimensions: SugarDimensions,
+    ) {
+        let index = match self.find_by_rich_text_id(rich_text_id) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if self.inner[index].val.dimension.dimension == dimensions {
+            return;
+        }
+
+        self.inner[index]
+            .val
+            .dimension
+            .update_dimensions(dimensions);
+        self.request_resize(index);
+    }
+
     pub fn resize(&mut self, new_width: f32, new_height: f32) {
         let width_difference = new_width - self.width;
         let height_difference = new_height - self.height;
@@ -1035,6 +1227,163 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn test_active_border_follows_current_split() {
+        let context_dimension = ContextDimension::build(
+            1200.0,
+            800.0,
+            SugarDimensions {
+                scale: 1.,
+                width: 14.,
+                height: 8.,
+            },
+            1.0,
+            Delta::<f32>::default(),
+        );
+
+        let first_context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            0,
+            context_dimension,
+        );
+        let second_context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            1,
+            context_dimension,
+        );
+
+        let active = [1., 0., 1., 1.];
+        let mut grid = ContextGrid::<VoidListener>::new(
+            first_context,
+            Delta::<f32>::default(),
+            [1., 0., 0., 0.],
+        );
+        grid.set_active_border(Some(active), 2.);
+
+        let active_borders = |grid: &ContextGrid<VoidListener>| -> Vec<[f32; 2]> {
+            grid.objects()
+                .into_iter()
+                .filter_map(|object| match object {
+                    Object::Quad(composed) if composed.color == active => {
+                        Some(composed.quad.position)
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // A single split is not highlighted.
+        assert!(active_borders(&grid).is_empty());
+
+        grid.split_right(second_context);
+        assert_eq!(grid.current, 1);
+        let borders = active_borders(&grid);
+        assert_eq!(borders.len(), 4);
+        let right_x = grid
+            .objects()
+            .into_iter()
+            .find_map(|object| match object {
+                Object::RichText(rich_text) if rich_text.id == 1 => {
+                    Some(rich_text.position[0])
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(right_x > 0.);
+        assert!(borders.iter().all(|position| position[0] >= right_x));
+
+        grid.select_prev_split();
+        let borders = active_borders(&grid);
+        assert_eq!(borders.len(), 4);
+        assert_eq!(borders[0], [0., 0.]);
+    }
+
+    #[test]
+    fn test_broadcast_and_read_only_splits() {
+        let context_dimension = ContextDimension::build(
+            1200.0,
+            800.0,
+            SugarDimensions {
+                scale: 1.,
+                width: 14.,
+                height: 8.,
+            },
+            1.0,
+            Delta::<f32>::default(),
+        );
+
+        let mut grid = ContextGrid::<VoidListener>::new(
+            create_mock_context(
+                VoidListener {},
+                WindowId::from(0),
+                0,
+                0,
+                context_dimension,
+            ),
+            Delta::<f32>::default(),
+            [1., 0., 0., 0.],
+        );
+        let broadcast = [0., 1., 0., 1.];
+        let read_only = [0., 0., 1., 1.];
+        grid.set_state_borders(broadcast, read_only);
+
+        let borders = |grid: &ContextGrid<VoidListener>, color: [f32; 4]| -> usize {
+            grid.objects()
+                .into_iter()
+                .filter(|object| {
+                    matches!(object, Object::Quad(composed) if composed.color == color)
+                })
+                .count()
+        };
+
+        assert_eq!(grid.input_targets(), vec![0]);
+
+        // A single read-only split still shows its state.
+        grid.toggle_current_read_only();
+        assert!(grid.input_targets().is_empty());
+        assert_eq!(borders(&grid, read_only), 4);
+        grid.toggle_current_read_only();
+        assert_eq!(borders(&grid, read_only), 0);
+
+        for rich_text_id in 1..3 {
+            grid.split_right(create_mock_context(
+                VoidListener {},
+                WindowId::from(0),
+                0,
+                rich_text_id,
+                context_dimension,
+            ));
+        }
+        assert_eq!(grid.current, 2);
+
+        grid.toggle_broadcast();
+        assert_eq!(grid.input_targets(), vec![0, 1, 2]);
+        assert_eq!(borders(&grid, broadcast), 12);
+
+        // Read-only splits are left out of the broadcast.
+        grid.select_prev_split();
+        grid.toggle_current_read_only();
+        assert_eq!(grid.input_targets(), vec![0, 2]);
+        assert_eq!(borders(&grid, broadcast), 8);
+        assert_eq!(borders(&grid, read_only), 4);
+
+        // Leaving the group only writes to the current split.
+        grid.select_next_split();
+        grid.toggle_current_broadcast();
+        assert_eq!(grid.input_targets(), vec![2]);
+
+        // Toggling again broadcasts to every split, then to none.
+        grid.toggle_broadcast();
+        assert_eq!(grid.input_targets(), vec![0, 2]);
+        grid.toggle_broadcast();
+        assert_eq!(grid.input_targets(), vec![2]);
+        assert_eq!(borders(&grid, broadcast), 0);
+    }
+
     #[test]
     fn test_split_right() {
         let margin = Delta {
@@ -2173,6 +2522,80 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn test_update_context_dimension_only_changes_that_split() {
+        let context_dimension = ContextDimension::build(
+            1200.0,
+            800.0,
+            SugarDimensions {
+                scale: 1.,
+                width: 10.,
+                height: 10.,
+            },
+            1.0,
+            Delta::<f32>::default(),
+        );
+
+        let first_context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            0,
+            context_dimension,
+        );
+        let second_context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            1,
+            context_dimension,
+        );
+
+        let mut grid = ContextGrid::<VoidListener>::new(
+            first_context,
+            Delta::<f32>::default(),
+            [0., 0., 0., 0.],
+        );
+        grid.split_right(second_context);
+
+        let before = grid.contexts()[0].context().dimension;
+        let split = grid.contexts()[1].context().dimension;
+        assert_eq!(split.columns, 60);
+        assert_eq!(split.lines, 80);
+
+        // Zoom the second split to twice the cell size.
+        grid.update_context_dimension(
+            1,
+            SugarDimensions {
+                scale: 1.,
+                width: 20.,
+                height: 20.,
+            },
+        );
+
+        let zoomed = grid.contexts()[1].context().dimension;
+        assert_eq!(zoomed.width, split.width);
+        assert_eq!(zoomed.height, split.height);
+        assert_eq!(zoomed.columns, 30);
+        assert_eq!(zoomed.lines, 40);
+
+        let first = grid.contexts()[0].context().dimension;
+        assert_eq!(first.columns, before.columns);
+        assert_eq!(first.lines, before.lines);
+        assert_eq!(first.dimension, before.dimension);
+
+        // Unknown splits are ignored.
+        grid.update_context_dimension(
+            7,
+            SugarDimensions {
+                scale: 1.,
+                width: 5.,
+                height: 5.,
+            },
+        );
+        assert_eq!(grid.contexts()[1].context().dimension.columns, 30);
+    }
+
     #[test]
     fn test_resize() {
         let margin = Delta {
@@ -2,7 +2,7 @@ pub mod grid;
 pub mod renderable;
 pub mod title;
 
-use crate::ansi::CursorShape;
+use crate::ansi::{ColorScheme, CursorShape};
 use crate::context::grid::ContextDimension;
 use crate::context::grid::ContextGrid;
 use crate::context::grid::Delta;
@@ -14,8 +14,12 @@ use crate::event::RioEvent;
 use crate::ime::Ime;
 use crate::messenger::Messenger;
 use crate::performer::Machine;
+use crate::screen::paste::ShellKind;
 use renderable::Cursor;
 use renderable::RenderableContent;
+use rio_backend::config::colors::{ColorArray, Colors};
+use rio_backend::config::images::Images;
+use rio_backend::config::profile::{parse_env_vars, Profile};
 use rio_backend::config::Shell;
 use rio_backend::crosswords::{Crosswords, MIN_COLUMNS, MIN_LINES};
 use rio_backend::error::{RioError, RioErrorLevel, RioErrorType};
@@ -47,6 +51,14 @@ pub struct Context<T: EventListener> {
     pub rich_text_id: usize,
     pub dimension: ContextDimension,
     pub ime: Ime,
+    pub tab_color: Option<ColorArray>,
+    /// Tab title set by the user, replacing the title template.
+    pub pinned_title: Option<String>,
+    pub named_colors: Option<Colors>,
+    /// Input written to this split is also sent to other broadcasting splits.
+    pub broadcast: bool,
+    /// Input written to this split is dropped.
+    pub read_only: bool,
 }
 
 impl<T: rio_backend::event::EventListener> Drop for Context<T> {
@@ -99,6 +111,7 @@ impl<T: EventListener> Context<T> {
         }
 
         let terminal = self.terminal.lock();
+        self.renderable_content.history_size = terminal.history_size();
         self.renderable_content.update(
             terminal.visible_rows(),
             terminal.display_offset(),
@@ -129,10 +142,20 @@ pub struct ContextManagerConfig {
     pub working_dir: Option<String>,
     pub spawn_performer: bool,
     pub use_current_path: bool,
+    pub remote_command: Option<String>,
     pub is_native: bool,
     pub should_update_title_extra: bool,
     pub split_color: [f32; 4],
+    pub split_broadcast_color: [f32; 4],
+    pub split_read_only_color: [f32; 4],
+    pub active_split_border: Option<ColorArray>,
+    pub active_split_border_width: f32,
     pub title: rio_backend::config::title::Title,
+    pub env_vars: Vec<(String, String)>,
+    pub tab_color: Option<ColorArray>,
+    pub named_colors: Option<Colors>,
+    pub color_scheme: ColorScheme,
+    pub images: Images,
 }
 
 pub struct ContextManager<T: EventListener> {
@@ -177,6 +200,11 @@ pub fn create_dead_context<T: rio_backend::event::EventListener>(
         rich_text_id,
         dimension,
         ime: Ime::new(),
+        tab_color: None,
+        pinned_title: None,
+        named_colors: None,
+        broadcast: false,
+        read_only: false,
     }
 }
 
@@ -238,6 +266,12 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             route_id,
         );
         terminal.blinking_cursor = cursor_state.1;
+        terminal.set_color_scheme(config.color_scheme);
+        terminal.graphics.limits = config.images;
+        let waker_proxy = event_proxy.clone();
+        terminal.graphics.decoded.set_waker(move || {
+            waker_proxy.send_event(RioEvent::UpdateGraphicLibrary, window_id);
+        });
         let terminal: Arc<FairMutex<Crosswords<T>>> = Arc::new(FairMutex::new(terminal));
 
         let pty;
@@ -262,6 +296,7 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
                     &Cow::Borrowed(&config.shell.program),
                     config.shell.args.clone(),
                     &config.working_dir,
+                    &config.env_vars,
                     cols,
                     rows,
                 ) {
@@ -285,6 +320,7 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
                 &Cow::Borrowed(&config.shell.program),
                 config.shell.args.clone(),
                 &config.working_dir,
+                &config.env_vars,
                 cols,
                 rows,
             ) {
@@ -322,6 +358,11 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             renderable_content: RenderableContent::new(cursor_state.0.clone()),
             dimension,
             ime: Ime::new(),
+            tab_color: config.tab_color,
+            pinned_title: None,
+            named_colors: config.named_colors,
+            broadcast: false,
+            read_only: false,
         })
     }
 
@@ -388,15 +429,21 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             }
         }
 
+        let mut grid = ContextGrid::new(initial_context, margin, ctx_config.split_color);
+        grid.set_active_border(
+            ctx_config.active_split_border,
+            ctx_config.active_split_border_width,
+        );
+        grid.set_state_borders(
+            ctx_config.split_broadcast_color,
+            ctx_config.split_read_only_color,
+        );
+
         Ok(ContextManager {
             current_index: 0,
             current_route: 0,
             acc_current_route: 0,
-            contexts: vec![ContextGrid::new(
-                initial_context,
-                margin,
-                ctx_config.split_color,
-            )],
+            contexts: vec![grid],
             capacity: DEFAULT_CONTEXT_CAPACITY,
             event_proxy,
             window_id,
@@ -520,6 +567,12 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
         );
     }
 
+    #[inline]
+    pub fn schedule_render(&mut self, scheduled_time: u64) {
+        self.event_proxy
+            .send_event(RioEvent::PrepareRender(scheduled_time), self.window_id);
+    }
+
     #[inline]
     pub fn report_error_fonts_not_found(&mut self, fonts_not_found: Vec<SugarloafFont>) {
         if !fonts_not_found.is_empty() {
@@ -625,6 +678,16 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             .send_event(RioEvent::CreateConfigEditor, self.window_id);
     }
 
+    #[inline]
+    pub fn select_split(&mut self, tab_index: usize, split_index: usize) {
+        self.set_current(tab_index);
+        let grid = &mut self.contexts[self.current_index];
+        if split_index < grid.len() {
+            grid.current = split_index;
+        }
+        self.select_route_from_current_grid();
+    }
+
     #[inline]
     pub fn select_route_from_current_grid(&mut self) {
         self.current_route = self.current().route_id;
@@ -651,7 +714,10 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             self.titles.last_title_update = Some(Instant::now());
             let mut id = String::default();
             for (i, context) in self.contexts.iter_mut().enumerate() {
-                let content = update_title(&self.config.title.content, context.current());
+                let content = match context.pinned_title() {
+                    Some(title) => title.to_owned(),
+                    None => update_title(&self.config.title.content, context.current()),
+                };
 
                 self.event_proxy
                     .send_event(RioEvent::Title(content.to_owned()), self.window_id);
@@ -673,6 +739,95 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
         }
     }
 
+    /// Update titles now instead of waiting for the next interval.
+    #[inline]
+    pub fn refresh_titles(&mut self) {
+        self.titles.last_title_update = None;
+        self.update_titles();
+    }
+
+    #[inline]
+    pub fn current_tab_title(&self) -> String {
+        self.titles
+            .titles
+            .get(&self.current_index)
+            .map(|title| title.content.to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Pin a title on the current tab, `None` goes back to the title template.
+    #[inline]
+    pub fn set_current_tab_title(&mut self, title: Option<String>) {
+        self.contexts[self.current_index].set_pinned_title(title);
+        self.refresh_titles();
+    }
+
+    #[inline]
+    pub fn set_current_tab_color(&mut self, color: Option<ColorArray>) {
+        self.contexts[self.current_index].set_tab_color(color);
+    }
+
+    /// Set the color of the tab holding `route_id`, returns false if no
+    /// tab holds it.
+    pub fn set_tab_color_by_route(
+        &mut self,
+        route_id: usize,
+        color: Option<ColorArray>,
+    ) -> bool {
+        for grid in self.contexts.iter_mut() {
+            if grid
+                .contexts()
+                .iter()
+                .any(|item| item.context().route_id == route_id)
+            {
+                grid.set_tab_color(color);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Context of `route_id`, in any tab.
+    pub fn context_by_route(&self, route_id: usize) -> Option<&Context<T>> {
+        self.contexts
+            .iter()
+            .flat_map(|grid| grid.contexts())
+            .map(|item| item.context())
+            .find(|context| context.route_id == route_id)
+    }
+
+    /// Marks the tab owning `route_id` as having rung the bell.
+    pub fn set_bell_by_route(&mut self, route_id: usize) -> bool {
+        for grid in self.contexts.iter_mut() {
+            if grid
+                .contexts()
+                .iter()
+                .any(|item| item.context().route_id == route_id)
+            {
+                grid.has_bell = true;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    #[inline]
+    pub fn tab_bells(&self) -> Vec<bool> {
+        self.contexts.iter().map(|grid| grid.has_bell).collect()
+    }
+
+    #[inline]
+    pub fn tab_colors(&self) -> Vec<Option<ColorArray>> {
+        self.contexts.iter().map(|grid| grid.tab_color()).collect()
+    }
+
+    #[inline]
+    pub fn contexts(&self) -> &Vec<ContextGrid<T>> {
+        &self.contexts
+    }
+
     #[inline]
     pub fn contexts_mut(&mut self) -> &mut Vec<ContextGrid<T>> {
         &mut self.contexts
@@ -821,9 +976,36 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
         self.select_tab(target_index);
     }
 
-    pub fn split(&mut self, rich_text_id: usize, split_down: bool) {
+    /// Move the current tab to `target_index`, shifting the tabs in between.
+    #[inline]
+    pub fn move_current_to(&mut self, target_index: usize) {
+        let current = self.current_index;
+        if target_index >= self.contexts.len() || target_index == current {
+            return;
+        }
+
+        let grid = self.contexts.remove(current);
+        self.contexts.insert(target_index, grid);
+        self.set_current(target_index);
+        self.refresh_titles();
+    }
+
+    /// Working directory for a new tab or split, following the directory
+    /// reported through OSC 7 or otherwise the foreground process path when
+    /// `use_current_path` is enabled.
+    fn new_context_working_dir(&self) -> Option<String> {
         let mut working_dir = self.config.working_dir.clone();
         if self.config.use_current_path {
+            if let Some((host, path)) = self.current_osc_directory() {
+                // A remote directory does not exist in this machine, in that
+                // case the configured working directory is kept.
+                if is_local_host(host.as_deref()) {
+                    return Some(path);
+                }
+
+                return working_dir;
+            }
+
             #[cfg(not(target_os = "windows"))]
             {
                 let current_context = self.current();
@@ -845,37 +1027,91 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             }
         }
 
-        let mut cloned_config = self.config.clone();
-        if working_dir.is_some() {
-            cloned_config.working_dir = working_dir;
-        }
+        working_dir
+    }
 
-        self.acc_current_route += 1;
-        let current = self.current();
-        let cursor = current.cursor_from_ref();
+    /// Host and path reported through OSC 7 by the current context.
+    fn current_osc_directory(&self) -> Option<(Option<String>, String)> {
+        let terminal = self.current().terminal.lock();
+        let path = terminal.current_directory()?;
+        Some((
+            terminal.current_host().map(String::from),
+            path.to_string_lossy().to_string(),
+        ))
+    }
 
-        match ContextManager::create_context(
-            (&cursor, current.renderable_content.has_blinking_enabled),
-            self.event_proxy.clone(),
-            self.window_id,
-            self.acc_current_route,
-            rich_text_id,
-            self.current().dimension,
-            &cloned_config,
-        ) {
-            Ok(new_context) => {
-                if split_down {
-                    self.contexts[self.current_index].split_down(new_context);
-                } else {
-                    self.contexts[self.current_index].split_right(new_context);
-                }
+    /// Shell running `remote_command` for a new tab or split when the
+    /// current context reported a directory in a remote host.
+    fn new_context_remote_shell(&self) -> Option<Shell> {
+        if !self.config.use_current_path {
+            return None;
+        }
 
-                self.current_route = self.acc_current_route;
+        let template = self.config.remote_command.as_ref()?;
+        match self.current_osc_directory() {
+            Some((Some(host), path)) if !is_local_host(Some(&host)) => {
+                remote_shell(template, &host, &path)
             }
-            Err(..) => {
-                tracing::error!("not able to create a new context");
+            _ => None,
+        }
+    }
+
+    /// Config for a new tab or split that follows the current context.
+    fn new_context_config(&self) -> ContextManagerConfig {
+        let mut config = self.config.clone();
+        if let Some(working_dir) = self.new_context_working_dir() {
+            config.working_dir = Some(working_dir);
+        }
+
+        if let Some(shell) = self.new_context_remote_shell() {
+            config.shell = shell;
+            #[cfg(not(target_os = "windows"))]
+            {
+                config.use_fork = false;
             }
         }
+
+        config
+    }
+
+    fn profile_config(&self, profile: &Profile) -> ContextManagerConfig {
+        let mut config = self.config.clone();
+        if let Some(shell) = &profile.shell {
+            config.shell = shell.clone();
+        }
+
+        config.working_dir = if profile.working_dir.is_some() {
+            profile.working_dir.clone()
+        } else {
+            self.new_context_working_dir()
+        };
+
+        // Arguments, working directory and environment are only
+        // honored when the shell is spawned.
+        #[cfg(not(target_os = "windows"))]
+        {
+            config.use_fork = false;
+        }
+
+        config.env_vars.extend(parse_env_vars(&profile.env_vars));
+        config.tab_color = profile.tab_color;
+        config.named_colors = profile.colors;
+        config
+    }
+
+    pub fn split(&mut self, rich_text_id: usize, split_down: bool) {
+        let config = self.new_context_config();
+        self.split_with_config(rich_text_id, split_down, &config);
+    }
+
+    pub fn split_with_profile(
+        &mut self,
+        rich_text_id: usize,
+        split_down: bool,
+        profile: &Profile,
+    ) {
+        let profile_config = self.profile_config(profile);
+        self.split_with_config(rich_text_id, split_down, &profile_config);
     }
 
     pub fn split_from_config(
@@ -893,6 +1129,7 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
 
         let context_manager_config = ContextManagerConfig {
             use_current_path: config.navigation.use_current_path,
+            remote_command: config.navigation.remote_command.clone(),
             shell,
             working_dir,
             spawn_performer: true,
@@ -903,9 +1140,27 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             // does not make sense fetch for foreground process names
             should_update_title_extra: !config.navigation.color_automation.is_empty(),
             split_color: config.colors.split,
+            split_broadcast_color: config.colors.split_broadcast,
+            split_read_only_color: config.colors.split_read_only,
+            active_split_border: config.active_split_border,
+            active_split_border_width: config.active_split_border_width,
             title: config.title,
+            env_vars: parse_env_vars(&config.env_vars),
+            tab_color: None,
+            named_colors: None,
+            color_scheme: ColorScheme::from_background(config.colors.background.0),
+            images: config.images,
         };
 
+        self.split_with_config(rich_text_id, split_down, &context_manager_config);
+    }
+
+    fn split_with_config(
+        &mut self,
+        rich_text_id: usize,
+        split_down: bool,
+        config: &ContextManagerConfig,
+    ) {
         self.acc_current_route += 1;
         let current = self.current();
         let cursor = current.cursor_from_ref();
@@ -917,7 +1172,7 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             self.acc_current_route,
             rich_text_id,
             self.current().dimension,
-            &context_manager_config,
+            config,
         ) {
             Ok(new_context) => {
                 if split_down {
@@ -936,44 +1191,45 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
 
     #[inline]
     pub fn add_context(&mut self, redirect: bool, rich_text_id: usize) {
-        let mut working_dir = self.config.working_dir.clone();
-        if self.config.use_current_path {
-            #[cfg(not(target_os = "windows"))]
-            {
-                let current_context = self.current();
-                if let Ok(path) = teletypewriter::foreground_process_path(
-                    *current_context.main_fd,
-                    current_context.shell_pid,
-                ) {
-                    working_dir = Some(path.to_string_lossy().to_string());
-                }
-            }
-
-            #[cfg(target_os = "windows")]
-            {
-                // if let Ok(path) = teletypewriter::foreground_process_path() {
-                //     working_dir =
-                //         Some(path.to_string_lossy().to_string());
-                // }
-                working_dir = None;
-            }
-        }
-
         if self.config.is_native {
+            let working_dir = self.new_context_working_dir();
             self.event_proxy
                 .send_event(RioEvent::CreateNativeTab(working_dir), self.window_id);
             return;
         }
 
+        let config = self.new_context_config();
+        self.add_context_with_config(redirect, rich_text_id, &config);
+    }
+
+    pub fn add_context_with_profile(
+        &mut self,
+        redirect: bool,
+        rich_text_id: usize,
+        profile: &Profile,
+    ) {
+        if self.config.is_native {
+            self.event_proxy.send_event(
+                RioEvent::CreateNativeTabWithProfile(profile.name.to_owned()),
+                self.window_id,
+            );
+            return;
+        }
+
+        let profile_config = self.profile_config(profile);
+        self.add_context_with_config(redirect, rich_text_id, &profile_config);
+    }
+
+    fn add_context_with_config(
+        &mut self,
+        redirect: bool,
+        rich_text_id: usize,
+        config: &ContextManagerConfig,
+    ) {
         let size = self.contexts.len();
         if size < self.capacity {
             let last_index = self.contexts.len();
 
-            let mut cloned_config = self.config.clone();
-            if working_dir.is_some() {
-                cloned_config.working_dir = working_dir;
-            }
-
             self.acc_current_route += 1;
             let current = self.current();
             let cursor = current.cursor_from_ref();
@@ -984,15 +1240,24 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
                 self.acc_current_route,
                 rich_text_id,
                 self.current_grid().grid_dimension(),
-                &cloned_config,
+                config,
             ) {
                 Ok(new_context) => {
                     let previous_margin = self.contexts[self.current_index].margin;
-                    self.contexts.push(ContextGrid::new(
+                    let mut grid = ContextGrid::new(
                         new_context,
                         previous_margin,
                         self.config.split_color,
-                    ));
+                    );
+                    grid.set_active_border(
+                        self.config.active_split_border,
+                        self.config.active_split_border_width,
+                    );
+                    grid.set_state_borders(
+                        self.config.split_broadcast_color,
+                        self.config.split_read_only_color,
+                    );
+                    self.contexts.push(grid);
                     if redirect {
                         self.current_index = last_index;
                         self.current_route = self.current().route_id;
@@ -1004,6 +1269,126 @@ impl<T: EventListener + Clone + std::marker::Send + 'static> ContextManager<T> {
             }
         }
     }
+
+    pub fn open_profile_picker(&self) {
+        self.event_proxy
+            .send_event(RioEvent::OpenProfilePicker, self.window_id);
+    }
+
+    pub fn open_theme_picker(&self) {
+        self.event_proxy
+            .send_event(RioEvent::OpenThemePicker, self.window_id);
+    }
+
+    /// Updates the color scheme of every context, notifying the ones that
+    /// asked for it through mode 2031.
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        self.config.color_scheme = color_scheme;
+        for grid in &self.contexts {
+            for item in grid.contexts() {
+                item.context()
+                    .terminal
+                    .lock()
+                    .set_color_scheme(color_scheme);
+            }
+        }
+    }
+
+    /// Updates the image limits of every context.
+    pub fn set_image_limits(&mut self, images: Images) {
+        self.config.images = images;
+        for grid in &self.contexts {
+            for item in grid.contexts() {
+                item.context().terminal.lock().graphics.limits = images;
+            }
+        }
+    }
+
+    pub fn confirm_paste(&self, message: String) {
+        self.event_proxy
+            .send_event(RioEvent::ConfirmPaste(message), self.window_id);
+    }
+}
+
+/// Whether `host` reported through OSC 7 is this machine, shells that do
+/// not send a host are assumed to be local.
+pub fn is_local_host(host: Option<&str>) -> bool {
+    match host {
+        None | Some("localhost") => true,
+        Some(host) => match local_hostname() {
+            Some(hostname) => hostname.eq_ignore_ascii_case(host),
+            None => false,
+        },
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn local_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let result =
+        unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if result != 0 {
+        return None;
+    }
+
+    let len = buf.iter().position(|byte| *byte == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(target_os = "windows")]
+fn local_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+/// Replaces `{host}` and `{path}` in a word of the `remote-command`
+/// template, shell quoted since the remote shell reads them.
+pub fn expand_remote_command(word: &str, host: &str, path: &str) -> String {
+    let quote = |value: &str| ShellKind::Posix.quote(value);
+    word.replace("{host}", &quote(host))
+        .replace("{path}", &quote(path))
+}
+
+/// Host names and IP addresses, anything else reported by OSC 7 is
+/// refused before reaching `remote-command`.
+pub fn is_valid_remote_host(host: &str) -> bool {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return true;
+    }
+
+    host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// The host and path come from the program output, the template is split
+/// into words before they are replaced and the command runs without a
+/// local shell.
+fn remote_shell(template: &str, host: &str, path: &str) -> Option<Shell> {
+    if !is_valid_remote_host(host) || path.contains(['\0', '\n', '\r']) {
+        tracing::warn!("ignoring remote directory reported by OSC 7: {host:?} {path:?}");
+        return None;
+    }
+
+    let words = match shlex::split(template) {
+        Some(words) => words,
+        None => {
+            tracing::warn!("invalid remote-command: {template}");
+            return None;
+        }
+    };
+
+    let mut words = words
+        .iter()
+        .map(|word| expand_remote_command(word, host, path));
+    Some(Shell {
+        program: words.next()?,
+        args: words.collect(),
+    })
 }
 
 pub fn process_open_url(
@@ -1041,6 +1426,69 @@ pub mod test {
     use super::*;
     use crate::event::VoidListener;
 
+    #[test]
+    fn test_remote_command() {
+        assert!(is_local_host(None));
+        assert!(is_local_host(Some("localhost")));
+        assert!(!is_local_host(Some("remote.example.com")));
+        if let Some(hostname) = local_hostname() {
+            assert!(is_local_host(Some(&hostname)));
+        }
+
+        let template = r#"ssh {host} -t "cd {path}; exec $SHELL""#;
+        let shell = remote_shell(template, "devbox", "/srv/app").unwrap();
+        assert_eq!(shell.program, "ssh");
+        assert_eq!(shell.args, vec!["devbox", "-t", "cd /srv/app; exec $SHELL"]);
+
+        let shell = remote_shell(template, "10.0.0.2", "/srv/my app").unwrap();
+        assert_eq!(
+            shell.args,
+            vec!["10.0.0.2", "-t", "cd '/srv/my app'; exec $SHELL"]
+        );
+        assert!(remote_shell(template, "::1", "/").is_some());
+        assert!(remote_shell("ssh \"{host}", "devbox", "/").is_none());
+    }
+
+    #[test]
+    fn test_remote_command_with_hostile_values() {
+        let template = r#"ssh {host} -t "cd {path}; exec $SHELL""#;
+
+        // The path can't leave its quotes in the remote shell, nor add words.
+        let shell =
+            remote_shell(template, "devbox", "/tmp/$(touch pwned); rm -rf ~").unwrap();
+        assert_eq!(
+            shell.args,
+            vec![
+                "devbox",
+                "-t",
+                "cd '/tmp/$(touch pwned); rm -rf ~'; exec $SHELL"
+            ]
+        );
+        let shell = remote_shell(template, "devbox", "/tmp/it's").unwrap();
+        assert_eq!(shell.args[2], r#"cd '/tmp/it'\''s'; exec $SHELL"#);
+        let shell = remote_shell("ls {path}", "devbox", "/a b;c").unwrap();
+        assert_eq!(shell.args, vec!["'/a b;c'"]);
+
+        for path in ["/tmp\nrm -rf ~", "/tmp\0", "/tmp\r"] {
+            assert!(remote_shell(template, "devbox", path).is_none(), "{path:?}");
+        }
+
+        for host in [
+            "-oProxyCommand=touch pwned",
+            "devbox;id",
+            "$(id)",
+            "dev box",
+            "devbox\n",
+            "user@devbox",
+            "dev..box",
+            "",
+        ] {
+            assert!(!is_valid_remote_host(host), "{host:?}");
+            assert!(remote_shell(template, host, "/").is_none(), "{host:?}");
+        }
+        assert!(is_valid_remote_host("build-01.example.com"));
+    }
+
     #[test]
     fn test_capacity() {
         let window_id: WindowId = WindowId::from(0);
@@ -1312,4 +1760,125 @@ pub mod test {
         assert_eq!(context_manager.current_index, 4);
         assert_eq!(context_manager.current().rich_text_id, 1);
     }
+
+    #[test]
+    fn test_move_current_to() {
+        let window_id = WindowId::from(0);
+
+        let mut context_manager =
+            ContextManager::start_with_capacity(5, VoidListener {}, window_id).unwrap();
+        let should_redirect = false;
+
+        context_manager.add_context(should_redirect, 1);
+        context_manager.add_context(should_redirect, 2);
+        context_manager.add_context(should_redirect, 3);
+
+        let rich_text_ids = |context_manager: &ContextManager<VoidListener>| {
+            context_manager
+                .contexts()
+                .iter()
+                .map(|grid| grid.current().rich_text_id)
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(rich_text_ids(&context_manager), vec![0, 1, 2, 3]);
+
+        context_manager.move_current_to(2);
+        assert_eq!(context_manager.current_index, 2);
+        assert_eq!(context_manager.current().rich_text_id, 0);
+        assert_eq!(rich_text_ids(&context_manager), vec![1, 2, 0, 3]);
+
+        context_manager.set_current(3);
+        context_manager.move_current_to(0);
+        assert_eq!(context_manager.current_index, 0);
+        assert_eq!(rich_text_ids(&context_manager), vec![3, 1, 2, 0]);
+
+        // Out of bounds is ignored.
+        context_manager.move_current_to(10);
+        assert_eq!(context_manager.current_index, 0);
+        assert_eq!(rich_text_ids(&context_manager), vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_pinned_title_and_tab_color() {
+        let window_id = WindowId::from(0);
+
+        let mut context_manager =
+            ContextManager::start_with_capacity(5, VoidListener {}, window_id).unwrap();
+        context_manager.add_context(false, 1);
+
+        context_manager.set_current_tab_title(Some(String::from("logs")));
+        assert_eq!(context_manager.current_tab_title(), "logs");
+        assert_eq!(
+            context_manager.contexts()[0].pinned_title(),
+            Some(&String::from("logs"))
+        );
+        assert_eq!(context_manager.contexts()[1].pinned_title(), None);
+
+        context_manager.set_current_tab_title(None);
+        assert_eq!(context_manager.contexts()[0].pinned_title(), None);
+
+        let red = [1.0, 0.0, 0.0, 1.0];
+        context_manager.set_current_tab_color(Some(red));
+        assert_eq!(context_manager.tab_colors(), vec![Some(red), None]);
+
+        let route_id = context_manager.contexts()[1].current().route_id;
+        assert!(context_manager.set_tab_color_by_route(route_id, Some(red)));
+        assert!(!context_manager.set_tab_color_by_route(100, None));
+        assert_eq!(context_manager.tab_colors(), vec![Some(red), Some(red)]);
+    }
+
+    #[test]
+    fn test_tab_bell() {
+        let window_id = WindowId::from(0);
+
+        let mut context_manager =
+            ContextManager::start_with_capacity(5, VoidListener {}, window_id).unwrap();
+        context_manager.add_context(false, 1);
+        assert_eq!(context_manager.tab_bells(), vec![false, false]);
+
+        let route_id = context_manager.contexts()[1].current().route_id;
+        assert!(context_manager.set_bell_by_route(route_id));
+        assert!(!context_manager.set_bell_by_route(100));
+        assert_eq!(context_manager.tab_bells(), vec![false, true]);
+
+        context_manager.set_current(1);
+        context_manager.current_grid_mut().has_bell = false;
+        assert_eq!(context_manager.tab_bells(), vec![false, false]);
+    }
+
+    #[test]
+    fn test_color_scheme_reports_on_route() {
+        use rio_backend::ansi::mode::{NamedPrivateMode, PrivateMode};
+        use rio_backend::headless::HeadlessListener;
+        use rio_backend::performer::handler::Handler;
+
+        let window_id: WindowId = WindowId::from(0);
+        let listener = HeadlessListener::default();
+        let mut context_manager =
+            ContextManager::start_with_capacity(5, listener.clone(), window_id).unwrap();
+        context_manager.add_context(false, 0);
+        context_manager.add_context(false, 0);
+        assert_eq!(context_manager.current_index, 0);
+
+        // Only a background tab enabled mode 2031.
+        let background = context_manager.contexts()[2].current();
+        let route_id = background.route_id;
+        background
+            .terminal
+            .lock()
+            .set_private_mode(PrivateMode::Named(NamedPrivateMode::ColorSchemeUpdates));
+        listener.take_events();
+
+        context_manager.set_color_scheme(ColorScheme::Light);
+        let writes = listener
+            .take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                RioEvent::PtyWriteOnRoute(route_id, text) => Some((route_id, text)),
+                RioEvent::PtyWrite(text) => Some((usize::MAX, text)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(writes, vec![(route_id, String::from("\x1b[?997;2n"))]);
+    }
 }
@@ -24,6 +24,9 @@ pub struct Cursor {
 pub struct RenderableContent {
     pub inner: Vec<Row<Square>>,
     pub display_offset: i32,
+    pub history_size: usize,
+    /// When the display offset last changed, shows the scrollbar.
+    pub last_scroll: Option<Instant>,
     // TODO: Should not use default
     pub cursor: Cursor,
     pub has_blinking_enabled: bool,
@@ -42,6 +45,8 @@ impl RenderableContent {
             cursor,
             has_blinking_enabled: false,
             display_offset: 0,
+            history_size: 0,
+            last_scroll: None,
             strategy: RenderableContentStrategy::Noop,
             selection_range: None,
             hyperlink_range: None,
@@ -111,6 +116,9 @@ impl RenderableContent {
 
         self.has_pending_updates = false;
 
+        if self.display_offset != display_offset as i32 {
+            self.last_scroll = Some(Instant::now());
+        }
         self.display_offset = display_offset as i32;
         self.has_blinking_enabled = has_blinking_enabled;
 
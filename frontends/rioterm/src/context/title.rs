@@ -80,6 +80,9 @@ pub fn create_title_extra_from_context<T: rio_backend::event::EventListener>(
 // - `CANONICAL_PATH`: (e.g `.../Documents/a/rio`, `~/Documents/a`)
 // - `COLUMNS`: current columns
 // - `LINES`: current lines
+// - `DIRECTORY`: working directory reported by the shell through OSC 7
+// - `HOST`: host of the working directory reported through OSC 7
+// - `USER.NAME`: user variable `NAME` set through OSC 1337 `SetUserVar`
 
 #[inline]
 pub fn update_title<T: rio_backend::event::EventListener>(
@@ -179,6 +182,57 @@ pub fn update_title<T: rio_backend::event::EventListener>(
                         }
                     }
                 }
+                "directory" | "host" => {
+                    let value = {
+                        let terminal = context.terminal.lock();
+                        if var == "directory" {
+                            terminal
+                                .current_directory()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .unwrap_or_default()
+                        } else {
+                            terminal.current_host().unwrap_or_default().to_string()
+                        }
+                    };
+
+                    // In case it has a fallback and value is empty
+                    // or
+                    // In case is the last then we need to erase variables either way
+                    let is_only_one = variables.len() == 1;
+                    let is_last = i == variables.len() - 1;
+                    if is_only_one || is_last {
+                        new_template = new_template.replace(to_replace_str, &value);
+                        continue;
+                    }
+
+                    if !value.is_empty() {
+                        new_template = new_template.replace(to_replace_str, &value);
+                        matched = true;
+                    }
+                }
+                user_var if user_var.starts_with("user.") => {
+                    // Variable names are case sensitive.
+                    let name = &scoped_variable.trim()["user.".len()..];
+                    let value = {
+                        let terminal = context.terminal.lock();
+                        terminal.user_var(name).unwrap_or_default().to_string()
+                    };
+
+                    // In case it has a fallback and value is empty
+                    // or
+                    // In case is the last then we need to erase variables either way
+                    let is_only_one = variables.len() == 1;
+                    let is_last = i == variables.len() - 1;
+                    if is_only_one || is_last {
+                        new_template = new_template.replace(to_replace_str, &value);
+                        continue;
+                    }
+
+                    if !value.is_empty() {
+                        new_template = new_template.replace(to_replace_str, &value);
+                        matched = true;
+                    }
+                }
                 // TODO:
                 // "path_relative" => {
                 //     #[cfg(unix)]
@@ -323,5 +377,99 @@ pub mod test {
             update_title("{{ absolute_path || title }}", &context),
             String::from("Something")
         );
+
+        assert_eq!(
+            update_title("{{ host || title }}", &context),
+            String::from("Something")
+        );
+    }
+
+    #[test]
+    fn test_update_title_with_current_directory() {
+        use rio_backend::performer::handler::Handler;
+
+        let context_dimension = ContextDimension::build(
+            1200.0,
+            800.0,
+            SugarDimensions {
+                scale: 2.,
+                width: 18.,
+                height: 9.,
+            },
+            1.0,
+            Delta::<f32>::default(),
+        );
+
+        let context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            0,
+            context_dimension,
+        );
+        assert_eq!(update_title("{{ directory }}", &context), String::from(""));
+        assert_eq!(update_title("{{ host }}", &context), String::from(""));
+
+        {
+            let mut term = context.terminal.lock();
+            term.set_current_directory(
+                Some(String::from("devbox")),
+                std::path::PathBuf::from("/srv/app"),
+            );
+        };
+
+        assert_eq!(
+            update_title("{{ host }}:{{ directory }}", &context),
+            String::from("devbox:/srv/app")
+        );
+        assert_eq!(
+            update_title("{{ HOST || columns }}", &context),
+            String::from("devbox")
+        );
+    }
+
+    #[test]
+    fn test_update_title_with_user_vars() {
+        use rio_backend::performer::handler::Handler;
+
+        let context_dimension = ContextDimension::build(
+            1200.0,
+            800.0,
+            SugarDimensions {
+                scale: 2.,
+                width: 18.,
+                height: 9.,
+            },
+            1.0,
+            Delta::<f32>::default(),
+        );
+
+        let context = create_mock_context(
+            VoidListener {},
+            WindowId::from(0),
+            0,
+            0,
+            context_dimension,
+        );
+        assert_eq!(update_title("{{ user.gitBranch }}", &context), "");
+        assert_eq!(
+            update_title("{{ user.gitBranch || columns }}", &context),
+            "66"
+        );
+
+        {
+            let mut term = context.terminal.lock();
+            term.set_user_var(String::from("gitBranch"), String::from("main"));
+        };
+
+        assert_eq!(
+            update_title("{{ columns }} ({{ user.gitBranch }})", &context),
+            "66 (main)"
+        );
+        assert_eq!(
+            update_title("{{ user.gitBranch || columns }}", &context),
+            "main"
+        );
+        assert_eq!(update_title("{{ user.gitbranch }}", &context), "");
     }
 }
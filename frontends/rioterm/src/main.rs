@@ -22,7 +22,7 @@ mod screen;
 mod watcher;
 
 use clap::Parser;
-use rio_backend::config::config_dir_path;
+use rio_backend::config::{config_dir_path, ConfigError};
 use rio_backend::event::EventPayload;
 use rio_backend::{ansi, crosswords, event, performer, selection};
 use std::path::PathBuf;
@@ -124,6 +124,77 @@ fn setup_logs_by_filter_level(
     Ok(())
 }
 
+/// Report the config errors and warnings, exits with 1 when the config
+/// can't be loaded.
+fn check_config() -> ! {
+    let path = rio_backend::config::config_file_path();
+    match rio_backend::config::Config::try_load() {
+        Ok(config) => {
+            for warning in &config.warnings {
+                eprintln!("warning: {warning}\n");
+            }
+            println!("{} is valid", path.display());
+            std::process::exit(0)
+        }
+        // Rio runs with the default configuration without a file.
+        Err(ConfigError::PathNotFound) => {
+            println!("{} was not found, the defaults are used", path.display());
+            std::process::exit(0)
+        }
+        Err(
+            ConfigError::ErrLoadingConfig(message)
+            | ConfigError::ErrLoadingTheme(message),
+        ) => {
+            eprintln!("error: {message}");
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Write the theme `file` of another terminal to the themes directory.
+fn import_theme(file: &std::path::Path, name: Option<String>, force: bool) -> ! {
+    let name = match name.or_else(|| {
+        file.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+    }) {
+        Some(name) => name,
+        None => {
+            eprintln!("error: {} has no file name", file.display());
+            std::process::exit(1)
+        }
+    };
+
+    let path = config_dir_path()
+        .join("themes")
+        .join(&name)
+        .with_extension("toml");
+    if path.exists() && !force {
+        eprintln!(
+            "error: {} already exists, use --force to overwrite it",
+            path.display()
+        );
+        std::process::exit(1)
+    }
+
+    let result = rio_backend::config::theme::import(file).and_then(|theme| {
+        std::fs::create_dir_all(config_dir_path().join("themes"))
+            .and_then(|_| std::fs::write(&path, theme.to_toml()))
+            .map_err(|err| format!("unable to write {}: {err}", path.display()))
+    });
+
+    match result {
+        Ok(_) => {
+            println!("theme written to {}", path.display());
+            println!("set `theme = \"{name}\"` in the configuration to use it");
+            std::process::exit(0)
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1)
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(windows)]
     panic::attach_handler();
@@ -138,6 +209,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Load command line options.
     let args = cli::Cli::parse();
+    rio_backend::config::set_load_options(args.config_file.clone(), args.option.clone());
+
+    if args.check_config {
+        check_config();
+    }
+
+    if let Some(cli::Subcommands::Theme(cli::ThemeSubcommands::Import {
+        file,
+        name,
+        force,
+    })) = args.subcommand
+    {
+        import_theme(&file, name, force);
+    }
 
     let write_config_path = args.window_options.terminal_options.write_config.clone();
     if let Some(config_path) = write_config_path {
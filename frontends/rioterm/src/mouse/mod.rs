@@ -29,6 +29,8 @@ pub struct Mouse {
     pub accumulated_scroll: AccumulatedScroll,
     pub square_side: Side,
     pub inside_text_area: bool,
+    /// A tab was pressed in the tab bar and follows the mouse.
+    pub dragging_tab: bool,
     pub x: usize,
     pub y: usize,
 }
@@ -46,6 +48,7 @@ impl Default for Mouse {
             click_state: ClickState::None,
             square_side: Side::Left,
             inside_text_area: Default::default(),
+            dragging_tab: false,
             accumulated_scroll: AccumulatedScroll::default(),
             x: Default::default(),
             y: Default::default(),
@@ -106,6 +109,42 @@ pub fn calculate_mouse_position(
     Pos::new(row, col)
 }
 
+/// Mouse position in pixels relative to the text area of a split, clamped
+/// to it. Used by SGR-Pixels (mode 1016) reports.
+#[inline]
+pub fn calculate_mouse_pixel_position(
+    mouse: &Mouse,
+    scale_factor: f32,
+    margin_x_left: f32,
+    margin_y_top: f32,
+    text_area: (f32, f32),
+) -> (usize, usize) {
+    let max_x = (text_area.0 as usize).saturating_sub(1);
+    let max_y = (text_area.1 as usize).saturating_sub(1);
+    let x = mouse
+        .x
+        .saturating_sub((margin_x_left * scale_factor) as usize)
+        .min(max_x);
+    let y = mouse
+        .y
+        .saturating_sub((margin_y_top * scale_factor) as usize)
+        .min(max_y);
+
+    (x, y)
+}
+
+/// SGR mouse report (modes 1006 and 1016), `x` and `y` are zero based and
+/// either cells or pixels.
+#[inline]
+pub fn sgr_mouse_sequence(button: u8, x: usize, y: usize, state: ElementState) -> String {
+    let c = match state {
+        ElementState::Pressed => 'M',
+        ElementState::Released => 'm',
+    };
+
+    format!("\x1b[<{};{};{}{}", button, x + 1, y + 1, c)
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -574,4 +613,63 @@ pub mod test {
         );
         assert_eq!(pos, Pos::new(Line(0), Column(2)));
     }
+
+    #[test]
+    fn test_pixel_position_with_margins() {
+        let text_area = (94.0, 90.0);
+
+        let mouse = Mouse {
+            x: 30,
+            y: 25,
+            ..Default::default()
+        };
+        // Split offset at (10, 10) with scale 2.
+        assert_eq!(
+            calculate_mouse_pixel_position(&mouse, 2.0, 10.0, 10.0, text_area),
+            (10, 5)
+        );
+        assert_eq!(
+            calculate_mouse_pixel_position(&mouse, 1.0, 0.0, 0.0, text_area),
+            (30, 25)
+        );
+
+        // Padding before the text area is clamped to its first pixel.
+        let mouse = Mouse {
+            x: 5,
+            y: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            calculate_mouse_pixel_position(&mouse, 2.0, 10.0, 10.0, text_area),
+            (0, 0)
+        );
+
+        // And past it to the last one.
+        let mouse = Mouse {
+            x: 500,
+            y: 500,
+            ..Default::default()
+        };
+        assert_eq!(
+            calculate_mouse_pixel_position(&mouse, 1.0, 0.0, 0.0, text_area),
+            (93, 89)
+        );
+    }
+
+    #[test]
+    fn test_sgr_mouse_sequence() {
+        assert_eq!(
+            sgr_mouse_sequence(0, 0, 0, ElementState::Pressed),
+            "\x1b[<0;1;1M"
+        );
+        assert_eq!(
+            sgr_mouse_sequence(2, 9, 4, ElementState::Released),
+            "\x1b[<2;10;5m"
+        );
+        // Pixel coordinates with shift (4) on a motion event (32).
+        assert_eq!(
+            sgr_mouse_sequence(32 + 4, 311, 1042, ElementState::Pressed),
+            "\x1b[<36;312;1043M"
+        );
+    }
 }
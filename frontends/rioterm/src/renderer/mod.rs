@@ -1,4 +1,6 @@
 pub mod navigation;
+mod palette;
+pub mod scrollbar;
 mod search;
 pub mod utils;
 
@@ -8,8 +10,11 @@ use crate::context::ContextManager;
 use crate::crosswords::grid::row::Row;
 use crate::crosswords::pos::{Column, Line, Pos};
 use crate::crosswords::square::{Flags, Square};
+use crate::router::routes::themes::ThemePicker;
 use crate::screen::hint::HintMatches;
+use crate::screen::palette::CommandPalette;
 use navigation::ScreenNavigation;
+use rio_backend::config::bell::Bell;
 use rio_backend::config::colors::{
     term::{List, TermColors},
     AnsiColor, ColorArray, Colors, NamedColor,
@@ -17,15 +22,27 @@ use rio_backend::config::colors::{
 use rio_backend::config::Config;
 use rio_backend::event::EventProxy;
 use rio_backend::sugarloaf::{
-    Content, FragmentStyle, FragmentStyleDecoration, Graphic, Stretch, Style,
-    SugarCursor, Sugarloaf, UnderlineInfo, UnderlineShape, Weight,
+    Content, FragmentStyle, FragmentStyleDecoration, Graphic, Object, Rect, Stretch,
+    Style, SugarCursor, Sugarloaf, UnderlineInfo, UnderlineShape, Weight,
 };
+use scrollbar::{Scrollbar, SCROLLBAR_TIMEOUT};
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
+use std::time::Instant;
 
 use rustc_hash::FxHashMap;
 use unicode_width::UnicodeWidthChar;
 
+/// Dialogs drawn on top of the splits, borrowed for one frame.
+#[derive(Default)]
+pub struct Overlays<'a> {
+    pub palette: Option<&'a CommandPalette>,
+    pub tab_rename: Option<&'a str>,
+    pub theme_picker: Option<&'a ThemePicker>,
+    /// Name of the search preset being searched.
+    pub search_preset: Option<&'a str>,
+}
+
 pub struct Renderer {
     is_vi_mode_enabled: bool,
     pub named_colors: Colors,
@@ -47,6 +64,16 @@ pub struct Renderer {
         (usize, f32),
     >,
     active_search: Option<String>,
+    inactive_split_opacity: f32,
+    // Focused split of the last frame, unfocused splits are fully
+    // redrawn when it changes so dimming follows the focus.
+    last_active_rich_text_id: Option<usize>,
+    bell: Bell,
+    // When the visual bell started, cleared once it fades out.
+    bell_start: Option<Instant>,
+    scrollbar: bool,
+    // When the last visible scrollbar hides, to render once more.
+    scrollbar_hide_at: Option<Instant>,
 }
 
 impl Renderer {
@@ -94,6 +121,12 @@ impl Renderer {
             named_colors,
             dynamic_background,
             active_search: None,
+            inactive_split_opacity: config.inactive_split_opacity.clamp(0.0, 1.0),
+            last_active_rich_text_id: None,
+            bell: config.bell.clone(),
+            bell_start: None,
+            scrollbar: config.scroll.scrollbar,
+            scrollbar_hide_at: None,
             font_cache: FxHashMap::default(),
             font_context: font_context.clone(),
         }
@@ -104,6 +137,62 @@ impl Renderer {
         self.active_search = active_search;
     }
 
+    #[inline]
+    pub fn is_scrollbar_enabled(&self) -> bool {
+        self.scrollbar
+    }
+
+    /// Time left until a visible scrollbar hides.
+    #[inline]
+    pub fn scrollbar_hides_in(&self) -> Option<std::time::Duration> {
+        self.scrollbar_hide_at
+            .map(|hide_at| hide_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Swap the colors used to draw, the background keeps the opacity
+    /// of the window.
+    pub fn set_named_colors(&mut self, named_colors: Colors) {
+        let background = named_colors.background;
+        self.dynamic_background.0 = background.0;
+        if self.dynamic_background.1 != wgpu::Color::TRANSPARENT {
+            self.dynamic_background.1 = wgpu::Color {
+                a: self.dynamic_background.1.a,
+                ..background.1
+            };
+        }
+        self.named_colors = named_colors;
+    }
+
+    /// Starts the visual bell, returns false when it is disabled.
+    #[inline]
+    pub fn start_bell(&mut self) -> bool {
+        if self.bell.duration == 0 {
+            return false;
+        }
+
+        self.bell_start = Some(Instant::now());
+        true
+    }
+
+    #[inline]
+    pub fn is_bell_animating(&self) -> bool {
+        self.bell_start.is_some()
+    }
+
+    /// Flash intensity of the visual bell, `None` once it faded out.
+    #[inline]
+    fn bell_intensity(&mut self) -> Option<f32> {
+        let start = self.bell_start?;
+        let progress =
+            start.elapsed().as_millis() as f32 / self.bell.duration.max(1) as f32;
+        if progress >= 1.0 {
+            self.bell_start = None;
+            return None;
+        }
+
+        Some(self.bell.animation.intensity(progress))
+    }
+
     #[inline]
     fn create_style(&mut self, square: &Square) -> (FragmentStyle, char) {
         let flags = square.flags;
@@ -292,6 +381,18 @@ impl Renderer {
                 }
             }
 
+            if !is_active && self.inactive_split_opacity < 1.0 {
+                let background = self.named_colors.background.0;
+                let opacity = self.inactive_split_opacity;
+                style.color = dim_color(style.color, background, opacity);
+                style.background_color = style
+                    .background_color
+                    .map(|color| dim_color(color, background, opacity));
+                style.decoration_color = style
+                    .decoration_color
+                    .map(|color| dim_color(color, background, opacity));
+            }
+
             if square.flags.contains(Flags::GRAPHICS) {
                 // let graphics = square.graphics().map(|graphics| {
                 //     graphics
@@ -653,15 +754,32 @@ impl Renderer {
         context_manager: &mut ContextManager<EventProxy>,
         hints: &mut Option<HintMatches>,
         focused_match: &Option<RangeInclusive<Pos>>,
+        // Lines with search matches in the focused split, its scrollbar
+        // stays visible while they are set.
+        scrollbar_matches: Option<&[i32]>,
+        overlays: Overlays,
     ) {
         let content = sugarloaf.content();
         let grid = context_manager.current_grid_mut();
         let active_index = grid.current;
 
+        let active_rich_text_id = grid.current().rich_text_id;
+        let focus_changed = self.last_active_rich_text_id != Some(active_rich_text_id);
+        self.last_active_rich_text_id = Some(active_rich_text_id);
+        let redraw_for_focus =
+            focus_changed && self.inactive_split_opacity < 1.0 && grid.len() > 1;
+
+        // Index, history size, screen lines and display offset.
+        let mut scrollbars = Vec::new();
+        self.scrollbar_hide_at = None;
         for (index, grid_context) in grid.contexts_mut().iter_mut().enumerate() {
             let is_active = active_index == index;
             let context = grid_context.context_mut();
             let rich_text_id = context.rich_text_id;
+            // Profiles with a theme render with their own colors
+            let global_colors = context
+                .named_colors
+                .map(|colors| std::mem::replace(&mut self.named_colors, colors));
             let renderable_content = context.renderable_content();
             let mut is_cursor_visible = renderable_content.is_cursor_visible
                 && renderable_content.cursor.state.is_visible();
@@ -670,7 +788,30 @@ impl Renderer {
             }
 
             let display_offset = renderable_content.display_offset;
-            let strategy = if is_active && hints.is_some() {
+            if self.scrollbar {
+                let since_scroll = renderable_content
+                    .last_scroll
+                    .map(|last_scroll| last_scroll.elapsed());
+                let is_recent =
+                    since_scroll.is_some_and(|elapsed| elapsed < SCROLLBAR_TIMEOUT);
+                if is_recent || (is_active && scrollbar_matches.is_some()) {
+                    scrollbars.push((
+                        index,
+                        renderable_content.history_size,
+                        renderable_content.inner.len(),
+                        display_offset as usize,
+                    ));
+                }
+                if let Some(elapsed) = since_scroll.filter(|_| is_recent) {
+                    let hide_at = Instant::now() + (SCROLLBAR_TIMEOUT - elapsed);
+                    self.scrollbar_hide_at = Some(
+                        self.scrollbar_hide_at
+                            .map_or(hide_at, |current| current.max(hide_at)),
+                    );
+                }
+            }
+
+            let strategy = if (is_active && hints.is_some()) || redraw_for_focus {
                 &RenderableContentStrategy::Full
             } else {
                 &renderable_content.strategy
@@ -721,6 +862,10 @@ impl Renderer {
                 }
                 RenderableContentStrategy::Noop => {}
             }
+
+            if let Some(colors) = global_colors {
+                self.named_colors = colors;
+            }
         }
 
         let window_size = sugarloaf.window_size();
@@ -740,6 +885,7 @@ impl Renderer {
                 &self.named_colors,
                 (window_size.width, window_size.height, scale_factor),
                 active_search_content,
+                overlays.search_preset,
             );
 
             self.active_search = None;
@@ -749,6 +895,82 @@ impl Renderer {
             objects.push(rte);
         }
 
+        if !scrollbars.is_empty() {
+            let grid = context_manager.current_grid();
+            let matches = scrollbar_matches.unwrap_or_default();
+            for (index, position, size) in grid.rich_text_bounds() {
+                let bar = scrollbars.iter().find(|bar| bar.0 == index);
+                if let Some(&(_, history_size, screen_lines, display_offset)) = bar {
+                    if let Some(scrollbar) = Scrollbar::new(
+                        position,
+                        size,
+                        history_size,
+                        screen_lines,
+                        display_offset,
+                    ) {
+                        let match_lines =
+                            if index == grid.current { matches } else { &[] };
+                        scrollbar.draw(&mut objects, &self.named_colors, match_lines);
+                    }
+                }
+            }
+        }
+
+        if let Some(intensity) = self.bell_intensity() {
+            let mut color = self.bell.color;
+            color[3] *= intensity;
+            objects.push(Object::Rect(Rect {
+                position: [0., 0.],
+                color,
+                size: [
+                    window_size.width / scale_factor,
+                    window_size.height / scale_factor,
+                ],
+            }));
+        }
+
+        // Drawn last so it stays on top of the splits.
+        if let Some(palette) = overlays.palette {
+            palette::draw_command_palette(
+                &mut objects,
+                &self.named_colors,
+                (window_size.width, window_size.height, scale_factor),
+                context_manager.current_grid().margin.top_y,
+                palette,
+            );
+        }
+
+        if let Some(content) = overlays.tab_rename {
+            palette::draw_tab_rename(
+                &mut objects,
+                &self.named_colors,
+                (window_size.width, window_size.height, scale_factor),
+                context_manager.current_grid().margin.top_y,
+                content,
+            );
+        }
+
+        if let Some(picker) = overlays.theme_picker {
+            palette::draw_theme_picker(
+                &mut objects,
+                &self.named_colors,
+                (window_size.width, window_size.height, scale_factor),
+                context_manager.current_grid().margin.top_y,
+                picker,
+            );
+        }
+
         sugarloaf.set_objects(objects);
     }
 }
+
+/// Blend `color` towards `background`, keeping `opacity` of the original.
+#[inline]
+fn dim_color(color: [f32; 4], background: [f32; 4], opacity: f32) -> [f32; 4] {
+    [
+        background[0] + (color[0] - background[0]) * opacity,
+        background[1] + (color[1] - background[1]) * opacity,
+        background[2] + (color[2] - background[2]) * opacity,
+        color[3],
+    ]
+}
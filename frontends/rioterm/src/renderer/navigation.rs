@@ -6,6 +6,10 @@ use rio_backend::sugarloaf::{Object, Rect, Text};
 use rustc_hash::FxHashMap;
 use std::collections::HashMap;
 
+/// Horizontal space taken by each tab in TopTab and BottomTab modes.
+const TAB_WIDTH: f32 = 130.;
+const MAX_TAB_WIDTH: f32 = 140.;
+
 pub struct ScreenNavigation {
     pub navigation: Navigation,
     pub objects: Vec<Object>,
@@ -17,6 +21,8 @@ pub struct ScreenNavigation {
     scale: f32,
     pub padding_y: [f32; 2],
     color_automation: HashMap<String, HashMap<String, [f32; 4]>>,
+    tab_colors: Vec<Option<[f32; 4]>>,
+    tab_bells: Vec<bool>,
 }
 
 impl ScreenNavigation {
@@ -30,6 +36,8 @@ impl ScreenNavigation {
             objects: Vec::with_capacity(26),
             keys: String::from(""),
             color_automation,
+            tab_colors: Vec::new(),
+            tab_bells: Vec::new(),
             current: 0,
             len: 0,
             padding_y,
@@ -91,6 +99,18 @@ impl ScreenNavigation {
             has_changes = true;
         }
 
+        let tab_colors = context_manager.tab_colors();
+        if tab_colors != self.tab_colors {
+            self.tab_colors = tab_colors;
+            has_changes = true;
+        }
+
+        let tab_bells = context_manager.tab_bells();
+        if tab_bells != self.tab_bells {
+            self.tab_bells = tab_bells;
+            has_changes = true;
+        }
+
         if !has_changes {
             objects.extend(self.objects.clone());
             return;
@@ -169,13 +189,25 @@ impl ScreenNavigation {
                 }
             }
 
+            if let Some(Some(tab_color)) = self.tab_colors.get(i) {
+                color = *tab_color;
+            }
+
             let renderable = Rect {
                 position: [initial_position, 0.0],
                 color,
                 size: [30.0, size],
             };
-            initial_position -= position_modifier;
             self.objects.push(Object::Rect(renderable));
+
+            if self.has_bell(i) {
+                self.objects.push(Object::Rect(Rect {
+                    position: [initial_position, size],
+                    color: colors.yellow,
+                    size: [30.0, 4.0],
+                }));
+            }
+            initial_position -= position_modifier;
         }
     }
 
@@ -202,14 +234,7 @@ impl ScreenNavigation {
 
         self.objects.push(Object::Rect(renderable));
 
-        let iter = 0..len;
-        let mut tabs = Vec::from_iter(iter);
-
-        let max_tab_width = 140.;
-        let screen_limit = ((self.width / self.scale) / max_tab_width).floor() as usize;
-        if len > screen_limit && self.current > screen_limit {
-            tabs = Vec::from_iter(self.current - screen_limit..len);
-        }
+        let tabs = self.first_visible_tab()..len;
 
         let text_pos_mod = 11.;
         for i in tabs {
@@ -240,7 +265,11 @@ impl ScreenNavigation {
                 }
             }
 
-            let name_modifier = 90.;
+            if let Some(Some(tab_color)) = self.tab_colors.get(i) {
+                foreground_color = colors.tabs;
+                background_color = *tab_color;
+            }
+
             if name.len() >= 14 {
                 name = name[0..14].to_string();
             }
@@ -268,6 +297,8 @@ impl ScreenNavigation {
 
             let text = if is_current {
                 format!("▲ {}", name)
+            } else if self.has_bell(i) {
+                format!("{}.{} •", i + 1, name)
             } else {
                 format!("{}.{}", i + 1, name)
             };
@@ -279,7 +310,53 @@ impl ScreenNavigation {
                 foreground_color,
             )));
 
-            initial_position_x += name_modifier + 40.;
+            initial_position_x += TAB_WIDTH;
+        }
+    }
+
+    /// Background tab that rang the bell since it was last selected.
+    #[inline]
+    fn has_bell(&self, index: usize) -> bool {
+        index != self.current && self.tab_bells.get(index).copied().unwrap_or(false)
+    }
+
+    /// First tab drawn by TopTab and BottomTab, tabs before it are
+    /// scrolled out of the screen.
+    #[inline]
+    fn first_visible_tab(&self) -> usize {
+        let screen_limit = ((self.width / self.scale) / MAX_TAB_WIDTH).floor() as usize;
+        if self.len > screen_limit && self.current > screen_limit {
+            self.current - screen_limit
+        } else {
+            0
+        }
+    }
+
+    /// Index of the tab drawn at the physical position (x, y), only for
+    /// TopTab and BottomTab modes.
+    pub fn tab_at(&self, x: f32, y: f32) -> Option<usize> {
+        if self.scale == 0.0 || (self.navigation.hide_if_single && self.len <= 1) {
+            return None;
+        }
+
+        let (x, y) = (x / self.scale, y / self.scale);
+        let position_y = match self.navigation.mode {
+            NavigationMode::TopTab => 0.0,
+            NavigationMode::BottomTab => {
+                (self.height / self.scale) - PADDING_Y_BOTTOM_TABS
+            }
+            _ => return None,
+        };
+
+        if y < position_y || y > position_y + PADDING_Y_BOTTOM_TABS {
+            return None;
+        }
+
+        let index = self.first_visible_tab() + (x / TAB_WIDTH) as usize;
+        if index < self.len {
+            Some(index)
+        } else {
+            None
         }
     }
 }
@@ -0,0 +1,217 @@
+use crate::router::routes::themes::ThemePicker;
+use crate::screen::palette::{CommandPalette, PALETTE_VISIBLE_ITEMS};
+use rio_backend::config::colors::Colors;
+use rio_backend::sugarloaf::{Object, Rect, Text};
+
+const PALETTE_MAX_WIDTH: f32 = 600.;
+const PALETTE_ROW_HEIGHT: f32 = 24.;
+
+/// Horizontal position and width of the palette for a window width.
+#[inline]
+fn palette_bounds(width: f32, scale: f32) -> (f32, f32) {
+    let width = width / scale;
+    let palette_width = PALETTE_MAX_WIDTH.min(width - 40.).max(0.);
+    (((width - palette_width) / 2.).max(0.), palette_width)
+}
+
+#[inline]
+pub fn draw_command_palette(
+    objects: &mut Vec<Object>,
+    colors: &Colors,
+    dimensions: (f32, f32, f32),
+    top_y: f32,
+    palette: &CommandPalette,
+) {
+    let (width, _height, scale) = dimensions;
+    let (position_x, palette_width) = palette_bounds(width, scale);
+    let position_y = top_y + 10.;
+
+    let visible = palette.matches_len().min(PALETTE_VISIBLE_ITEMS);
+    let palette_height = PALETTE_ROW_HEIGHT * (visible as f32 + 1.) + 8.;
+
+    objects.push(Object::Rect(Rect {
+        position: [position_x, position_y],
+        color: colors.bar,
+        size: [palette_width, palette_height],
+    }));
+
+    let dimmed = [
+        colors.foreground[0],
+        colors.foreground[1],
+        colors.foreground[2],
+        colors.foreground[3] - 0.3,
+    ];
+
+    if palette.query.is_empty() {
+        objects.push(Object::Text(Text::single_line(
+            (position_x + 8., position_y + 6.),
+            String::from("> type a command, tab or split..."),
+            14.,
+            dimmed,
+        )));
+    } else {
+        objects.push(Object::Text(Text::single_line(
+            (position_x + 8., position_y + 6.),
+            format!("> {}", palette.query),
+            14.,
+            colors.foreground,
+        )));
+    }
+
+    // Keep the selected item inside the visible window.
+    let selected = palette.selected();
+    let skip = (selected + 1).saturating_sub(PALETTE_VISIBLE_ITEMS);
+
+    let mut row_y = position_y + PALETTE_ROW_HEIGHT + 4.;
+    for (index, item) in palette.matches().enumerate().skip(skip).take(visible) {
+        let is_selected = index == selected;
+        if is_selected {
+            objects.push(Object::Rect(Rect {
+                position: [position_x, row_y - 2.],
+                color: colors.tabs_active,
+                size: [palette_width, PALETTE_ROW_HEIGHT],
+            }));
+        }
+
+        let foreground = if is_selected {
+            colors.tabs_active_foreground
+        } else {
+            colors.foreground
+        };
+
+        objects.push(Object::Text(Text::single_line(
+            (position_x + 8., row_y + 2.),
+            item.title.to_owned(),
+            14.,
+            foreground,
+        )));
+
+        if !item.detail.is_empty() {
+            let detail_width = item.detail.chars().count() as f32 * 7.5;
+            objects.push(Object::Text(Text::single_line(
+                (position_x + palette_width - detail_width - 8., row_y + 3.),
+                item.detail.to_owned(),
+                12.,
+                if is_selected { foreground } else { dimmed },
+            )));
+        }
+
+        row_y += PALETTE_ROW_HEIGHT;
+    }
+}
+
+#[inline]
+pub fn draw_tab_rename(
+    objects: &mut Vec<Object>,
+    colors: &Colors,
+    dimensions: (f32, f32, f32),
+    top_y: f32,
+    content: &str,
+) {
+    let (width, _height, scale) = dimensions;
+    let (position_x, palette_width) = palette_bounds(width, scale);
+    let position_y = top_y + 10.;
+
+    objects.push(Object::Rect(Rect {
+        position: [position_x, position_y],
+        color: colors.bar,
+        size: [palette_width, PALETTE_ROW_HEIGHT + 8.],
+    }));
+
+    objects.push(Object::Text(Text::single_line(
+        (position_x + 8., position_y + 6.),
+        format!("Rename tab: {content}"),
+        14.,
+        colors.foreground,
+    )));
+}
+
+#[inline]
+pub fn draw_theme_picker(
+    objects: &mut Vec<Object>,
+    colors: &Colors,
+    dimensions: (f32, f32, f32),
+    top_y: f32,
+    picker: &ThemePicker,
+) {
+    let (width, _height, scale) = dimensions;
+    let (position_x, palette_width) = palette_bounds(width, scale);
+    let position_y = top_y + 10.;
+
+    let visible = picker.themes.len().min(PALETTE_VISIBLE_ITEMS);
+    let palette_height = PALETTE_ROW_HEIGHT * (visible as f32 + 1.) + 8.;
+
+    objects.push(Object::Rect(Rect {
+        position: [position_x, position_y],
+        color: colors.bar,
+        size: [palette_width, palette_height],
+    }));
+
+    let dimmed = [
+        colors.foreground[0],
+        colors.foreground[1],
+        colors.foreground[2],
+        colors.foreground[3] - 0.3,
+    ];
+
+    objects.push(Object::Text(Text::single_line(
+        (position_x + 8., position_y + 6.),
+        String::from("Theme"),
+        14.,
+        colors.foreground,
+    )));
+
+    let hint = "enter to save, escape to cancel";
+    let hint_width = hint.chars().count() as f32 * 7.5;
+    objects.push(Object::Text(Text::single_line(
+        (
+            position_x + palette_width - hint_width - 8.,
+            position_y + 7.,
+        ),
+        hint.to_string(),
+        12.,
+        dimmed,
+    )));
+
+    // Keep the selected theme inside the visible window.
+    let selected = picker.selected;
+    let skip = (selected + 1).saturating_sub(PALETTE_VISIBLE_ITEMS);
+
+    let mut row_y = position_y + PALETTE_ROW_HEIGHT + 4.;
+    for (index, name) in picker.themes.iter().enumerate().skip(skip).take(visible) {
+        let is_selected = index == selected;
+        if is_selected {
+            objects.push(Object::Rect(Rect {
+                position: [position_x, row_y - 2.],
+                color: colors.tabs_active,
+                size: [palette_width, PALETTE_ROW_HEIGHT],
+            }));
+        }
+
+        objects.push(Object::Text(Text::single_line(
+            (position_x + 8., row_y + 2.),
+            name.to_owned(),
+            14.,
+            if is_selected {
+                colors.tabs_active_foreground
+            } else {
+                colors.foreground
+            },
+        )));
+
+        row_y += PALETTE_ROW_HEIGHT;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_bounds() {
+        assert_eq!(palette_bounds(1600., 2.), (100., 600.));
+        assert_eq!(palette_bounds(400., 1.), (20., 360.));
+        // Narrower than the margins.
+        assert_eq!(palette_bounds(30., 1.), (15., 0.));
+    }
+}
@@ -0,0 +1,151 @@
+use rio_backend::config::colors::Colors;
+use rio_backend::sugarloaf::{Object, Rect};
+use std::time::Duration;
+
+pub const SCROLLBAR_WIDTH: f32 = 6.;
+const MIN_THUMB_HEIGHT: f32 = 16.;
+const MATCH_TICK_HEIGHT: f32 = 2.;
+
+/// How long the scrollbar stays visible after the last scroll.
+pub const SCROLLBAR_TIMEOUT: Duration = Duration::from_millis(1200);
+
+/// Scrollbar of a split, in the same coordinates as the split objects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scrollbar {
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+    pub thumb_y: f32,
+    pub thumb_height: f32,
+    history_size: usize,
+    screen_lines: usize,
+}
+
+impl Scrollbar {
+    /// Returns `None` when there is no history to scroll.
+    pub fn new(
+        position: [f32; 2],
+        size: [f32; 2],
+        history_size: usize,
+        screen_lines: usize,
+        display_offset: usize,
+    ) -> Option<Scrollbar> {
+        if history_size == 0 || screen_lines == 0 || size[1] <= 0. {
+            return None;
+        }
+
+        let height = size[1];
+        let total_lines = (history_size + screen_lines) as f32;
+        let thumb_height = (height * screen_lines as f32 / total_lines)
+            .max(MIN_THUMB_HEIGHT)
+            .min(height);
+        let scrolled = history_size.saturating_sub(display_offset) as f32;
+        let thumb_y =
+            position[1] + (height - thumb_height) * scrolled / history_size as f32;
+
+        Some(Scrollbar {
+            x: position[0] + size[0] - SCROLLBAR_WIDTH,
+            y: position[1],
+            height,
+            thumb_y,
+            thumb_height,
+            history_size,
+            screen_lines,
+        })
+    }
+
+    #[inline]
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x
+            && x <= self.x + SCROLLBAR_WIDTH
+            && y >= self.y
+            && y <= self.y + self.height
+    }
+
+    #[inline]
+    pub fn thumb_contains(&self, y: f32) -> bool {
+        y >= self.thumb_y && y <= self.thumb_y + self.thumb_height
+    }
+
+    /// Display offset that places the top of the thumb at `thumb_y`.
+    pub fn display_offset_at(&self, thumb_y: f32) -> usize {
+        let range = self.height - self.thumb_height;
+        if range <= 0. {
+            return 0;
+        }
+
+        let progress = ((thumb_y - self.y) / range).clamp(0., 1.);
+        let scrolled = (progress * self.history_size as f32).round() as usize;
+        self.history_size - scrolled.min(self.history_size)
+    }
+
+    /// Position of a grid line, from the top of the history.
+    pub fn line_y(&self, line: i32) -> f32 {
+        let total_lines = (self.history_size + self.screen_lines) as f32;
+        let index = (line + self.history_size as i32).max(0) as f32;
+        self.y + self.height * (index / total_lines).min(1.)
+    }
+
+    pub fn draw(&self, objects: &mut Vec<Object>, colors: &Colors, match_lines: &[i32]) {
+        let foreground = colors.foreground;
+        objects.push(Object::Rect(Rect {
+            position: [self.x, self.y],
+            color: [foreground[0], foreground[1], foreground[2], 0.1],
+            size: [SCROLLBAR_WIDTH, self.height],
+        }));
+        objects.push(Object::Rect(Rect {
+            position: [self.x, self.thumb_y],
+            color: [foreground[0], foreground[1], foreground[2], 0.4],
+            size: [SCROLLBAR_WIDTH, self.thumb_height],
+        }));
+
+        for line in match_lines {
+            let y = self
+                .line_y(*line)
+                .min(self.y + self.height - MATCH_TICK_HEIGHT);
+            objects.push(Object::Rect(Rect {
+                position: [self.x, y],
+                color: colors.search_match_background,
+                size: [SCROLLBAR_WIDTH, MATCH_TICK_HEIGHT],
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrollbar_geometry() {
+        // 100 lines of history and 20 on screen, at the bottom.
+        let scrollbar = Scrollbar::new([10., 20.], [400., 240.], 100, 20, 0).unwrap();
+        assert_eq!(scrollbar.x, 404.);
+        assert_eq!(scrollbar.thumb_height, 40.);
+        assert_eq!(scrollbar.thumb_y, 220.);
+        assert!(scrollbar.contains(406., 100.));
+        assert!(!scrollbar.contains(400., 100.));
+        assert!(scrollbar.thumb_contains(230.));
+
+        // Scrolled to the top of the history.
+        let top = Scrollbar::new([10., 20.], [400., 240.], 100, 20, 100).unwrap();
+        assert_eq!(top.thumb_y, 20.);
+
+        assert_eq!(scrollbar.display_offset_at(20.), 100);
+        assert_eq!(scrollbar.display_offset_at(120.), 50);
+        assert_eq!(scrollbar.display_offset_at(500.), 0);
+
+        assert_eq!(scrollbar.line_y(-100), 20.);
+        assert_eq!(scrollbar.line_y(0), 220.);
+
+        assert!(Scrollbar::new([0., 0.], [400., 240.], 0, 20, 0).is_none());
+    }
+
+    #[test]
+    fn test_scrollbar_min_thumb_height() {
+        let scrollbar = Scrollbar::new([0., 0.], [400., 200.], 10_000, 20, 0).unwrap();
+        assert_eq!(scrollbar.thumb_height, MIN_THUMB_HEIGHT);
+        assert_eq!(scrollbar.thumb_y, 200. - MIN_THUMB_HEIGHT);
+        assert_eq!(scrollbar.display_offset_at(0.), 10_000);
+    }
+}
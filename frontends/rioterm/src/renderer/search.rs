@@ -8,6 +8,7 @@ pub fn draw_search_bar(
     colors: &Colors,
     dimensions: (f32, f32, f32),
     content: &String,
+    preset: Option<&str>,
 ) {
     let (width, height, scale) = dimensions;
     let position_y = (height / scale) - PADDING_Y_BOTTOM_TABS;
@@ -33,9 +34,13 @@ pub fn draw_search_bar(
         return;
     }
 
+    let text = match preset {
+        Some(preset) => format!("Search [{preset}]: {content}"),
+        None => format!("Search: {content}"),
+    };
     objects.push(Object::Text(Text::single_line(
         (4., position_y + 10.),
-        format!("Search: {}", content),
+        text,
         14.,
         colors.foreground,
     )));
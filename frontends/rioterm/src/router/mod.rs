@@ -10,6 +10,7 @@ use rio_backend::clipboard::Clipboard;
 use rio_backend::config::Config as RioConfig;
 use rio_backend::error::{RioError, RioErrorLevel, RioErrorType};
 use rio_backend::event::{EventPayload, RioEvent, RioEventType};
+use rio_window::event::ElementState;
 use rio_window::event_loop::ActiveEventLoop;
 use rio_window::keyboard::{Key, NamedKey};
 #[cfg(not(any(target_os = "macos", windows)))]
@@ -17,10 +18,11 @@ use rio_window::platform::startup_notify::{
     self, EventLoopExtStartupNotify, WindowAttributesExtStartupNotify,
 };
 use rio_window::window::{Window, WindowId};
-use routes::{assistant, RoutePath};
+use routes::{assistant, themes::ThemePicker, RoutePath};
 use rustc_hash::FxHashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 // 𜱭𜱭 unicode is not available yet for all OS
@@ -34,6 +36,12 @@ pub struct Route<'a> {
     pub assistant: assistant::Assistant,
     pub path: RoutePath,
     pub window: RouteWindow<'a>,
+    pub selected_profile: usize,
+    pub theme_picker: Option<ThemePicker>,
+    /// Question shown by the paste confirmation dialog.
+    pub paste_warning: String,
+    /// Question shown by the download confirmation dialog.
+    pub download_warning: String,
 }
 
 impl Route<'_> {
@@ -48,6 +56,10 @@ impl Route<'_> {
             assistant,
             path,
             window,
+            selected_profile: 0,
+            theme_picker: None,
+            paste_warning: String::new(),
+            download_warning: String::new(),
         }
     }
 }
@@ -114,6 +126,7 @@ impl Route<'_> {
     pub fn clear_errors(&mut self) {
         self.assistant.clear();
         self.path = RoutePath::Terminal;
+        self.show_next_download();
     }
 
     #[inline]
@@ -121,6 +134,70 @@ impl Route<'_> {
         self.path = RoutePath::ConfirmQuit;
     }
 
+    #[inline]
+    pub fn confirm_paste(&mut self, message: String) {
+        self.paste_warning = message;
+        self.path = RoutePath::ConfirmPaste;
+    }
+
+    /// Queues the download, it is asked about once no other dialog is open.
+    #[inline]
+    pub fn confirm_download(&mut self, name: Option<String>, data: Arc<Vec<u8>>) {
+        if self.window.screen.request_download(name, data) {
+            self.show_next_download();
+        }
+    }
+
+    /// Opens the dialog of the next queued download from the terminal.
+    fn show_next_download(&mut self) {
+        if self.path != RoutePath::Terminal {
+            return;
+        }
+
+        if let Some(message) = self.window.screen.download_message() {
+            self.download_warning = message;
+            self.path = RoutePath::ConfirmDownload;
+        }
+    }
+
+    #[inline]
+    pub fn open_profile_picker(&mut self) {
+        if self.window.screen.profiles.is_empty() {
+            return;
+        }
+
+        self.selected_profile = 0;
+        self.path = RoutePath::ProfilePicker;
+    }
+
+    #[inline]
+    pub fn open_theme_picker(&mut self, config: &RioConfig) {
+        self.theme_picker = Some(ThemePicker::new(config));
+        self.path = RoutePath::ThemePicker;
+    }
+
+    /// Leave the theme picker, restoring the colors it was opened with
+    /// unless the selected theme was saved.
+    fn close_theme_picker(&mut self, save: bool) {
+        self.path = RoutePath::Terminal;
+        let picker = match self.theme_picker.take() {
+            Some(picker) => picker,
+            None => return,
+        };
+
+        if save {
+            match rio_backend::config::save_theme(picker.selected_name()) {
+                // The config watcher reloads the config with the new theme.
+                Ok(()) => return,
+                Err(err_message) => {
+                    tracing::error!("unable to save theme: {err_message}");
+                }
+            }
+        }
+
+        self.window.screen.set_colors(picker.original);
+    }
+
     #[inline]
     pub fn quit(&mut self) {
         std::process::exit(0);
@@ -128,6 +205,13 @@ impl Route<'_> {
 
     #[inline]
     pub fn has_key_wait(&mut self, key_event: &rio_window::event::KeyEvent) -> bool {
+        let has_key_wait = self.handle_dialog_key(key_event);
+        // Downloads that arrived while another dialog was open.
+        self.show_next_download();
+        has_key_wait
+    }
+
+    fn handle_dialog_key(&mut self, key_event: &rio_window::event::KeyEvent) -> bool {
         if self.path == RoutePath::Terminal {
             return false;
         }
@@ -152,6 +236,116 @@ impl Route<'_> {
             return true;
         }
 
+        if self.path == RoutePath::ConfirmPaste {
+            if key_event.state == ElementState::Released {
+                return true;
+            }
+
+            if key_event.logical_key == Key::Named(NamedKey::Escape) {
+                self.window.screen.cancel_paste();
+                self.path = RoutePath::Terminal;
+            } else if is_enter {
+                self.path = RoutePath::Terminal;
+                self.window.screen.confirm_paste();
+            }
+
+            self.request_redraw();
+            return true;
+        }
+
+        if self.path == RoutePath::ConfirmDownload {
+            if key_event.state == ElementState::Released {
+                return true;
+            }
+
+            if key_event.logical_key == Key::Named(NamedKey::Escape) {
+                self.window.screen.cancel_download();
+                self.path = RoutePath::Terminal;
+            } else if is_enter {
+                self.path = RoutePath::Terminal;
+                self.window.screen.confirm_download();
+            }
+
+            self.request_redraw();
+            return true;
+        }
+
+        if self.path == RoutePath::ProfilePicker {
+            if key_event.state == ElementState::Released {
+                return true;
+            }
+
+            let len = self.window.screen.profiles.len();
+            let mut open_profile = None;
+            match key_event.logical_key.as_ref() {
+                Key::Named(NamedKey::ArrowUp) => {
+                    self.selected_profile = (self.selected_profile + len - 1) % len;
+                }
+                Key::Named(NamedKey::ArrowDown) | Key::Named(NamedKey::Tab) => {
+                    self.selected_profile = (self.selected_profile + 1) % len;
+                }
+                Key::Named(NamedKey::Escape) => {
+                    self.path = RoutePath::Terminal;
+                }
+                Key::Named(NamedKey::Enter) => {
+                    open_profile = Some(self.selected_profile);
+                }
+                Key::Character(digit) => {
+                    if let Ok(number) = digit.parse::<usize>() {
+                        if number > 0 && number <= len {
+                            open_profile = Some(number - 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            if let Some(index) = open_profile {
+                self.path = RoutePath::Terminal;
+                let name = self.window.screen.profiles[index].name.to_owned();
+                self.window.screen.create_tab_with_profile(&name);
+            }
+
+            self.request_redraw();
+            return true;
+        }
+
+        if self.path == RoutePath::ThemePicker {
+            if key_event.state == ElementState::Released {
+                return true;
+            }
+
+            let picker = match &mut self.theme_picker {
+                Some(picker) => picker,
+                None => {
+                    self.path = RoutePath::Terminal;
+                    return true;
+                }
+            };
+
+            let mut preview = None;
+            match key_event.logical_key.as_ref() {
+                Key::Named(NamedKey::ArrowUp) => {
+                    picker.select_prev();
+                    preview = picker.selected_colors();
+                }
+                Key::Named(NamedKey::ArrowDown) | Key::Named(NamedKey::Tab) => {
+                    picker.select_next();
+                    preview = picker.selected_colors();
+                }
+                Key::Named(NamedKey::Escape) => self.close_theme_picker(false),
+                Key::Named(NamedKey::Enter) => self.close_theme_picker(true),
+                _ => {}
+            }
+
+            if let Some(colors) = preview {
+                self.window.screen.set_colors(colors);
+            }
+
+            self.request_redraw();
+            return true;
+        }
+
         if self.path == RoutePath::Welcome && is_enter {
             rio_backend::config::create_config_file(None);
             self.path = RoutePath::Terminal;
@@ -328,11 +522,7 @@ impl Router<'_> {
         );
         let id = window.winit_window.id();
 
-        let mut route = Route {
-            window,
-            path: RoutePath::Terminal,
-            assistant: Assistant::new(),
-        };
+        let mut route = Route::new(Assistant::new(), RoutePath::Terminal, window);
 
         if let Some(err) = &self.propagated_report {
             route.report_error(err);
@@ -364,11 +554,7 @@ impl Router<'_> {
         );
         self.routes.insert(
             window.winit_window.id(),
-            Route {
-                window,
-                path: RoutePath::Terminal,
-                assistant: Assistant::new(),
-            },
+            Route::new(Assistant::new(), RoutePath::Terminal, window),
         );
     }
 }
@@ -6,6 +6,7 @@ pub fn screen(
     sugarloaf: &mut Sugarloaf,
     context_dimension: &ContextDimension,
     content: &str,
+    confirm: &str,
 ) {
     let blue = [0.1764706, 0.6039216, 1.0, 1.0];
     let yellow = [0.9882353, 0.7294118, 0.15686275, 1.0];
@@ -49,7 +50,7 @@ pub fn screen(
 
     objects.push(Object::Text(Text::single_line(
         (70., mid_screen + 30.),
-        String::from("To quit press enter key"),
+        format!("To {confirm} press enter key"),
         18.,
         yellow,
     )));
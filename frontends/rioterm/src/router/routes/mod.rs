@@ -1,5 +1,7 @@
 pub mod assistant;
 pub mod dialog;
+pub mod profiles;
+pub mod themes;
 pub mod welcome;
 
 #[derive(PartialEq)]
@@ -8,4 +10,8 @@ pub enum RoutePath {
     Terminal,
     Welcome,
     ConfirmQuit,
+    ConfirmPaste,
+    ConfirmDownload,
+    ProfilePicker,
+    ThemePicker,
 }
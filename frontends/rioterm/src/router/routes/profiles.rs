@@ -0,0 +1,87 @@
+use crate::context::grid::ContextDimension;
+use rio_backend::config::profile::Profile;
+use rio_backend::sugarloaf::{Object, Rect, Sugarloaf, Text};
+
+#[inline]
+pub fn screen(
+    sugarloaf: &mut Sugarloaf,
+    context_dimension: &ContextDimension,
+    profiles: &[Profile],
+    selected: usize,
+) {
+    let blue = [0.1764706, 0.6039216, 1.0, 1.0];
+    let yellow = [0.9882353, 0.7294118, 0.15686275, 1.0];
+    let black = [0.0, 0.0, 0.0, 1.0];
+    let gray = [0.2, 0.2, 0.2, 1.0];
+
+    let layout = sugarloaf.window_size();
+
+    let mut objects = Vec::with_capacity(profiles.len() * 3 + 4);
+
+    objects.push(Object::Rect(Rect {
+        position: [0., 0.0],
+        color: black,
+        size: [layout.width, layout.height],
+    }));
+    objects.push(Object::Rect(Rect {
+        position: [0., 30.0],
+        color: blue,
+        size: [30., layout.height],
+    }));
+
+    let top_y = context_dimension.margin.top_y + 50.;
+    objects.push(Object::Text(Text::single_line(
+        (70., top_y),
+        String::from("Profiles"),
+        28.,
+        [1., 1., 1., 1.],
+    )));
+
+    objects.push(Object::Text(Text::single_line(
+        (70., top_y + 30.),
+        String::from("> up/down to select, enter to open in a new tab, escape to close"),
+        14.,
+        yellow,
+    )));
+
+    let mut position_y = top_y + 70.;
+    for (index, profile) in profiles.iter().enumerate() {
+        if index == selected {
+            objects.push(Object::Rect(Rect {
+                position: [60., position_y - 4.],
+                color: gray,
+                size: [layout.width, 26.],
+            }));
+        }
+
+        if let Some(tab_color) = profile.tab_color {
+            objects.push(Object::Rect(Rect {
+                position: [60., position_y - 4.],
+                color: tab_color,
+                size: [4., 26.],
+            }));
+        }
+
+        let content = match &profile.shell {
+            Some(shell) if !shell.program.is_empty() => {
+                format!("{}. {} ({})", index + 1, profile.name, shell.program)
+            }
+            _ => format!("{}. {}", index + 1, profile.name),
+        };
+
+        objects.push(Object::Text(Text::single_line(
+            (70., position_y),
+            content,
+            18.,
+            if index == selected {
+                yellow
+            } else {
+                [1., 1., 1., 1.]
+            },
+        )));
+
+        position_y += 30.;
+    }
+
+    sugarloaf.set_objects(objects);
+}
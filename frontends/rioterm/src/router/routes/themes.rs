@@ -0,0 +1,94 @@
+use rio_backend::config::colors::Colors;
+use rio_backend::config::theme::{self, DEFAULT_THEME};
+use rio_backend::config::{config_dir_path, Config};
+use std::path::PathBuf;
+
+/// Themes of the themes directory plus the built-in ones, the selected
+/// theme is previewed on the terminal behind the list.
+#[derive(Debug)]
+pub struct ThemePicker {
+    pub themes: Vec<String>,
+    pub selected: usize,
+    /// Colors when the picker was opened, restored on escape.
+    pub original: Colors,
+    dir: PathBuf,
+}
+
+impl ThemePicker {
+    pub fn new(config: &Config) -> Self {
+        let dir = config_dir_path().join("themes");
+        let mut themes = vec![DEFAULT_THEME.to_string()];
+        themes.extend(theme::list(&dir));
+
+        // Themes outside of the directory are listed too.
+        let current = config.theme.as_str();
+        if !current.is_empty() && !themes.iter().any(|name| name == current) {
+            themes.insert(1, current.to_string());
+        }
+
+        let selected = themes
+            .iter()
+            .position(|name| name == current)
+            .unwrap_or_default();
+
+        ThemePicker {
+            themes,
+            selected,
+            original: config.colors,
+            dir,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.themes.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = (self.selected + self.themes.len() - 1) % self.themes.len();
+    }
+
+    #[inline]
+    pub fn selected_name(&self) -> &str {
+        &self.themes[self.selected]
+    }
+
+    /// Colors of the selected theme, `None` if it fails to load.
+    pub fn selected_colors(&self) -> Option<Colors> {
+        match theme::colors(&self.dir, self.selected_name()) {
+            Ok(colors) => Some(colors),
+            Err(err_message) => {
+                tracing::warn!(
+                    "failed to load theme {}: {err_message}",
+                    self.selected_name()
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_picker_selection() {
+        let config = Config {
+            theme: String::from("/tmp/rio-missing-theme.toml"),
+            ..Config::default()
+        };
+        let mut picker = ThemePicker::new(&config);
+        assert_eq!(picker.themes[0], DEFAULT_THEME);
+        assert_eq!(picker.selected_name(), "/tmp/rio-missing-theme.toml");
+        assert_eq!(picker.selected_colors(), None);
+
+        picker.select_prev();
+        assert_eq!(picker.selected_name(), DEFAULT_THEME);
+        assert_eq!(picker.selected_colors(), Some(Colors::default()));
+
+        picker.select_prev();
+        assert_eq!(picker.selected, picker.themes.len() - 1);
+        picker.select_next();
+        assert_eq!(picker.selected, 0);
+    }
+}
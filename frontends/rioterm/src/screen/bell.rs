@@ -0,0 +1,136 @@
+// `bell.command` runs on BEL, which any program can print in a loop,
+// so it is spawned at most once per interval and never while the
+// previous one is still running.
+
+use rio_backend::config::Shell;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two runs of the bell command in a window.
+pub const BELL_COMMAND_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+pub struct BellCommand {
+    shell: Option<Shell>,
+    last_run: Option<Instant>,
+    /// Set while the last spawned command hasn't exited.
+    running: Arc<AtomicBool>,
+}
+
+impl BellCommand {
+    pub fn new(shell: Option<Shell>) -> Self {
+        BellCommand {
+            shell,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_shell(&mut self, shell: Option<Shell>) {
+        self.shell = shell;
+    }
+
+    /// Runs the command in the directory given by `cwd` unless it is
+    /// throttled, returns whether it was spawned.
+    pub fn ring(&mut self, now: Instant, cwd: impl FnOnce() -> Option<PathBuf>) -> bool {
+        let shell = match &self.shell {
+            Some(shell) => shell,
+            None => return false,
+        };
+
+        if self.running.load(Ordering::Acquire) {
+            return false;
+        }
+
+        if let Some(last_run) = self.last_run {
+            if now.saturating_duration_since(last_run) < BELL_COMMAND_INTERVAL {
+                return false;
+            }
+        }
+
+        let mut command = Command::new(&shell.program);
+        command
+            .args(&shell.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(cwd) = cwd() {
+            command.current_dir(cwd);
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        self.last_run = Some(now);
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                tracing::warn!("unable to run bell command {}: {err}", shell.program);
+                return false;
+            }
+        };
+
+        tracing::debug!("launched bell command {} {:?}", shell.program, shell.args);
+        self.running.store(true, Ordering::Release);
+        let running = self.running.clone();
+        std::thread::spawn(move || {
+            let _ = child.wait();
+            running.store(false, Ordering::Release);
+        });
+
+        true
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sleep(seconds: &str) -> Option<Shell> {
+        Some(Shell {
+            program: String::from("sleep"),
+            args: vec![String::from(seconds)],
+        })
+    }
+
+    #[test]
+    fn test_burst_of_bells_runs_one_command() {
+        let mut bell = BellCommand::new(sleep("2"));
+        let start = Instant::now();
+
+        let spawned = (0..1000)
+            .filter(|index| bell.ring(start + Duration::from_micros(*index), || None))
+            .count();
+        assert_eq!(spawned, 1);
+
+        // Past the interval the previous command is still running.
+        assert!(!bell.ring(start + BELL_COMMAND_INTERVAL * 2, || None));
+    }
+
+    #[test]
+    fn test_bell_command_runs_again_after_exit() {
+        let mut bell = BellCommand::new(sleep("0"));
+        let start = Instant::now();
+        assert!(bell.ring(start, || None));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while bell.running.load(Ordering::Acquire) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(!bell.ring(start + BELL_COMMAND_INTERVAL / 2, || None));
+        assert!(bell.ring(start + BELL_COMMAND_INTERVAL, || None));
+    }
+
+    #[test]
+    fn test_bell_without_command() {
+        let mut bell = BellCommand::new(None);
+        assert!(!bell.ring(Instant::now(), || None));
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DEFAULT_FILE_NAME: &str = "download";
+
+/// Max number of downloads waiting to be confirmed.
+const MAX_PENDING_DOWNLOADS: usize = 8;
+
+/// File sent through OSC 1337 waiting to be confirmed.
+pub struct PendingDownload {
+    pub file_name: String,
+    pub data: Arc<Vec<u8>>,
+}
+
+impl PendingDownload {
+    pub fn new(name: Option<&str>, data: Arc<Vec<u8>>) -> PendingDownload {
+        PendingDownload {
+            file_name: file_name(name),
+            data,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        format!("Download {} ({} bytes)?", self.file_name, self.data.len())
+    }
+
+    /// Writes the file to `dir` without replacing existing files.
+    pub fn save(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = unique_path(dir, &self.file_name);
+        std::fs::write(&path, self.data.as_slice())?;
+        Ok(path)
+    }
+}
+
+/// Downloads waiting to be confirmed, one dialog at a time.
+#[derive(Default)]
+pub struct DownloadQueue {
+    downloads: VecDeque<PendingDownload>,
+}
+
+impl DownloadQueue {
+    /// Queues the download, returns false when too many are waiting.
+    pub fn push(&mut self, download: PendingDownload) -> bool {
+        if self.downloads.len() >= MAX_PENDING_DOWNLOADS {
+            return false;
+        }
+
+        self.downloads.push_back(download);
+        true
+    }
+
+    /// The download the dialog asks about.
+    #[inline]
+    pub fn front(&self) -> Option<&PendingDownload> {
+        self.downloads.front()
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<PendingDownload> {
+        self.downloads.pop_front()
+    }
+}
+
+/// Directory downloads are saved to, `download-dir` or the user's
+/// download directory.
+pub fn download_dir(config: Option<&str>) -> PathBuf {
+    match config {
+        Some(dir) if !dir.is_empty() => {
+            match (dir.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(dir),
+            }
+        }
+        _ => dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(std::env::temp_dir),
+    }
+}
+
+/// Keeps only the last component of the name sent by the program, so
+/// files can't be written outside of the download directory.
+fn file_name(name: Option<&str>) -> String {
+    name.and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().trim().to_owned())
+        .filter(|name| !name.is_empty() && !name.starts_with('.'))
+        .unwrap_or_else(|| String::from(DEFAULT_FILE_NAME))
+}
+
+/// `name`, or `name (1)`, `name (2)`... when the file already exists.
+fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return path;
+    }
+
+    let file_path = Path::new(file_name);
+    let stem = file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let extension = file_path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|index| dir.join(format!("{stem} ({index}){extension}")))
+        .find(|path| !path.exists())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_file_name() {
+        assert_eq!(file_name(Some("report.pdf")), "report.pdf");
+        assert_eq!(file_name(Some("../../etc/passwd")), "passwd");
+        assert_eq!(file_name(Some("/tmp/a b.txt")), "a b.txt");
+        assert_eq!(file_name(Some(".bashrc")), DEFAULT_FILE_NAME);
+        assert_eq!(file_name(Some("..")), DEFAULT_FILE_NAME);
+        assert_eq!(file_name(None), DEFAULT_FILE_NAME);
+
+        let download = PendingDownload::new(Some("a.txt"), Arc::new(vec![0; 12]));
+        assert_eq!(download.message(), "Download a.txt (12 bytes)?");
+    }
+
+    #[test]
+    fn test_download_queue() {
+        let mut queue = DownloadQueue::default();
+        for index in 0..MAX_PENDING_DOWNLOADS {
+            let name = format!("{index}.txt");
+            assert!(queue.push(PendingDownload::new(Some(&name), Arc::default())));
+        }
+        assert!(!queue.push(PendingDownload::new(None, Arc::default())));
+
+        assert_eq!(queue.front().unwrap().file_name, "0.txt");
+        assert_eq!(queue.pop().unwrap().file_name, "0.txt");
+        assert_eq!(queue.front().unwrap().file_name, "1.txt");
+        assert!(queue.push(PendingDownload::new(None, Arc::default())));
+    }
+
+    #[test]
+    fn test_download_save() {
+        let dir = std::env::temp_dir()
+            .join(format!("rio-test-{}-download-save", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let download = PendingDownload::new(Some("notes.txt"), Arc::new(b"hi".to_vec()));
+        assert_eq!(download.save(&dir).unwrap(), dir.join("notes.txt"));
+        assert_eq!(download.save(&dir).unwrap(), dir.join("notes (1).txt"));
+        assert_eq!(std::fs::read(dir.join("notes (1).txt")).unwrap(), b"hi");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
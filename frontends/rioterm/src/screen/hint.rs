@@ -1,3 +1,4 @@
+use rio_backend::crosswords::grid::Dimensions;
 use rio_backend::crosswords::pos::{Column, Direction, Line, Pos};
 use rio_backend::crosswords::search::Match;
 use rio_backend::crosswords::search::{RegexIter, RegexSearch};
@@ -8,6 +9,9 @@ use std::ops::Deref;
 /// Maximum number of linewraps followed outside of the viewport during search highlighting.
 pub const MAX_SEARCH_LINES: usize = 100;
 
+/// Maximum number of matches marked on the scrollbar.
+const MAX_SCROLLBAR_MATCHES: usize = 1000;
+
 /// Iterate over all visible regex matches.
 pub fn visible_regex_match_iter<'a, T: rio_backend::event::EventListener>(
     term: &'a Crosswords<T>,
@@ -25,6 +29,58 @@ pub fn visible_regex_match_iter<'a, T: rio_backend::event::EventListener>(
         .take_while(move |rm| rm.start().row <= viewport_end)
 }
 
+/// Lines of the whole history with a regex match, used for scrollbar marks.
+pub fn regex_match_lines<T: rio_backend::event::EventListener>(
+    term: &Crosswords<T>,
+    regex: &mut RegexSearch,
+) -> Vec<i32> {
+    let start = Pos::new(term.topmost_line(), Column(0));
+    let end = Pos::new(term.bottommost_line(), term.last_column());
+
+    let mut lines: Vec<i32> = RegexIter::new(start, end, Direction::Right, term, regex)
+        .take(MAX_SCROLLBAR_MATCHES)
+        .map(|rm| rm.start().row.0)
+        .collect();
+    lines.dedup();
+    lines
+}
+
+/// Lines with a match for the scrollbar, only searched again when the
+/// query, the terminal or its content changes.
+#[derive(Default)]
+pub struct MatchLinesCache {
+    /// Query, route and content generation the lines were found for.
+    key: Option<(String, usize, u64)>,
+    lines: Vec<i32>,
+}
+
+impl MatchLinesCache {
+    pub fn lines<T: rio_backend::event::EventListener>(
+        &mut self,
+        query: &str,
+        term: &Crosswords<T>,
+        regex: Option<&mut RegexSearch>,
+    ) -> &[i32] {
+        let generation = term.content_generation();
+        let is_cached = matches!(
+            &self.key,
+            Some((cached, route_id, cached_generation))
+                if cached == query
+                    && *route_id == term.route_id
+                    && *cached_generation == generation
+        );
+
+        if !is_cached {
+            self.lines = regex
+                .map(|regex| regex_match_lines(term, regex))
+                .unwrap_or_default();
+            self.key = Some((query.to_owned(), term.route_id, generation));
+        }
+
+        &self.lines
+    }
+}
+
 /// Visible hint match tracking.
 #[derive(Default)]
 pub struct HintMatches<'a> {
@@ -78,3 +134,36 @@ impl Deref for HintMatches<'_> {
         self.matches.deref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rio_backend::headless::HeadlessTerminal;
+
+    #[test]
+    fn test_match_lines_cache() {
+        let mut terminal = HeadlessTerminal::new(20, 5);
+        terminal.feed(b"foo\r\nbar\r\nfoo");
+        let mut regex = RegexSearch::new("foo").unwrap();
+        let mut cache = MatchLinesCache::default();
+        assert_eq!(
+            cache.lines("foo", terminal.terminal(), Some(&mut regex)),
+            &[0, 2]
+        );
+
+        // Nothing changed, the previous lines are kept.
+        assert_eq!(cache.lines("foo", terminal.terminal(), None), &[0, 2]);
+
+        terminal.feed(b"\r\nfoo");
+        assert_eq!(
+            cache.lines("foo", terminal.terminal(), Some(&mut regex)),
+            &[0, 2, 3]
+        );
+
+        let mut regex = RegexSearch::new("bar").unwrap();
+        assert_eq!(
+            cache.lines("bar", terminal.terminal(), Some(&mut regex)),
+            &[1]
+        );
+    }
+}
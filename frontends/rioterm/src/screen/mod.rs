@@ -6,7 +6,12 @@
 // were retired from https://github.com/alacritty/alacritty/blob/c39c3c97f1a1213418c3629cc59a1d46e34070e0/alacritty/src/input.rs
 // which is licensed under Apache 2.0 license.
 
+pub mod bell;
+pub mod download;
 pub mod hint;
+pub mod palette;
+pub mod paste;
+pub mod search_history;
 pub mod touch;
 
 use crate::bindings::kitty_keyboard::build_key_sequence;
@@ -26,20 +31,30 @@ use crate::crosswords::{
     vi_mode::ViMotion,
     Mode,
 };
-use crate::mouse::{calculate_mouse_position, Mouse};
+use crate::mouse::{
+    calculate_mouse_pixel_position, calculate_mouse_position, sgr_mouse_sequence, Mouse,
+};
 use crate::renderer::{
+    scrollbar::{Scrollbar, SCROLLBAR_TIMEOUT},
     utils::{padding_bottom_from_config, padding_top_from_config},
-    Renderer,
+    Overlays, Renderer,
 };
+use crate::screen::download::PendingDownload;
 use crate::screen::hint::HintMatches;
+use crate::screen::palette::{CommandPalette, PaletteItem, PaletteTarget};
+use crate::screen::paste::{PasteWarning, ShellKind};
+use crate::screen::search_history::SearchHistory;
 use crate::selection::{Selection, SelectionType};
 use core::fmt::Debug;
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use rio_backend::ansi::ColorScheme;
 use rio_backend::clipboard::Clipboard;
 use rio_backend::clipboard::ClipboardType;
 use rio_backend::config::{
-    colors::term::List,
+    colors::{term::List, ColorBuilder, Colors, Format},
+    profile::{find_profile, parse_env_vars, Profile},
     renderer::{Backend as RendererBackend, Performance as RendererPerformance},
+    FontSizeScope, SearchPreset,
 };
 use rio_backend::crosswords::pos::{Boundary, CursorState, Direction, Line};
 use rio_backend::crosswords::search::RegexSearch;
@@ -60,6 +75,7 @@ use std::cmp::{max, min};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::rc::Rc;
+use std::time::Instant;
 use touch::TouchPurpose;
 
 /// Minimum number of pixels at the bottom/top where selection scrolling is performed.
@@ -81,10 +97,34 @@ pub struct Screen<'screen> {
     pub mouse: Mouse,
     pub touchpurpose: TouchPurpose,
     pub search_state: SearchState,
+    scrollbar_matches: hint::MatchLinesCache,
     pub renderer: Renderer,
     pub sugarloaf: Sugarloaf<'screen>,
     pub context_manager: context::ContextManager<EventProxy>,
     pub clipboard: Rc<RefCell<Clipboard>>,
+    pub profiles: Vec<Profile>,
+    pub palette: Option<CommandPalette>,
+    /// Title being typed for the current tab.
+    pub tab_rename: Option<String>,
+    bell_command: bell::BellCommand,
+    paste_config: rio_backend::config::Paste,
+    /// Paste waiting for confirmation and whether it is bracketed.
+    pending_paste: Option<(String, bool)>,
+    pending_downloads: download::DownloadQueue,
+    download_dir: std::path::PathBuf,
+    /// Files dropped since the last flush, pasted together.
+    dropped_files: Vec<std::path::PathBuf>,
+    shell_kind: ShellKind,
+    /// Split whose scrollbar is dragged and where the thumb was grabbed.
+    scrollbar_drag: Option<(usize, f32)>,
+    /// Animated images stay on their current frame.
+    animations_paused: bool,
+    search_history: SearchHistory,
+    search_presets: Vec<SearchPreset>,
+    /// Last selected preset, its name is shown in the search bar while
+    /// its regex is searched.
+    search_preset: Option<usize>,
+    font_size_scope: FontSizeScope,
 }
 
 pub struct ScreenWindowProperties {
@@ -158,6 +198,7 @@ impl Screen<'_> {
             power_preference,
             backend,
             font_features: config.fonts.features.clone(),
+            atlas_memory_limit: config.renderer.atlas_memory_limit * 1024 * 1024,
         };
 
         let mut sugarloaf: Sugarloaf = match Sugarloaf::new(
@@ -195,6 +236,7 @@ impl Screen<'_> {
 
         let context_manager_config = context::ContextManagerConfig {
             use_current_path: config.navigation.use_current_path,
+            remote_command: config.navigation.remote_command.clone(),
             shell,
             working_dir,
             spawn_performer: true,
@@ -205,7 +247,16 @@ impl Screen<'_> {
             // does not make sense fetch for foreground process names/path
             should_update_title_extra: !config.navigation.color_automation.is_empty(),
             split_color: config.colors.split,
+            split_broadcast_color: config.colors.split_broadcast,
+            split_read_only_color: config.colors.split_read_only,
+            active_split_border: config.active_split_border,
+            active_split_border_width: config.active_split_border_width,
             title: config.title.clone(),
+            env_vars: parse_env_vars(&config.env_vars),
+            tab_color: None,
+            named_colors: None,
+            color_scheme: ColorScheme::from_background(config.colors.background.0),
+            images: config.images,
         };
 
         let rich_text_id = sugarloaf.create_rich_text();
@@ -256,6 +307,7 @@ impl Screen<'_> {
 
         Ok(Screen {
             search_state: SearchState::default(),
+            scrollbar_matches: hint::MatchLinesCache::default(),
             mouse_bindings: crate::bindings::default_mouse_bindings(),
             modifiers: Modifiers::default(),
             context_manager,
@@ -265,6 +317,29 @@ impl Screen<'_> {
             renderer,
             bindings,
             clipboard,
+            profiles: config.profiles.clone(),
+            palette: None,
+            tab_rename: None,
+            bell_command: bell::BellCommand::new(config.bell.command.clone()),
+            paste_config: config.paste.clone(),
+            pending_paste: None,
+            pending_downloads: download::DownloadQueue::default(),
+            download_dir: download::download_dir(config.download_dir.as_deref()),
+            dropped_files: Vec::new(),
+            shell_kind: ShellKind::from_shell(&config.shell),
+            scrollbar_drag: None,
+            animations_paused: false,
+            search_history: SearchHistory::new(
+                config
+                    .search
+                    .persist_history
+                    .then(search_history::search_history_path)
+                    .flatten(),
+                config.search.per_directory_history,
+            ),
+            search_presets: config.search.presets.clone(),
+            search_preset: None,
+            font_size_scope: config.font_size_scope,
         })
     }
 
@@ -301,6 +376,7 @@ impl Screen<'_> {
             .select_current_based_on_mouse(&self.mouse)
         {
             self.context_manager.select_route_from_current_grid();
+            self.render();
         }
     }
 
@@ -324,6 +400,26 @@ impl Screen<'_> {
         )
     }
 
+    /// Mouse position in pixels within the current split text area.
+    pub fn mouse_pixel_position(&self) -> (usize, usize) {
+        let current_grid = self.context_manager.current_grid();
+        let (context, margin) = current_grid.current_context_with_computed_dimension();
+        let context_dimension = context.dimension;
+        let style = self.sugarloaf.style();
+        calculate_mouse_pixel_position(
+            &self.mouse,
+            style.scale_factor,
+            margin.x,
+            margin.top_y,
+            (
+                context_dimension.columns as f32 * context_dimension.dimension.width,
+                context_dimension.lines as f32
+                    * context_dimension.dimension.height
+                    * style.line_height,
+            ),
+        )
+    }
+
     #[inline]
     pub fn touch_purpose(&mut self) -> &mut TouchPurpose {
         &mut self.touchpurpose
@@ -339,6 +435,59 @@ impl Screen<'_> {
         pos_y <= DEADZONE_START_Y * scale_f64 && pos_y >= DEADZONE_END_Y * scale_f64
     }
 
+    /// Draws every split with `colors`, without changing the config.
+    ///
+    /// The color scheme reported to programs is left alone, it follows
+    /// the config once a theme is saved.
+    pub fn set_colors(&mut self, colors: Colors) {
+        self.renderer.set_named_colors(colors);
+        if !cfg!(target_os = "macos") {
+            self.sugarloaf
+                .set_background_color(Some(self.renderer.dynamic_background.1));
+        }
+
+        for context_grid in self.context_manager.contexts_mut() {
+            for item in context_grid.contexts_mut() {
+                item.context_mut().terminal.lock().mark_fully_damaged();
+            }
+        }
+    }
+
+    /// Sends new and removed graphics of every context to sugarloaf.
+    ///
+    /// Returns true when a decoded graphic arrived and the screen has to be
+    /// drawn again.
+    pub fn update_graphic_library(&mut self) -> bool {
+        let mut decoded = false;
+        for context_grid in self.context_manager.contexts_mut() {
+            for item in context_grid.contexts_mut() {
+                let mut terminal = item.context_mut().terminal.lock();
+                let queues = match terminal.graphics_take_queues() {
+                    Some(queues) => queues,
+                    None => continue,
+                };
+
+                // Graphics decoded in the background were placed in the grid
+                // earlier, their cells must be drawn again.
+                if !queues.pending.is_empty() {
+                    terminal.mark_fully_damaged();
+                    decoded = true;
+                }
+                drop(terminal);
+
+                for graphic_data in queues.pending {
+                    self.sugarloaf.graphics.insert(graphic_data);
+                }
+
+                for graphic_data in queues.remove_queue {
+                    self.sugarloaf.graphics.remove(&graphic_data);
+                }
+            }
+        }
+
+        decoded
+    }
+
     /// update_config is triggered in any configuration file update
     #[inline]
     pub fn update_config(
@@ -372,8 +521,28 @@ impl Screen<'_> {
             .update_filters(config.renderer.filters.as_slice());
         self.renderer = Renderer::new(config, font_library);
 
+        self.context_manager
+            .set_color_scheme(ColorScheme::from_background(config.colors.background.0));
+        self.context_manager.set_image_limits(config.images);
+        self.context_manager.config.active_split_border = config.active_split_border;
+        self.context_manager.config.use_current_path = config.navigation.use_current_path;
+        self.context_manager.config.remote_command =
+            config.navigation.remote_command.clone();
+        self.context_manager.config.active_split_border_width =
+            config.active_split_border_width;
+        self.context_manager.config.split_broadcast_color = config.colors.split_broadcast;
+        self.context_manager.config.split_read_only_color = config.colors.split_read_only;
+
         for context_grid in self.context_manager.contexts_mut() {
             context_grid.update_line_height(config.line_height);
+            context_grid.set_active_border(
+                config.active_split_border,
+                config.active_split_border_width,
+            );
+            context_grid.set_state_borders(
+                config.colors.split_broadcast,
+                config.colors.split_read_only,
+            );
 
             context_grid.update_margin((
                 config.padding_x,
@@ -403,6 +572,21 @@ impl Screen<'_> {
 
         self.mouse
             .set_multiplier_and_divider(config.scroll.multiplier, config.scroll.divider);
+        self.profiles = config.profiles.clone();
+        self.bell_command.set_shell(config.bell.command.clone());
+        self.paste_config = config.paste.clone();
+        self.download_dir = download::download_dir(config.download_dir.as_deref());
+        self.search_history = SearchHistory::new(
+            config
+                .search
+                .persist_history
+                .then(search_history::search_history_path)
+                .flatten(),
+            config.search.per_directory_history,
+        );
+        self.search_presets = config.search.presets.clone();
+        self.font_size_scope = config.font_size_scope;
+        self.shell_kind = ShellKind::from_shell(&config.shell);
 
         if cfg!(target_os = "macos") {
             self.sugarloaf.set_background_color(None);
@@ -418,6 +602,9 @@ impl Screen<'_> {
         self.resize_all_contexts();
     }
 
+    /// Changes the font size of the focused split, and of the other splits
+    /// of the tab or window depending on `font-size-scope`. Only the splits
+    /// with a new font size are resized.
     #[inline]
     pub fn change_font_size(&mut self, action: FontSizeAction) {
         let action: u8 = match action {
@@ -426,17 +613,45 @@ impl Screen<'_> {
             FontSizeAction::Reset => 0,
         };
 
-        self.sugarloaf.set_rich_text_font_size_based_on_action(
-            &self.context_manager.current().rich_text_id,
-            action,
-        );
+        let current_rich_text_id = self.context_manager.current().rich_text_id;
+        self.sugarloaf
+            .set_rich_text_font_size_based_on_action(&current_rich_text_id, action);
+        let font_size = self
+            .sugarloaf
+            .rich_text_layout(&current_rich_text_id)
+            .font_size;
 
-        self.context_manager
-            .current_grid_mut()
-            .update_dimensions(&self.sugarloaf);
+        let current_grid = self.context_manager.current_index();
+        for (index, context_grid) in
+            self.context_manager.contexts_mut().iter_mut().enumerate()
+        {
+            let is_in_scope = match self.font_size_scope {
+                FontSizeScope::Split => false,
+                FontSizeScope::Tab => index == current_grid,
+                FontSizeScope::Window => true,
+            };
+
+            let rich_text_ids: Vec<usize> = context_grid
+                .contexts()
+                .iter()
+                .map(|item| item.context().rich_text_id)
+                .collect();
+            for rich_text_id in rich_text_ids {
+                if rich_text_id != current_rich_text_id {
+                    if !is_in_scope {
+                        continue;
+                    }
+
+                    self.sugarloaf
+                        .set_rich_text_font_size(&rich_text_id, font_size);
+                }
+
+                let layout = self.sugarloaf.rich_text_layout(&rich_text_id);
+                context_grid.update_context_dimension(rich_text_id, layout.dimensions);
+            }
+        }
 
         self.render();
-        self.resize_all_contexts();
     }
 
     #[inline]
@@ -512,6 +727,19 @@ impl Screen<'_> {
         mode.intersects(Mode::MOUSE_MODE) && !mode.contains(Mode::VI)
     }
 
+    /// Whether mouse events are reported to the application instead of
+    /// selecting text. Shift overrides the capture unless the application
+    /// asked for it to be reported (XTSHIFTESCAPE).
+    #[inline]
+    pub fn mouse_captured(&self) -> bool {
+        let mode = self.get_mode();
+        mode.intersects(Mode::MOUSE_MODE)
+            && !mode.contains(Mode::VI)
+            && !self.ctx().current().read_only
+            && (!self.modifiers.state().shift_key()
+                || mode.contains(Mode::MOUSE_SHIFT_REPORT))
+    }
+
     #[inline]
     pub fn display_offset(&self) -> usize {
         let terminal = self.ctx().current().terminal.lock();
@@ -543,6 +771,20 @@ impl Screen<'_> {
             return;
         }
 
+        if self.palette.is_some() {
+            if key.state == ElementState::Pressed {
+                self.process_palette_key(key);
+            }
+            return;
+        }
+
+        if self.tab_rename.is_some() {
+            if key.state == ElementState::Pressed {
+                self.process_tab_rename_key(key);
+            }
+            return;
+        }
+
         let mode = self.get_mode();
         let mods = self.modifiers.state();
 
@@ -573,7 +815,7 @@ impl Screen<'_> {
                 _ => build_key_sequence(key, mods, mode),
             };
 
-            self.ctx_mut().current_mut().messenger.send_write(bytes);
+            self.context_manager.current_grid_mut().write_input(bytes);
 
             return;
         }
@@ -617,7 +859,7 @@ impl Screen<'_> {
             self.scroll_bottom_when_cursor_not_visible();
             self.clear_selection();
 
-            self.ctx_mut().current_mut().messenger.send_bytes(bytes);
+            self.context_manager.current_grid_mut().write_input(bytes);
         }
     }
 
@@ -717,359 +959,774 @@ impl Screen<'_> {
             if binding.is_triggered_by(binding_mode.to_owned(), mods, &key_match) {
                 *ignore_chars.get_or_insert(true) &= binding.action != Act::ReceiveChar;
 
-                match &binding.action {
-                    Act::Run(program) => self.exec(program.program(), program.args()),
-                    Act::Esc(s) => {
-                        let current_context = self.context_manager.current_mut();
-                        current_context.set_selection(None);
-                        let mut terminal = current_context.terminal.lock();
-                        terminal.selection.take();
-                        terminal.scroll_display(Scroll::Bottom);
-                        drop(terminal);
-                        current_context
-                            .messenger
-                            .send_bytes(s.to_owned().into_bytes());
-                    }
-                    Act::Paste => {
-                        let content =
-                            self.clipboard.borrow_mut().get(ClipboardType::Clipboard);
-                        self.paste(&content, true);
-                    }
-                    Act::ClearSelection => {
-                        self.clear_selection();
-                    }
-                    Act::PasteSelection => {
-                        let content =
-                            self.clipboard.borrow_mut().get(ClipboardType::Selection);
-                        self.paste(&content, true);
-                    }
-                    Act::Copy => {
-                        self.copy_selection(ClipboardType::Clipboard);
-                    }
-                    Act::SearchForward => {
-                        self.start_search(Direction::Right);
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::SearchBackward => {
-                        self.start_search(Direction::Left);
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchConfirm) => {
-                        self.confirm_search();
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchCancel) => {
-                        self.cancel_search();
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchClear) => {
-                        let direction = self.search_state.direction;
-                        self.cancel_search();
-                        self.start_search(direction);
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchFocusNext) => {
-                        self.advance_search_origin(self.search_state.direction);
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchFocusPrevious) => {
-                        let direction = self.search_state.direction.opposite();
-                        self.advance_search_origin(direction);
-                        self.resize_top_or_bottom_line(self.ctx().len());
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchDeleteWord) => {
-                        self.search_pop_word();
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchHistoryPrevious) => {
-                        self.search_history_previous();
-                        self.render();
-                    }
-                    Act::Search(SearchAction::SearchHistoryNext) => {
-                        self.search_history_next();
-                        self.render();
-                    }
-                    Act::ToggleViMode => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        terminal.toggle_vi_mode();
-                        let has_vi_mode_enabled = terminal.mode().contains(Mode::VI);
-                        drop(terminal);
-                        self.renderer.set_vi_mode(has_vi_mode_enabled);
-                        self.render();
-                    }
-                    Act::ViMotion(motion) => {
-                        let current_context = self.context_manager.current_mut();
-                        let mut terminal = current_context.terminal.lock();
-                        if terminal.mode().contains(Mode::VI) {
-                            terminal.vi_motion(*motion);
-                        }
-
-                        if let Some(selection) = &terminal.selection {
-                            current_context.renderable_content.selection_range =
-                                selection.to_range(&terminal);
-                        };
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::Vi(ViAction::CenterAroundViCursor) => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        let display_offset = terminal.display_offset() as i32;
-                        let target =
-                            -display_offset + terminal.grid.screen_lines() as i32 / 2 - 1;
-                        let line = terminal.vi_mode_cursor.pos.row;
-                        let scroll_lines = target - line.0;
-
-                        terminal.scroll_display(Scroll::Delta(scroll_lines));
-                        drop(terminal);
-                    }
-                    Act::Vi(ViAction::ToggleNormalSelection) => {
-                        self.toggle_selection(SelectionType::Simple, Side::Left);
-                        self.render();
-                    }
-                    Act::Vi(ViAction::ToggleLineSelection) => {
-                        self.toggle_selection(SelectionType::Lines, Side::Left);
-                        self.render();
-                    }
-                    Act::Vi(ViAction::ToggleBlockSelection) => {
-                        self.toggle_selection(SelectionType::Block, Side::Left);
-                        self.render();
-                    }
-                    Act::Vi(ViAction::ToggleSemanticSelection) => {
-                        self.toggle_selection(SelectionType::Semantic, Side::Left);
-                        self.render();
-                    }
-                    Act::SplitRight => {
-                        self.split_right();
-                    }
-                    Act::SplitDown => {
-                        self.split_down();
-                    }
-                    Act::ConfigEditor => {
-                        self.context_manager.switch_to_settings();
-                    }
-                    Act::WindowCreateNew => {
-                        self.context_manager.create_new_window();
-                    }
-                    Act::CloseCurrentSplitOrTab => {
-                        self.close_split_or_tab();
-                    }
-                    Act::TabCreateNew => {
-                        self.create_tab();
-                    }
-                    Act::TabCloseCurrent => {
-                        self.close_tab();
-                    }
-                    Act::TabCloseUnfocused => {
-                        self.clear_selection();
-                        self.cancel_search();
-                        if self.ctx().len() <= 1 {
-                            return true;
-                        }
-                        self.context_manager.close_unfocused_tabs();
-                        self.resize_top_or_bottom_line(1);
+                let action = binding.action.clone();
+                self.run_action(&action);
+            }
+        }
+
+        ignore_chars.unwrap_or(false)
+    }
+
+    /// Run a bound action, as if its key binding had been triggered.
+    pub fn run_action(&mut self, action: &Act) {
+        match action {
+            Act::Run(program) => self.exec(program.program(), program.args()),
+            Act::Esc(s) => {
+                let current_context = self.context_manager.current_mut();
+                current_context.set_selection(None);
+                let mut terminal = current_context.terminal.lock();
+                terminal.selection.take();
+                terminal.scroll_display(Scroll::Bottom);
+                drop(terminal);
+                self.context_manager
+                    .current_grid_mut()
+                    .write_input(s.to_owned().into_bytes());
+            }
+            Act::Paste => {
+                let content = self.clipboard.borrow_mut().get(ClipboardType::Clipboard);
+                self.paste(&content, true);
+            }
+            Act::ClearSelection => {
+                self.clear_selection();
+            }
+            Act::PasteSelection => {
+                let content = self.clipboard.borrow_mut().get(ClipboardType::Selection);
+                self.paste(&content, true);
+            }
+            Act::Copy => {
+                self.copy_selection(ClipboardType::Clipboard);
+            }
+            Act::SearchForward => {
+                self.start_search(Direction::Right);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::SearchBackward => {
+                self.start_search(Direction::Left);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchConfirm) => {
+                self.confirm_search();
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchCancel) => {
+                self.cancel_search();
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchClear) => {
+                let direction = self.search_state.direction;
+                self.cancel_search();
+                self.start_search(direction);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchFocusNext) => {
+                self.advance_search_origin(self.search_state.direction);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchFocusPrevious) => {
+                let direction = self.search_state.direction.opposite();
+                self.advance_search_origin(direction);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::Search(SearchAction::SearchDeleteWord) => {
+                self.search_pop_word();
+                self.render();
+            }
+            Act::Search(SearchAction::SearchHistoryPrevious) => {
+                self.search_history_previous();
+                self.render();
+            }
+            Act::Search(SearchAction::SearchHistoryNext) => {
+                self.search_history_next();
+                self.render();
+            }
+            Act::Search(SearchAction::SearchNextPreset) => {
+                self.search_next_preset();
+                self.render();
+            }
+            Act::SearchPreset(name) => {
+                let name = name.to_owned();
+                self.search_preset_by_name(&name);
+                self.resize_top_or_bottom_line(self.ctx().len());
+                self.render();
+            }
+            Act::ToggleViMode => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                terminal.toggle_vi_mode();
+                let has_vi_mode_enabled = terminal.mode().contains(Mode::VI);
+                drop(terminal);
+                self.renderer.set_vi_mode(has_vi_mode_enabled);
+                self.render();
+            }
+            Act::ViMotion(motion) => {
+                let current_context = self.context_manager.current_mut();
+                let mut terminal = current_context.terminal.lock();
+                if terminal.mode().contains(Mode::VI) {
+                    terminal.vi_motion(*motion);
+                }
+
+                if let Some(selection) = &terminal.selection {
+                    current_context.renderable_content.selection_range =
+                        selection.to_range(&terminal);
+                };
+                drop(terminal);
+                self.render();
+            }
+            Act::Vi(ViAction::CenterAroundViCursor) => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                let display_offset = terminal.display_offset() as i32;
+                let target =
+                    -display_offset + terminal.grid.screen_lines() as i32 / 2 - 1;
+                let line = terminal.vi_mode_cursor.pos.row;
+                let scroll_lines = target - line.0;
+
+                terminal.scroll_display(Scroll::Delta(scroll_lines));
+                drop(terminal);
+            }
+            Act::Vi(ViAction::ToggleNormalSelection) => {
+                self.toggle_selection(SelectionType::Simple, Side::Left);
+                self.render();
+            }
+            Act::Vi(ViAction::ToggleLineSelection) => {
+                self.toggle_selection(SelectionType::Lines, Side::Left);
+                self.render();
+            }
+            Act::Vi(ViAction::ToggleBlockSelection) => {
+                self.toggle_selection(SelectionType::Block, Side::Left);
+                self.render();
+            }
+            Act::Vi(ViAction::ToggleSemanticSelection) => {
+                self.toggle_selection(SelectionType::Semantic, Side::Left);
+                self.render();
+            }
+            Act::SplitRight => {
+                self.split_right();
+            }
+            Act::SplitDown => {
+                self.split_down();
+            }
+            Act::SplitRightWithProfile(profile) => {
+                let profile = profile.to_owned();
+                self.split_with_profile(&profile, false);
+            }
+            Act::SplitDownWithProfile(profile) => {
+                let profile = profile.to_owned();
+                self.split_with_profile(&profile, true);
+            }
+            Act::ConfigEditor => {
+                self.context_manager.switch_to_settings();
+            }
+            Act::WindowCreateNew => {
+                self.context_manager.create_new_window();
+            }
+            Act::CloseCurrentSplitOrTab => {
+                self.close_split_or_tab();
+            }
+            Act::TabCreateNew => {
+                self.create_tab();
+            }
+            Act::TabCreateNewWithProfile(profile) => {
+                let profile = profile.to_owned();
+                self.create_tab_with_profile(&profile);
+            }
+            Act::OpenProfilePicker => {
+                self.context_manager.open_profile_picker();
+            }
+            Act::OpenThemePicker => {
+                self.context_manager.open_theme_picker();
+            }
+            Act::OpenCommandPalette => {
+                self.open_command_palette();
+            }
+            Act::TabCloseCurrent => {
+                self.close_tab();
+            }
+            Act::TabCloseUnfocused => {
+                self.clear_selection();
+                self.cancel_search();
+                if self.ctx().len() <= 1 {
+                    return;
+                }
+                self.context_manager.close_unfocused_tabs();
+                self.resize_top_or_bottom_line(1);
+                self.render();
+            }
+            Act::Quit => {
+                self.context_manager.quit();
+            }
+            Act::IncreaseFontSize => {
+                self.change_font_size(FontSizeAction::Increase);
+            }
+            Act::DecreaseFontSize => {
+                self.change_font_size(FontSizeAction::Decrease);
+            }
+            Act::ResetFontSize => {
+                self.change_font_size(FontSizeAction::Reset);
+            }
+            Act::ScrollPageUp => {
+                // Move vi mode cursor.
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                let scroll_lines = terminal.grid.screen_lines() as i32;
+                terminal.vi_mode_cursor =
+                    terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+                terminal.scroll_display(Scroll::PageUp);
+                drop(terminal);
+                self.render();
+            }
+            Act::ScrollPageDown => {
+                // Move vi mode cursor.
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                let scroll_lines = -(terminal.grid.screen_lines() as i32);
+
+                terminal.vi_mode_cursor =
+                    terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+
+                terminal.scroll_display(Scroll::PageDown);
+                drop(terminal);
+                self.render();
+            }
+            Act::ScrollHalfPageUp => {
+                // Move vi mode cursor.
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                let scroll_lines = terminal.grid.screen_lines() as i32 / 2;
+
+                terminal.vi_mode_cursor =
+                    terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+
+                terminal.scroll_display(Scroll::Delta(scroll_lines));
+                drop(terminal);
+                self.render();
+            }
+            Act::ScrollHalfPageDown => {
+                // Move vi mode cursor.
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                let scroll_lines = -(terminal.grid.screen_lines() as i32 / 2);
+
+                terminal.vi_mode_cursor =
+                    terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+
+                terminal.scroll_display(Scroll::Delta(scroll_lines));
+                drop(terminal);
+                self.render();
+            }
+            Act::ScrollToTop => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                terminal.scroll_display(Scroll::Top);
+
+                let topmost_line = terminal.grid.topmost_line();
+                terminal.vi_mode_cursor.pos.row = topmost_line;
+                terminal.vi_motion(ViMotion::FirstOccupied);
+                drop(terminal);
+                self.render();
+            }
+            Act::ScrollToBottom => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                terminal.scroll_display(Scroll::Bottom);
+
+                // Move vi mode cursor.
+                terminal.vi_mode_cursor.pos.row = terminal.grid.bottommost_line();
+
+                // Move to beginning twice, to always jump across linewraps.
+                terminal.vi_motion(ViMotion::FirstOccupied);
+                terminal.vi_motion(ViMotion::FirstOccupied);
+                drop(terminal);
+                self.render();
+            }
+            Act::Scroll(delta) => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                terminal.scroll_display(Scroll::Delta(*delta));
+                drop(terminal);
+                self.render();
+            }
+            Act::ClearHistory => {
+                let mut terminal = self.context_manager.current_mut().terminal.lock();
+                terminal.clear_saved_history();
+                drop(terminal);
+                self.render();
+            }
+            Act::ToggleFullscreen => self.context_manager.toggle_full_screen(),
+            Act::Minimize => {
+                self.context_manager.minimize();
+            }
+            Act::Hide => {
+                self.context_manager.hide();
+            }
+            #[cfg(target_os = "macos")]
+            Act::HideOtherApplications => {
+                self.context_manager.hide_other_apps();
+            }
+            Act::SelectNextSplit => {
+                self.cancel_search();
+                self.context_manager.select_next_split();
+                self.render();
+            }
+            Act::SelectPrevSplit => {
+                self.cancel_search();
+                self.context_manager.select_prev_split();
+                self.render();
+            }
+            Act::ToggleBroadcastInput => {
+                self.context_manager.current_grid_mut().toggle_broadcast();
+                self.render();
+            }
+            Act::ToggleBroadcastInputSplit => {
+                self.context_manager
+                    .current_grid_mut()
+                    .toggle_current_broadcast();
+                self.render();
+            }
+            Act::ToggleReadOnly => {
+                self.context_manager
+                    .current_grid_mut()
+                    .toggle_current_read_only();
+                self.render();
+            }
+            Act::ToggleAnimations => {
+                self.animations_paused = !self.animations_paused;
+                self.render();
+            }
+            Act::SelectTab(tab_index) => {
+                self.context_manager.select_tab(*tab_index);
+                self.cancel_search();
+                self.render();
+            }
+            Act::SelectLastTab => {
+                self.cancel_search();
+                self.context_manager.select_last_tab();
+                self.render();
+            }
+            Act::SelectNextTab => {
+                self.cancel_search();
+                self.clear_selection();
+                self.context_manager.switch_to_next();
+                self.render();
+            }
+            Act::MoveCurrentTabToPrev => {
+                self.cancel_search();
+                self.clear_selection();
+                self.context_manager.move_current_to_prev();
+                self.render();
+            }
+            Act::MoveCurrentTabToNext => {
+                self.cancel_search();
+                self.clear_selection();
+                self.context_manager.move_current_to_next();
+                self.render();
+            }
+            Act::RenameTab => {
+                self.cancel_search();
+                self.tab_rename = Some(self.context_manager.current_tab_title());
+                self.render();
+            }
+            Act::SetTabColor(color) => {
+                match ColorBuilder::from_hex(color.to_owned(), Format::SRGB0_1) {
+                    Ok(color) => {
+                        self.context_manager
+                            .set_current_tab_color(Some(color.to_arr()));
                         self.render();
                     }
-                    Act::Quit => {
-                        self.context_manager.quit();
-                    }
-                    Act::IncreaseFontSize => {
-                        self.change_font_size(FontSizeAction::Increase);
-                    }
-                    Act::DecreaseFontSize => {
-                        self.change_font_size(FontSizeAction::Decrease);
-                    }
-                    Act::ResetFontSize => {
-                        self.change_font_size(FontSizeAction::Reset);
-                    }
-                    Act::ScrollPageUp => {
-                        // Move vi mode cursor.
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        let scroll_lines = terminal.grid.screen_lines() as i32;
-                        terminal.vi_mode_cursor =
-                            terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
-                        terminal.scroll_display(Scroll::PageUp);
-                        drop(terminal);
-                        self.render();
+                    Err(err) => tracing::warn!("invalid tab color {color}: {err}"),
+                }
+            }
+            Act::ResetTabColor => {
+                self.context_manager.set_current_tab_color(None);
+                self.render();
+            }
+            Act::SelectPrevTab => {
+                self.cancel_search();
+                self.clear_selection();
+                self.context_manager.switch_to_prev();
+                self.render();
+            }
+            Act::ReceiveChar | Act::None => (),
+            _ => (),
+        }
+    }
+
+    pub fn open_command_palette(&mut self) {
+        self.cancel_search();
+
+        let profiles: Vec<String> = self
+            .profiles
+            .iter()
+            .map(|profile| profile.name.to_owned())
+            .collect();
+        let mut items = CommandPalette::action_items(&self.bindings, &profiles);
+
+        let title_template = self.context_manager.config.title.content.to_owned();
+        let titles = &self.context_manager.titles.titles;
+        let mut tabs = Vec::new();
+        for (tab_index, grid) in self.context_manager.contexts().iter().enumerate() {
+            let tab_title = titles
+                .get(&tab_index)
+                .map(|title| title.content.to_owned())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| String::from("tab"));
+            tabs.push(PaletteItem {
+                title: format!("Tab {}: {}", tab_index + 1, tab_title),
+                detail: String::from("tab"),
+                target: PaletteTarget::Tab(tab_index),
+            });
+
+            if grid.len() <= 1 {
+                continue;
+            }
+
+            for (split_index, item) in grid.contexts().iter().enumerate() {
+                let split_title =
+                    context::title::update_title(&title_template, item.context());
+                tabs.push(PaletteItem {
+                    title: format!(
+                        "Tab {} Split {}: {}",
+                        tab_index + 1,
+                        split_index + 1,
+                        split_title
+                    ),
+                    detail: String::from("split"),
+                    target: PaletteTarget::Split(tab_index, split_index),
+                });
+            }
+        }
+        items.extend(tabs);
+
+        self.palette = Some(CommandPalette::new(items));
+        self.render();
+    }
+
+    fn process_palette_key(&mut self, key: &rio_window::event::KeyEvent) {
+        let palette = match &mut self.palette {
+            Some(palette) => palette,
+            None => return,
+        };
+
+        match key.logical_key.as_ref() {
+            Key::Named(NamedKey::Escape) => {
+                self.palette = None;
+            }
+            Key::Named(NamedKey::Enter) => {
+                let target = palette.selected_target();
+                self.palette = None;
+                if let Some(target) = target {
+                    self.run_palette_target(target);
+                }
+            }
+            Key::Named(NamedKey::ArrowUp) => palette.select_prev(),
+            Key::Named(NamedKey::ArrowDown) => palette.select_next(),
+            Key::Named(NamedKey::Tab) => {
+                if self.modifiers.state().shift_key() {
+                    palette.select_prev();
+                } else {
+                    palette.select_next();
+                }
+            }
+            Key::Named(NamedKey::Backspace) => palette.backspace(),
+            _ => {
+                if let Some(text) = key.text.as_ref() {
+                    for c in text.chars() {
+                        palette.input(c);
                     }
-                    Act::ScrollPageDown => {
-                        // Move vi mode cursor.
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        let scroll_lines = -(terminal.grid.screen_lines() as i32);
+                }
+            }
+        }
+
+        self.render();
+    }
+
+    fn process_tab_rename_key(&mut self, key: &rio_window::event::KeyEvent) {
+        let title = match &mut self.tab_rename {
+            Some(title) => title,
+            None => return,
+        };
+
+        match key.logical_key.as_ref() {
+            Key::Named(NamedKey::Escape) => {
+                self.tab_rename = None;
+            }
+            Key::Named(NamedKey::Enter) => {
+                // An empty title unpins it, going back to the title template.
+                let title = self
+                    .tab_rename
+                    .take()
+                    .map(|title| title.trim().to_owned())
+                    .filter(|title| !title.is_empty());
+                self.context_manager.set_current_tab_title(title);
+            }
+            Key::Named(NamedKey::Backspace) => {
+                title.pop();
+            }
+            _ => {
+                if let Some(text) = key.text.as_ref() {
+                    title.extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+
+        self.render();
+    }
+
+    /// Select the tab under the mouse in TopTab and BottomTab modes and
+    /// start dragging it. Returns false if the mouse is not over a tab.
+    pub fn select_tab_based_on_mouse(&mut self) -> bool {
+        let index = match self
+            .renderer
+            .navigation
+            .tab_at(self.mouse.x as f32, self.mouse.y as f32)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        self.mouse.dragging_tab = true;
+        if index != self.context_manager.current_index() {
+            self.cancel_search();
+            self.clear_selection();
+            self.context_manager.select_tab(index);
+            self.render();
+        }
+        true
+    }
 
-                        terminal.vi_mode_cursor =
-                            terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+    /// Move the dragged tab to the slot under the mouse. Returns false
+    /// if no tab is being dragged.
+    pub fn drag_tab_based_on_mouse(&mut self) -> bool {
+        if !self.mouse.dragging_tab {
+            return false;
+        }
 
-                        terminal.scroll_display(Scroll::PageDown);
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ScrollHalfPageUp => {
-                        // Move vi mode cursor.
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        let scroll_lines = terminal.grid.screen_lines() as i32 / 2;
+        if let Some(index) = self
+            .renderer
+            .navigation
+            .tab_at(self.mouse.x as f32, self.mouse.y as f32)
+        {
+            if index != self.context_manager.current_index() {
+                self.context_manager.move_current_to(index);
+                self.render();
+            }
+        }
+        true
+    }
+
+    fn scrollbar(&self, index: usize) -> Option<Scrollbar> {
+        let grid = self.context_manager.current_grid();
+        let (_, position, size) = grid
+            .rich_text_bounds()
+            .into_iter()
+            .find(|bounds| bounds.0 == index)?;
+        let terminal = grid.contexts().get(index)?.context().terminal.lock();
+        Scrollbar::new(
+            position,
+            size,
+            terminal.history_size(),
+            terminal.screen_lines(),
+            terminal.display_offset(),
+        )
+    }
 
-                        terminal.vi_mode_cursor =
-                            terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+    /// Split index and scrollbar under the mouse.
+    fn scrollbar_under_mouse(&self) -> Option<(usize, Scrollbar)> {
+        if !self.renderer.is_scrollbar_enabled() {
+            return None;
+        }
 
-                        terminal.scroll_display(Scroll::Delta(scroll_lines));
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ScrollHalfPageDown => {
-                        // Move vi mode cursor.
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        let scroll_lines = -(terminal.grid.screen_lines() as i32 / 2);
+        let scale = self.sugarloaf.scale_factor();
+        let (x, y) = (self.mouse.x as f32 / scale, self.mouse.y as f32 / scale);
+        (0..self.context_manager.current_grid_len()).find_map(|index| {
+            self.scrollbar(index)
+                .filter(|scrollbar| scrollbar.contains(x, y))
+                .map(|scrollbar| (index, scrollbar))
+        })
+    }
 
-                        terminal.vi_mode_cursor =
-                            terminal.vi_mode_cursor.scroll(&terminal, scroll_lines);
+    fn is_scrollbar_visible(&self, index: usize) -> bool {
+        let grid = self.context_manager.current_grid();
+        let renderable_content = match grid.contexts().get(index) {
+            Some(item) => &item.context().renderable_content,
+            None => return false,
+        };
 
-                        terminal.scroll_display(Scroll::Delta(scroll_lines));
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ScrollToTop => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        terminal.scroll_display(Scroll::Top);
-
-                        let topmost_line = terminal.grid.topmost_line();
-                        terminal.vi_mode_cursor.pos.row = topmost_line;
-                        terminal.vi_motion(ViMotion::FirstOccupied);
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ScrollToBottom => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        terminal.scroll_display(Scroll::Bottom);
-
-                        // Move vi mode cursor.
-                        terminal.vi_mode_cursor.pos.row = terminal.grid.bottommost_line();
-
-                        // Move to beginning twice, to always jump across linewraps.
-                        terminal.vi_motion(ViMotion::FirstOccupied);
-                        terminal.vi_motion(ViMotion::FirstOccupied);
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::Scroll(delta) => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        terminal.scroll_display(Scroll::Delta(*delta));
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ClearHistory => {
-                        let mut terminal =
-                            self.context_manager.current_mut().terminal.lock();
-                        terminal.clear_saved_history();
-                        drop(terminal);
-                        self.render();
-                    }
-                    Act::ToggleFullscreen => self.context_manager.toggle_full_screen(),
-                    Act::Minimize => {
-                        self.context_manager.minimize();
-                    }
-                    Act::Hide => {
-                        self.context_manager.hide();
-                    }
-                    #[cfg(target_os = "macos")]
-                    Act::HideOtherApplications => {
-                        self.context_manager.hide_other_apps();
-                    }
-                    Act::SelectNextSplit => {
-                        self.cancel_search();
-                        self.context_manager.select_next_split();
-                        self.render();
-                    }
-                    Act::SelectPrevSplit => {
-                        self.cancel_search();
-                        self.context_manager.select_prev_split();
-                        self.render();
-                    }
-                    Act::SelectTab(tab_index) => {
-                        self.context_manager.select_tab(*tab_index);
-                        self.cancel_search();
-                        self.render();
-                    }
-                    Act::SelectLastTab => {
-                        self.cancel_search();
-                        self.context_manager.select_last_tab();
-                        self.render();
-                    }
-                    Act::SelectNextTab => {
-                        self.cancel_search();
-                        self.clear_selection();
-                        self.context_manager.switch_to_next();
-                        self.render();
-                    }
-                    Act::MoveCurrentTabToPrev => {
-                        self.cancel_search();
-                        self.clear_selection();
-                        self.context_manager.move_current_to_prev();
-                        self.render();
-                    }
-                    Act::MoveCurrentTabToNext => {
-                        self.cancel_search();
-                        self.clear_selection();
-                        self.context_manager.move_current_to_next();
-                        self.render();
-                    }
-                    Act::SelectPrevTab => {
-                        self.cancel_search();
-                        self.clear_selection();
-                        self.context_manager.switch_to_prev();
+        (index == grid.current && self.search_active())
+            || renderable_content
+                .last_scroll
+                .is_some_and(|last_scroll| last_scroll.elapsed() < SCROLLBAR_TIMEOUT)
+    }
+
+    /// Keep the scrollbar of a split visible for a while.
+    fn reveal_scrollbar(&mut self, index: usize) {
+        if let Some(item) = self
+            .context_manager
+            .current_grid_mut()
+            .contexts_mut()
+            .get_mut(index)
+        {
+            item.context_mut().renderable_content.last_scroll = Some(Instant::now());
+        }
+    }
+
+    fn scroll_split_to(&mut self, index: usize, display_offset: usize) {
+        if let Some(item) = self
+            .context_manager
+            .current_grid_mut()
+            .contexts_mut()
+            .get_mut(index)
+        {
+            let mut terminal = item.context().terminal.lock();
+            let delta = display_offset as i32 - terminal.display_offset() as i32;
+            if delta != 0 {
+                terminal.scroll_display(Scroll::Delta(delta));
+            }
+        }
+
+        self.reveal_scrollbar(index);
+        self.render();
+    }
+
+    /// Start dragging a visible scrollbar under the mouse. Clicking
+    /// outside of the thumb jumps to that position.
+    pub fn start_scrollbar_drag(&mut self) -> bool {
+        let (index, scrollbar) = match self.scrollbar_under_mouse() {
+            Some(hit) if self.is_scrollbar_visible(hit.0) => hit,
+            _ => return false,
+        };
+
+        let y = self.mouse.y as f32 / self.sugarloaf.scale_factor();
+        let grab = if scrollbar.thumb_contains(y) {
+            y - scrollbar.thumb_y
+        } else {
+            scrollbar.thumb_height / 2.
+        };
+
+        self.scrollbar_drag = Some((index, grab));
+        self.scroll_split_to(index, scrollbar.display_offset_at(y - grab));
+        true
+    }
+
+    /// Scroll the dragged split to the mouse, or reveal the scrollbar
+    /// when hovered. Returns false if no scrollbar is being dragged.
+    pub fn update_scrollbar_drag(&mut self) -> bool {
+        let (index, grab) = match self.scrollbar_drag {
+            Some(drag) => drag,
+            None => {
+                if let Some((index, _)) = self.scrollbar_under_mouse() {
+                    if !self.is_scrollbar_visible(index) {
+                        self.reveal_scrollbar(index);
                         self.render();
                     }
-                    Act::ReceiveChar | Act::None => (),
-                    _ => (),
                 }
+                return false;
             }
+        };
+
+        if let Some(scrollbar) = self.scrollbar(index) {
+            let y = self.mouse.y as f32 / self.sugarloaf.scale_factor();
+            self.scroll_split_to(index, scrollbar.display_offset_at(y - grab));
         }
+        true
+    }
 
-        ignore_chars.unwrap_or(false)
+    pub fn end_scrollbar_drag(&mut self) -> bool {
+        self.scrollbar_drag.take().is_some()
+    }
+
+    fn run_palette_target(&mut self, target: PaletteTarget) {
+        match target {
+            PaletteTarget::Action(action) => self.run_action(&action),
+            PaletteTarget::Tab(tab_index) => {
+                self.clear_selection();
+                self.context_manager.select_tab(tab_index);
+            }
+            PaletteTarget::Split(tab_index, split_index) => {
+                self.clear_selection();
+                self.context_manager.select_split(tab_index, split_index);
+            }
+        }
     }
 
     pub fn split_right_with_config(&mut self, config: rio_backend::config::Config) {
+        let font_size = self.new_context_font_size(true);
         let rich_text_id = self.sugarloaf.create_rich_text();
         self.context_manager
             .split_from_config(rich_text_id, false, config);
 
+        self.set_context_font_size(rich_text_id, font_size);
         self.render();
     }
 
     pub fn split_right(&mut self) {
+        let font_size = self.new_context_font_size(true);
         let rich_text_id = self.sugarloaf.create_rich_text();
         self.context_manager.split(rich_text_id, false);
 
+        self.set_context_font_size(rich_text_id, font_size);
         self.render();
     }
 
     pub fn split_down(&mut self) {
+        let font_size = self.new_context_font_size(true);
         let rich_text_id = self.sugarloaf.create_rich_text();
         self.context_manager.split(rich_text_id, true);
 
+        self.set_context_font_size(rich_text_id, font_size);
         self.render();
     }
 
     pub fn create_tab(&mut self) {
         let redirect = true;
 
+        let font_size = self.new_context_font_size(false);
         let rich_text_id = self.sugarloaf.create_rich_text();
         self.context_manager.add_context(redirect, rich_text_id);
+        self.set_context_font_size(rich_text_id, font_size);
+
+        let num_tabs = self.ctx().len();
+        self.cancel_search();
+        self.resize_top_or_bottom_line(num_tabs);
+        self.render();
+    }
+
+    pub fn split_with_profile(&mut self, name: &str, split_down: bool) {
+        let profile = match find_profile(&self.profiles, name) {
+            Some(profile) => profile.clone(),
+            None => {
+                tracing::warn!("profile {name} not found");
+                return;
+            }
+        };
+
+        let font_size = profile
+            .font_size
+            .unwrap_or_else(|| self.new_context_font_size(true));
+        let rich_text_id = self.sugarloaf.create_rich_text();
+        self.context_manager
+            .split_with_profile(rich_text_id, split_down, &profile);
+
+        self.set_context_font_size(rich_text_id, font_size);
+        self.render();
+    }
+
+    pub fn create_tab_with_profile(&mut self, name: &str) {
+        let profile = match find_profile(&self.profiles, name) {
+            Some(profile) => profile.clone(),
+            None => {
+                tracing::warn!("profile {name} not found");
+                return;
+            }
+        };
+
+        let redirect = true;
+
+        let font_size = profile
+            .font_size
+            .unwrap_or_else(|| self.new_context_font_size(false));
+        let rich_text_id = self.sugarloaf.create_rich_text();
+        self.context_manager
+            .add_context_with_profile(redirect, rich_text_id, &profile);
+        self.set_context_font_size(rich_text_id, font_size);
 
         let num_tabs = self.ctx().len();
         self.cancel_search();
@@ -1077,6 +1734,36 @@ impl Screen<'_> {
         self.render();
     }
 
+    /// Font size of a new split or tab. The zoom of the focused split
+    /// carries over to new splits of its tab with the `tab` scope, and to
+    /// any new split or tab with the `window` scope.
+    fn new_context_font_size(&self, is_split: bool) -> f32 {
+        let inherits_zoom = match self.font_size_scope {
+            FontSizeScope::Split => false,
+            FontSizeScope::Tab => is_split,
+            FontSizeScope::Window => true,
+        };
+
+        if !inherits_zoom {
+            return self.sugarloaf.style().font_size;
+        }
+
+        self.sugarloaf
+            .rich_text_layout(&self.context_manager.current().rich_text_id)
+            .font_size
+    }
+
+    /// New splits and tabs copy the dimension of the split they were
+    /// created from, it is computed again for their own font size.
+    fn set_context_font_size(&mut self, rich_text_id: usize, font_size: f32) {
+        self.sugarloaf
+            .set_rich_text_font_size(&rich_text_id, font_size);
+        let layout = self.sugarloaf.rich_text_layout(&rich_text_id);
+        self.context_manager
+            .current_grid_mut()
+            .update_context_dimension(rich_text_id, layout.dimensions);
+    }
+
     pub fn close_split_or_tab(&mut self) {
         if self.context_manager.current_grid_len() > 1 {
             self.clear_selection();
@@ -1123,9 +1810,9 @@ impl Screen<'_> {
             let layout = self
                 .sugarloaf
                 .rich_text_layout(&self.context_manager.current().rich_text_id);
-            let s = self.sugarloaf.style_mut();
-            s.font_size = layout.font_size;
-            s.line_height = layout.line_height;
+            // The font size stays the configured one, it is the size of new
+            // splits and tabs that don't inherit the zoom.
+            self.sugarloaf.style_mut().line_height = layout.line_height;
 
             let d = self.context_manager.current_grid_mut();
             d.update_margin((d.margin.x, padding_y_top, padding_y_bottom));
@@ -1155,6 +1842,55 @@ impl Screen<'_> {
         self.update_search();
     }
 
+    /// Replace the search regex with the next preset.
+    fn search_next_preset(&mut self) {
+        if self.search_state.history_index.is_none() || self.search_presets.is_empty() {
+            return;
+        }
+
+        let index = match self.search_preset {
+            Some(index) => (index + 1) % self.search_presets.len(),
+            None => 0,
+        };
+        self.select_search_preset(index);
+    }
+
+    /// Search with the preset named `name`, starting a forward search
+    /// when none is active.
+    /// `name` is lowercase, as actions are parsed.
+    fn search_preset_by_name(&mut self, name: &str) {
+        let index = match self
+            .search_presets
+            .iter()
+            .position(|preset| preset.name.to_lowercase() == name)
+        {
+            Some(index) => index,
+            None => {
+                tracing::warn!("search preset {name} not found");
+                return;
+            }
+        };
+
+        if self.search_state.history_index.is_none() {
+            self.start_search(Direction::Right);
+        }
+        self.select_search_preset(index);
+    }
+
+    fn select_search_preset(&mut self, index: usize) {
+        self.search_preset = Some(index);
+        self.search_state.history[0] = self.search_presets[index].regex.clone();
+        self.search_state.history_index = Some(0);
+        self.update_search();
+    }
+
+    /// Working directory reported through OSC 7, which keys the history
+    /// when `search.per-directory-history` is enabled.
+    fn search_directory(&self) -> Option<std::path::PathBuf> {
+        let terminal = self.context_manager.current().terminal.lock();
+        terminal.current_directory().map(|path| path.to_path_buf())
+    }
+
     /// Go to the previous regex in the search history.
     #[inline]
     fn search_history_next(&mut self) {
@@ -1418,6 +2154,34 @@ impl Screen<'_> {
         self.exec("cmd", ["/c", "start", "", hyperlink.uri()]);
     }
 
+    /// Runs the bell for `route_id`: flashes when the route is visible,
+    /// otherwise flags its tab. Returns true when a redraw is needed.
+    pub fn ring_bell(&mut self, route_id: usize) -> bool {
+        // The command runs in the directory of the split that rang.
+        #[cfg(unix)]
+        let cwd = || {
+            let context = self.context_manager.context_by_route(route_id)?;
+            teletypewriter::foreground_process_path(*context.main_fd, context.shell_pid)
+                .ok()
+        };
+        #[cfg(not(unix))]
+        let cwd = || None;
+        self.bell_command.ring(Instant::now(), cwd);
+
+        let is_visible = self
+            .context_manager
+            .current_grid()
+            .contexts()
+            .iter()
+            .any(|item| item.context().route_id == route_id);
+
+        if is_visible {
+            self.renderer.start_bell()
+        } else {
+            self.context_manager.set_bell_by_route(route_id)
+        }
+    }
+
     pub fn exec<I, S>(&self, program: &str, args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
@@ -1557,6 +2321,10 @@ impl Screen<'_> {
 
     #[inline]
     fn start_search(&mut self, direction: Direction) {
+        let directory = self.search_directory();
+        self.search_state.history = self.search_history.terms(directory.as_deref());
+        self.search_preset = None;
+
         // Only create new history entry if the previous regex wasn't empty.
         if self
             .search_state
@@ -1642,6 +2410,11 @@ impl Screen<'_> {
         // let vi_mode = self.get_mode().contains(Mode::VI);
         // self.window().set_ime_allowed(!vi_mode);
 
+        if let Some(regex) = self.search_state.regex().cloned() {
+            let directory = self.search_directory();
+            self.search_history.add(directory.as_deref(), &regex);
+        }
+
         self.search_state.history_index = None;
 
         // Clear focused match.
@@ -1800,12 +2573,16 @@ impl Screen<'_> {
     }
 
     fn sgr_mouse_report(&mut self, pos: Pos, button: u8, state: ElementState) {
-        let c = match state {
-            ElementState::Pressed => 'M',
-            ElementState::Released => 'm',
-        };
+        let msg = sgr_mouse_sequence(button, pos.col.0, pos.row.0 as usize, state);
+        self.ctx_mut()
+            .current_mut()
+            .messenger
+            .send_bytes(msg.into_bytes());
+    }
 
-        let msg = format!("\x1b[<{};{};{}{}", button, pos.col + 1, pos.row + 1, c);
+    fn sgr_pixels_mouse_report(&mut self, button: u8, state: ElementState) {
+        let (x, y) = self.mouse_pixel_position();
+        let msg = sgr_mouse_sequence(button, x, y, state);
         self.ctx_mut()
             .current_mut()
             .messenger
@@ -1823,8 +2600,18 @@ impl Screen<'_> {
         self.get_mode().intersects(Mode::MOUSE_MOTION)
     }
 
+    #[inline]
+    pub fn has_sgr_pixels_mouse(&self) -> bool {
+        let mode = self.get_mode();
+        mode.intersects(Mode::MOUSE_MODE) && mode.contains(Mode::SGR_PIXELS_MOUSE)
+    }
+
     #[inline]
     pub fn mouse_report(&mut self, button: u8, state: ElementState) {
+        if self.ctx().current().read_only {
+            return;
+        }
+
         let terminal = self.ctx().current().terminal.lock();
         let display_offset = terminal.display_offset();
         let mode = terminal.mode();
@@ -1851,7 +2638,9 @@ impl Screen<'_> {
         }
 
         // Report mouse events.
-        if mode.contains(Mode::SGR_MOUSE) {
+        if mode.contains(Mode::SGR_PIXELS_MOUSE) {
+            self.sgr_pixels_mouse_report(button + mods, state);
+        } else if mode.contains(Mode::SGR_MOUSE) {
             self.sgr_mouse_report(pos, button + mods, state);
         } else if let ElementState::Released = state {
             self.normal_mouse_report(pos, 3 + mods);
@@ -1916,13 +2705,14 @@ impl Screen<'_> {
         let width = layout.dimensions.width as f64;
         let height = layout.dimensions.height as f64;
         let mode = self.get_mode();
+        let read_only = self.ctx().current().read_only;
 
         const MOUSE_WHEEL_UP: u8 = 64;
         const MOUSE_WHEEL_DOWN: u8 = 65;
         const MOUSE_WHEEL_LEFT: u8 = 66;
         const MOUSE_WHEEL_RIGHT: u8 = 67;
 
-        if mode.intersects(Mode::MOUSE_MODE) && !mode.contains(Mode::VI) {
+        if mode.intersects(Mode::MOUSE_MODE) && !mode.contains(Mode::VI) && !read_only {
             self.mouse.accumulated_scroll.x += new_scroll_x_px;
             self.mouse.accumulated_scroll.y += new_scroll_y_px;
 
@@ -1949,6 +2739,7 @@ impl Screen<'_> {
             }
         } else if mode.contains(Mode::ALT_SCREEN | Mode::ALTERNATE_SCROLL)
             && !self.modifiers.state().shift_key()
+            && !read_only
         {
             self.mouse.accumulated_scroll.x +=
                 (new_scroll_x_px * self.mouse.multiplier) / self.mouse.divider;
@@ -2005,32 +2796,116 @@ impl Screen<'_> {
             for c in text.chars() {
                 self.search_input(c);
             }
-        } else if bracketed && self.get_mode().contains(Mode::BRACKETED_PASTE) {
-            self.ctx_mut()
-                .current_mut()
-                .messenger
-                .send_bytes(b"\x1b[200~"[..].to_vec());
-
-            // Write filtered escape sequences.
-            //
-            // We remove `\x1b` to ensure it's impossible for the pasted text to write the bracketed
-            // paste end escape `\x1b[201~` and `\x03` since some shells incorrectly terminate
-            // bracketed paste on its receival.
-            let filtered = text.replace(['\x1b', '\x03'], "");
-            self.ctx_mut()
-                .current_mut()
-                .messenger
-                .send_bytes(filtered.into_bytes());
+            return;
+        }
 
-            self.ctx_mut()
-                .current_mut()
-                .messenger
-                .send_bytes(b"\x1b[201~"[..].to_vec());
+        let bracketed = bracketed && self.get_mode().contains(Mode::BRACKETED_PASTE);
+        if self.paste_config.guard {
+            if let Some(warning) =
+                PasteWarning::check(text, bracketed, self.paste_config.max_size)
+            {
+                self.pending_paste = Some((text.to_owned(), bracketed));
+                self.context_manager.confirm_paste(warning.message());
+                return;
+            }
+        }
+
+        self.write_paste(text, bracketed);
+    }
+
+    /// Writes the paste held by the paste guard.
+    pub fn confirm_paste(&mut self) {
+        if let Some((text, bracketed)) = self.pending_paste.take() {
+            self.write_paste(&text, bracketed);
+        }
+    }
+
+    /// Holds a file sent through OSC 1337 until it is confirmed, returns
+    /// false when it is dropped because too many are waiting.
+    pub fn request_download(
+        &mut self,
+        name: Option<String>,
+        data: std::sync::Arc<Vec<u8>>,
+    ) -> bool {
+        let download = PendingDownload::new(name.as_deref(), data);
+        if self.pending_downloads.push(download) {
+            return true;
+        }
+
+        tracing::warn!("Ignoring download, too many are waiting to be confirmed");
+        false
+    }
+
+    /// Question for the confirmation dialog of the next download.
+    #[inline]
+    pub fn download_message(&self) -> Option<String> {
+        self.pending_downloads.front().map(PendingDownload::message)
+    }
+
+    pub fn confirm_download(&mut self) {
+        if let Some(download) = self.pending_downloads.pop() {
+            match download.save(&self.download_dir) {
+                Ok(path) => tracing::info!("Downloaded file to {}", path.display()),
+                Err(err) => tracing::error!(
+                    "Failed to save {} to {}: {err}",
+                    download.file_name,
+                    self.download_dir.display()
+                ),
+            }
+        }
+    }
+
+    #[inline]
+    pub fn cancel_download(&mut self) {
+        self.pending_downloads.pop();
+    }
+
+    #[inline]
+    pub fn drop_file(&mut self, path: std::path::PathBuf) {
+        self.dropped_files.push(path);
+    }
+
+    /// Paste the files dropped at once as a single shell-quoted list.
+    pub fn flush_dropped_files(&mut self) {
+        if self.dropped_files.is_empty() {
+            return;
+        }
+
+        let paths = std::mem::take(&mut self.dropped_files);
+        let text = paste::format_dropped_paths(
+            &paths,
+            self.shell_kind,
+            self.paste_config.drop_format,
+            &self.paste_config.drop_template,
+        );
+        self.paste(&text, true);
+    }
+
+    #[inline]
+    pub fn cancel_paste(&mut self) {
+        self.pending_paste = None;
+    }
+
+    fn write_paste(&mut self, text: &str, bracketed: bool) {
+        let grid = self.context_manager.current_grid_mut();
+        if bracketed {
+            grid.write_input(b"\x1b[200~"[..].to_vec());
+
+            // Write filtered escape sequences, see `paste::filter_controls`.
+            let filtered = paste::filter_controls(text);
+            grid.write_input(filtered.into_bytes());
+
+            grid.write_input(b"\x1b[201~"[..].to_vec());
         } else {
-            self.ctx_mut()
-                .current_mut()
-                .messenger
-                .send_bytes(text.replace("\r\n", "\r").replace('\n', "\r").into_bytes());
+            let text = if self.paste_config.guard {
+                paste::filter_controls(text)
+            } else {
+                text.to_owned()
+            };
+
+            self.context_manager
+                .current_grid_mut()
+                .write_input(text.replace("\r\n", "\r").replace('\n', "\r").into_bytes());
         }
     }
 
@@ -2056,19 +2931,49 @@ impl Screen<'_> {
         self.sugarloaf.render();
     }
 
-    pub fn render_dialog(&mut self, content: &str) {
+    pub fn render_dialog(&mut self, content: &str, confirm: &str) {
         self.sugarloaf.clear();
         crate::router::routes::dialog::screen(
             &mut self.sugarloaf,
             &self.context_manager.current().dimension,
             content,
+            confirm,
+        );
+        self.sugarloaf.render();
+    }
+
+    /// Draws the terminal with the theme picker on top of it.
+    pub fn render_theme_picker(
+        &mut self,
+        picker: &crate::router::routes::themes::ThemePicker,
+    ) {
+        self.render_with_theme_picker(Some(picker));
+    }
+
+    pub fn render_profile_picker(&mut self, selected: usize) {
+        self.sugarloaf.clear();
+        crate::router::routes::profiles::screen(
+            &mut self.sugarloaf,
+            &self.context_manager.current().dimension,
+            &self.profiles,
+            selected,
         );
         self.sugarloaf.render();
     }
 
+    #[inline]
     pub fn render(&mut self) {
+        self.render_with_theme_picker(None);
+    }
+
+    fn render_with_theme_picker(
+        &mut self,
+        theme_picker: Option<&crate::router::routes::themes::ThemePicker>,
+    ) {
         // let start_total = std::time::Instant::now();
         // println!("_____________________________\nrender time elapsed");
+        self.context_manager.current_grid_mut().has_bell = false;
+
         let is_search_active = self.search_active();
         if is_search_active {
             if let Some(history_index) = self.search_state.history_index {
@@ -2078,6 +2983,32 @@ impl Screen<'_> {
             }
         }
 
+        let search_regex = self
+            .search_state
+            .history_index
+            .and_then(|index| self.search_state.history.get(index));
+        let search_preset = self
+            .search_preset
+            .and_then(|index| self.search_presets.get(index))
+            .filter(|preset| search_regex == Some(&preset.regex))
+            .map(|preset| preset.name.as_str());
+
+        let mut scrollbar_matches = None;
+        if is_search_active && self.renderer.is_scrollbar_enabled() {
+            let query = self
+                .search_state
+                .history_index
+                .and_then(|index| self.search_state.history.get(index))
+                .map_or("", String::as_str);
+            let terminal = self.context_manager.current().terminal.lock();
+            scrollbar_matches = Some(self.scrollbar_matches.lines(
+                query,
+                &terminal,
+                self.search_state.dfas.as_mut(),
+            ));
+            drop(terminal);
+        }
+
         let mut search_hints = if is_search_active {
             let terminal = self.context_manager.current().terminal.lock();
             let hints = self
@@ -2090,13 +3021,42 @@ impl Screen<'_> {
             None
         };
 
+        if !self.animations_paused {
+            self.sugarloaf.graphics.advance_animations(Instant::now());
+        }
+
         self.renderer.prepare_term(
             &mut self.sugarloaf,
             &mut self.context_manager,
             &mut search_hints,
             &self.search_state.focused_match,
+            scrollbar_matches,
+            Overlays {
+                palette: self.palette.as_ref(),
+                tab_rename: self.tab_rename.as_deref(),
+                theme_picker,
+                search_preset,
+            },
         );
         self.sugarloaf.render();
+
+        let next_animation_frame = if self.animations_paused {
+            None
+        } else {
+            self.sugarloaf.graphics.next_animation_frame(Instant::now())
+        };
+
+        if self.renderer.is_bell_animating() {
+            // Roughly 60 frames per second until the flash fades out.
+            self.context_manager.schedule_render(16);
+        } else if let Some(next_frame) = next_animation_frame {
+            self.context_manager
+                .schedule_render(next_frame.as_millis() as u64);
+        } else if let Some(hides_in) = self.renderer.scrollbar_hides_in() {
+            self.context_manager
+                .schedule_render(hides_in.as_millis() as u64 + 1);
+        }
+
         // In this case the configuration of blinking cursor is enabled
         // and the terminal also have instructions of blinking enabled
         // TODO: enable blinking for selection after adding debounce (https://github.com/raphamorim/rio/issues/437)
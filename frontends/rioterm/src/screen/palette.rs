@@ -0,0 +1,328 @@
+use crate::bindings::{Action, BindingKey, KeyBinding, NAMED_ACTIONS};
+use rio_window::keyboard::Key;
+
+/// Maximum number of items the palette shows at once.
+pub const PALETTE_VISIBLE_ITEMS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteTarget {
+    Action(Action),
+    Tab(usize),
+    /// Tab index and split index inside the tab.
+    Split(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteItem {
+    pub title: String,
+    /// Shown on the right side, e.g. the key binding.
+    pub detail: String,
+    pub target: PaletteTarget,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    items: Vec<PaletteItem>,
+    /// Indexes of `items` matching `query`, best match first.
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new(items: Vec<PaletteItem>) -> Self {
+        let mut palette = CommandPalette {
+            items,
+            ..Default::default()
+        };
+        palette.update_matches();
+        palette
+    }
+
+    /// Build the action items, labelled with their current key binding.
+    /// Actions only present in the user bindings (e.g. `Run`) are listed too.
+    pub fn action_items(
+        bindings: &[KeyBinding],
+        profiles: &[String],
+    ) -> Vec<PaletteItem> {
+        let mut items: Vec<PaletteItem> = NAMED_ACTIONS
+            .iter()
+            .filter_map(|(_, action, title)| title.map(|title| (title, action)))
+            .map(|(title, action)| PaletteItem {
+                title: title.to_string(),
+                detail: binding_label(bindings, action),
+                target: PaletteTarget::Action(action.clone()),
+            })
+            .collect();
+
+        for name in profiles {
+            for (title, action) in [
+                ("New Tab", Action::TabCreateNewWithProfile(name.to_owned())),
+                (
+                    "Split Right",
+                    Action::SplitRightWithProfile(name.to_owned()),
+                ),
+                ("Split Down", Action::SplitDownWithProfile(name.to_owned())),
+            ] {
+                items.push(PaletteItem {
+                    title: format!("{title}: {name}"),
+                    detail: binding_label(bindings, &action),
+                    target: PaletteTarget::Action(action),
+                });
+            }
+        }
+
+        for binding in bindings {
+            if !is_listed_in_palette(&binding.action) {
+                continue;
+            }
+
+            let target = PaletteTarget::Action(binding.action.clone());
+            if items.iter().any(|item| item.target == target) {
+                continue;
+            }
+
+            items.push(PaletteItem {
+                title: format!("{:?}", binding.action),
+                detail: binding_label(bindings, &binding.action),
+                target,
+            });
+        }
+
+        items
+    }
+
+    pub fn input(&mut self, c: char) {
+        if c.is_control() {
+            return;
+        }
+
+        self.query.push(c);
+        self.update_matches();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.update_matches();
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or(self.matches.len() - 1);
+        }
+    }
+
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_target(&self) -> Option<PaletteTarget> {
+        self.matches
+            .get(self.selected)
+            .map(|index| self.items[*index].target.clone())
+    }
+
+    /// Matching items, best match first.
+    pub fn matches(&self) -> impl Iterator<Item = &PaletteItem> {
+        self.matches.iter().map(|index| &self.items[*index])
+    }
+
+    #[inline]
+    pub fn matches_len(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(i32, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                fuzzy_score(&self.query, &item.title).map(|score| (score, index))
+            })
+            .collect();
+
+        // Stable sort keeps the original order between equal scores.
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+}
+
+/// Bindings for these actions are either internal or only meaningful
+/// in a given mode, so they are not offered by the palette.
+fn is_listed_in_palette(action: &Action) -> bool {
+    !matches!(
+        action,
+        Action::None
+            | Action::ReceiveChar
+            | Action::Esc(_)
+            | Action::Search(_)
+            | Action::ViMotion(_)
+            | Action::Vi(_)
+            | Action::OpenCommandPalette
+    )
+}
+
+/// Label of the first key binding triggering `action`, e.g. `Super+Shift+T`.
+pub fn binding_label(bindings: &[KeyBinding], action: &Action) -> String {
+    let binding = match bindings.iter().find(|binding| &binding.action == action) {
+        Some(binding) => binding,
+        None => return String::new(),
+    };
+
+    let mut label = String::new();
+    if binding.mods.control_key() {
+        label.push_str("Ctrl+");
+    }
+    if binding.mods.alt_key() {
+        label.push_str(if cfg!(target_os = "macos") {
+            "Option+"
+        } else {
+            "Alt+"
+        });
+    }
+    if binding.mods.shift_key() {
+        label.push_str("Shift+");
+    }
+    if binding.mods.super_key() {
+        label.push_str(if cfg!(target_os = "macos") {
+            "Cmd+"
+        } else {
+            "Super+"
+        });
+    }
+
+    match &binding.trigger {
+        BindingKey::Keycode { key, .. } => match key {
+            Key::Character(c) => label.push_str(&c.to_uppercase()),
+            Key::Named(named) => label.push_str(&format!("{named:?}")),
+            other => label.push_str(&format!("{other:?}")),
+        },
+        BindingKey::Scancode(code) => label.push_str(&format!("{code:?}")),
+    }
+
+    label
+}
+
+/// Scores `text` against `query` as a case-insensitive subsequence,
+/// rewarding consecutive characters and word starts. Returns `None`
+/// when `query` does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let is_word_start = |index: usize| index == 0 || !text[index - 1].is_alphanumeric();
+
+    // Matching is greedy after the first character, so try every place
+    // the first character occurs and keep the best result.
+    (0..text.len())
+        .filter(|start| text[*start] == query[0])
+        .filter_map(|start| {
+            let mut score = if is_word_start(start) { 9 } else { 1 };
+            let mut last = start;
+            for query_char in &query[1..] {
+                let found = (last + 1..text.len()).find(|i| text[*i] == *query_char)?;
+
+                score += 1;
+                if found == last + 1 {
+                    score += 5;
+                }
+                if is_word_start(found) {
+                    score += 8;
+                }
+                score -= (found - last - 1).min(5) as i32;
+                last = found;
+            }
+            Some(score)
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str) -> PaletteItem {
+        PaletteItem {
+            title: title.to_string(),
+            detail: String::new(),
+            target: PaletteTarget::Tab(0),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Split Right"), Some(0));
+        assert!(fuzzy_score("sr", "Split Right").is_some());
+        assert!(fuzzy_score("SPLIT", "Split Right").is_some());
+        assert!(fuzzy_score("rs", "Split Right").is_none());
+        assert!(fuzzy_score("x", "Split Right").is_none());
+
+        // Word starts score higher than letters in the middle of a word.
+        assert!(fuzzy_score("sd", "Split Down") > fuzzy_score("sd", "Scrolled"));
+        // Consecutive letters score higher than scattered ones.
+        assert!(fuzzy_score("tab", "Create Tab") > fuzzy_score("tab", "Toggle Last Bar"));
+    }
+
+    #[test]
+    fn test_palette_matches_and_selection() {
+        let mut palette = CommandPalette::new(vec![
+            item("Create Tab"),
+            item("Split Right"),
+            item("Split Down"),
+        ]);
+        assert_eq!(palette.matches_len(), 3);
+
+        palette.select_prev();
+        assert_eq!(palette.selected(), 2);
+        palette.select_next();
+        assert_eq!(palette.selected(), 0);
+
+        for c in "spd".chars() {
+            palette.input(c);
+        }
+        let titles: Vec<&str> = palette.matches().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["Split Down"]);
+
+        palette.input('z');
+        assert_eq!(palette.matches_len(), 0);
+        assert_eq!(palette.selected_target(), None);
+
+        palette.backspace();
+        assert_eq!(palette.matches_len(), 1);
+        assert_eq!(palette.selected_target(), Some(PaletteTarget::Tab(0)));
+    }
+
+    #[test]
+    fn test_every_action_is_listed() {
+        let items = CommandPalette::action_items(&[], &[]);
+        for (name, action, title) in NAMED_ACTIONS.iter() {
+            let target = PaletteTarget::Action(action.clone());
+            let listed = items.iter().filter(|item| item.target == target).count();
+            if is_listed_in_palette(action) {
+                assert!(title.is_some(), "{name} has no palette title");
+                assert_eq!(listed, 1, "{name}");
+            } else {
+                assert_eq!(listed, 0, "{name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use rio_backend::config::DropFormat;
+
+/// Whether `c` is a C0 or C1 control that should not reach the PTY from a
+/// paste. Tabs and line breaks are kept.
+#[inline]
+fn is_unsafe_control(c: char) -> bool {
+    match c {
+        '\t' | '\n' | '\r' => false,
+        '\x00'..='\x1f' | '\x7f' | '\u{80}'..='\u{9f}' => true,
+        _ => false,
+    }
+}
+
+/// Removes C0 and C1 controls (including `ESC` and `DEL`) from `text`.
+///
+/// Without `ESC` the pasted text can't write the bracketed paste end
+/// escape `\x1b[201~`, and without `\x03` shells that wrongly terminate
+/// bracketed paste on its receival are kept in it.
+pub fn filter_controls(text: &str) -> String {
+    text.chars().filter(|c| !is_unsafe_control(*c)).collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PasteWarning {
+    /// Number of lines in the paste.
+    Multiline(usize),
+    Controls,
+    /// Size of the paste in bytes.
+    TooLarge(usize),
+}
+
+impl PasteWarning {
+    /// Returns the reason a paste should be confirmed, if any.
+    ///
+    /// Line breaks and controls are only a concern outside of bracketed
+    /// paste, where they are interpreted by the shell as they arrive.
+    pub fn check(text: &str, bracketed: bool, max_size: usize) -> Option<PasteWarning> {
+        if !bracketed {
+            let lines = text.trim_end_matches(['\r', '\n']).lines().count();
+            if text.contains(['\r', '\n']) {
+                return Some(PasteWarning::Multiline(lines.max(1)));
+            }
+
+            if text.chars().any(is_unsafe_control) {
+                return Some(PasteWarning::Controls);
+            }
+        }
+
+        if max_size > 0 && text.len() > max_size {
+            return Some(PasteWarning::TooLarge(text.len()));
+        }
+
+        None
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            PasteWarning::Multiline(1) => String::from("Paste a line break?"),
+            PasteWarning::Multiline(lines) => format!("Paste {lines} lines?"),
+            PasteWarning::Controls => String::from("Paste control characters?"),
+            PasteWarning::TooLarge(size) => format!("Paste {size} bytes?"),
+        }
+    }
+}
+
+/// Quoting rules of the shell files are dropped into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    Posix,
+    Fish,
+    PowerShell,
+}
+
+impl ShellKind {
+    /// Detects the configured shell, an empty program is the login shell.
+    pub fn from_shell(shell: &rio_backend::config::Shell) -> ShellKind {
+        if shell.program.is_empty() {
+            let program = std::env::var("SHELL").unwrap_or_default();
+            return ShellKind::from_program(&program);
+        }
+
+        ShellKind::from_program(&shell.program)
+    }
+
+    /// Detects the shell from its program, e.g. `/usr/bin/fish` or `pwsh.exe`.
+    pub fn from_program(program: &str) -> ShellKind {
+        let name = program
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(program)
+            .to_lowercase();
+        match name.trim_end_matches(".exe") {
+            "fish" => ShellKind::Fish,
+            "pwsh" | "powershell" => ShellKind::PowerShell,
+            _ => ShellKind::Posix,
+        }
+    }
+
+    /// Quotes `arg` so the shell reads it as a single word.
+    pub fn quote(&self, arg: &str) -> String {
+        // PowerShell reads `,` as an array, `@` as splatting and `%` as
+        // ForEach-Object.
+        let safe = match self {
+            ShellKind::PowerShell => "_-+=:./",
+            ShellKind::Posix | ShellKind::Fish => "_-+=@%:,./",
+        };
+        let is_safe = |c: char| c.is_ascii_alphanumeric() || safe.contains(c);
+        if !arg.is_empty() && arg.chars().all(is_safe) {
+            return arg.to_owned();
+        }
+
+        match self {
+            ShellKind::Posix => format!("'{}'", arg.replace('\'', "'\\''")),
+            ShellKind::Fish => {
+                format!("'{}'", arg.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            ShellKind::PowerShell => format!("'{}'", arg.replace('\'', "''")),
+        }
+    }
+}
+
+/// Text pasted for dropped files: the quoted paths, or `file://` URIs,
+/// joined by spaces and placed into `template` at `{paths}`.
+pub fn format_dropped_paths(
+    paths: &[std::path::PathBuf],
+    shell: ShellKind,
+    format: DropFormat,
+    template: &str,
+) -> String {
+    let paths = paths
+        .iter()
+        .map(|path| {
+            let arg = match format {
+                DropFormat::Path => path.to_string_lossy().into_owned(),
+                DropFormat::Uri => match url::Url::from_file_path(path) {
+                    Ok(url) => url.to_string(),
+                    Err(..) => path.to_string_lossy().into_owned(),
+                },
+            };
+            shell.quote(&arg)
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    template.replace("{paths}", &paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_filter_controls() {
+        assert_eq!(filter_controls("echo hello"), "echo hello");
+        assert_eq!(filter_controls("a\tb\r\nc\n"), "a\tb\r\nc\n");
+        assert_eq!(filter_controls("\x1b[201~rm -rf ~"), "[201~rm -rf ~");
+        assert_eq!(filter_controls("a\x03b\x00c\x08d\x7f"), "abcd");
+        // C1 controls, e.g. CSI (U+009B) and OSC (U+009D).
+        assert_eq!(filter_controls("a\u{9b}31mb\u{9d}c"), "a31mbc");
+        assert_eq!(filter_controls("ação ▲"), "ação ▲");
+    }
+
+    #[test]
+    fn test_paste_warning() {
+        assert_eq!(PasteWarning::check("ls -la", false, 0), None);
+        assert_eq!(
+            PasteWarning::check("curl example.com | sh\n", false, 0),
+            Some(PasteWarning::Multiline(1))
+        );
+        assert_eq!(
+            PasteWarning::check("cd /\r\nls\r\n", false, 0),
+            Some(PasteWarning::Multiline(2))
+        );
+        assert_eq!(
+            PasteWarning::check("echo \x1b[31m", false, 0),
+            Some(PasteWarning::Controls)
+        );
+        // Tabs are fine.
+        assert_eq!(PasteWarning::check("a\tb", false, 0), None);
+
+        // Bracketed paste only checks the size.
+        assert_eq!(PasteWarning::check("cd /\nls\n", true, 0), None);
+        assert_eq!(PasteWarning::check("echo \x1b", true, 0), None);
+        assert_eq!(
+            PasteWarning::check("0123456789", true, 4),
+            Some(PasteWarning::TooLarge(10))
+        );
+        assert_eq!(PasteWarning::check("0123", true, 4), None);
+    }
+
+    #[test]
+    fn test_paste_warning_message() {
+        assert_eq!(PasteWarning::Multiline(1).message(), "Paste a line break?");
+        assert_eq!(PasteWarning::Multiline(3).message(), "Paste 3 lines?");
+        assert_eq!(PasteWarning::TooLarge(20).message(), "Paste 20 bytes?");
+    }
+
+    #[test]
+    fn test_shell_kind() {
+        assert_eq!(ShellKind::from_program("/bin/zsh"), ShellKind::Posix);
+        assert_eq!(ShellKind::from_program("/usr/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::from_program("pwsh"), ShellKind::PowerShell);
+        assert_eq!(
+            ShellKind::from_program("C:\\Windows\\powershell.exe"),
+            ShellKind::PowerShell
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(ShellKind::Posix.quote("/tmp/a.txt"), "/tmp/a.txt");
+        assert_eq!(ShellKind::Posix.quote("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(ShellKind::Posix.quote("it's"), "'it'\\''s'");
+        assert_eq!(ShellKind::Posix.quote("$(rm)"), "'$(rm)'");
+        assert_eq!(ShellKind::Fish.quote("it's a\\b"), "'it\\'s a\\\\b'");
+        assert_eq!(ShellKind::PowerShell.quote("it's $a"), "'it''s $a'");
+        assert_eq!(ShellKind::PowerShell.quote("C:/a.txt"), "C:/a.txt");
+        assert_eq!(ShellKind::PowerShell.quote("a,b"), "'a,b'");
+        assert_eq!(ShellKind::PowerShell.quote("@a"), "'@a'");
+        assert_eq!(ShellKind::PowerShell.quote("100%"), "'100%'");
+        assert_eq!(ShellKind::Posix.quote("a,b@100%"), "a,b@100%");
+        assert_eq!(ShellKind::Posix.quote(""), "''");
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_format_dropped_paths() {
+        let paths = [PathBuf::from("/tmp/a b.txt"), PathBuf::from("/tmp/c")];
+        assert_eq!(
+            format_dropped_paths(&paths, ShellKind::Posix, DropFormat::Path, "{paths} "),
+            "'/tmp/a b.txt' /tmp/c "
+        );
+        assert_eq!(
+            format_dropped_paths(&paths, ShellKind::Posix, DropFormat::Uri, "{paths}"),
+            "file:///tmp/a%20b.txt file:///tmp/c"
+        );
+        assert_eq!(
+            format_dropped_paths(
+                &paths[1..],
+                ShellKind::Fish,
+                DropFormat::Path,
+                "cd {paths}"
+            ),
+            "cd /tmp/c"
+        );
+    }
+}
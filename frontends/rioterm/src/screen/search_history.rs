@@ -0,0 +1,188 @@
+use super::MAX_SEARCH_HISTORY_SIZE;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Directories with their own history kept in the file.
+const MAX_DIRECTORIES: usize = 64;
+
+/// Search terms shared by every window, saved as `directory\tregex` lines
+/// with the most recent first. Terms searched without a directory have an
+/// empty one.
+pub struct SearchHistory {
+    /// `None` when the history is not persisted.
+    path: Option<PathBuf>,
+    per_directory: bool,
+    entries: Vec<(String, String)>,
+}
+
+impl SearchHistory {
+    pub fn new(path: Option<PathBuf>, per_directory: bool) -> SearchHistory {
+        let mut history = SearchHistory {
+            path,
+            per_directory,
+            entries: Vec::new(),
+        };
+        history.reload();
+        history
+    }
+
+    /// Terms searched in `directory`, the most recent first.
+    pub fn terms(&mut self, directory: Option<&Path>) -> VecDeque<String> {
+        self.reload();
+        let key = self.key(directory);
+        self.entries
+            .iter()
+            .filter(|(entry_key, _)| *entry_key == key)
+            .map(|(_, regex)| regex.clone())
+            .collect()
+    }
+
+    /// Move `regex` to the top of the history of `directory` and save it.
+    pub fn add(&mut self, directory: Option<&Path>, regex: &str) {
+        let key = self.key(directory);
+        if regex.is_empty() || regex.contains(['\t', '\n']) || key.contains(['\t', '\n'])
+        {
+            return;
+        }
+
+        self.reload();
+        self.entries
+            .retain(|(entry_key, entry)| *entry_key != key || entry != regex);
+        self.entries.insert(0, (key, regex.to_owned()));
+        self.truncate();
+        self.save();
+    }
+
+    fn key(&self, directory: Option<&Path>) -> String {
+        match directory {
+            Some(directory) if self.per_directory => directory.to_string_lossy().into(),
+            _ => String::new(),
+        }
+    }
+
+    /// Drop the oldest terms of each directory, and the directories not
+    /// searched for the longest time.
+    fn truncate(&mut self) {
+        let mut keys: Vec<&str> = Vec::new();
+        let mut counts = Vec::new();
+        let mut keep = Vec::with_capacity(self.entries.len());
+        for (key, _) in &self.entries {
+            let index = match keys.iter().position(|k| *k == key.as_str()) {
+                Some(index) => index,
+                None => {
+                    keys.push(key);
+                    counts.push(0);
+                    keys.len() - 1
+                }
+            };
+
+            counts[index] += 1;
+            keep.push(
+                index < MAX_DIRECTORIES && counts[index] <= MAX_SEARCH_HISTORY_SIZE,
+            );
+        }
+
+        let mut keep = keep.into_iter();
+        self.entries.retain(|_| keep.next().unwrap_or(false));
+    }
+
+    fn reload(&mut self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+
+        // The file is missing until the first search.
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            self.entries = contents
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .filter(|(_, regex)| !regex.is_empty())
+                .map(|(key, regex)| (key.to_owned(), regex.to_owned()))
+                .collect();
+        }
+    }
+
+    fn save(&self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut contents = String::new();
+        for (key, regex) in &self.entries {
+            contents.push_str(key);
+            contents.push('\t');
+            contents.push_str(regex);
+            contents.push('\n');
+        }
+
+        let result = match path.parent() {
+            Some(parent) => std::fs::create_dir_all(parent),
+            None => Ok(()),
+        }
+        .and_then(|_| std::fs::write(path, contents));
+
+        if let Err(err) = result {
+            tracing::warn!("Failed to save the search history: {err}");
+        }
+    }
+}
+
+/// File in the data directory where the search history is saved.
+pub fn search_history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("rio").join("search_history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_history_dedup_and_cap() {
+        let mut history = SearchHistory::new(None, false);
+        history.add(None, "foo");
+        history.add(None, "bar");
+        history.add(Some(Path::new("/tmp")), "foo");
+        history.add(None, "");
+        assert_eq!(history.terms(None), ["foo", "bar"]);
+
+        for index in 0..MAX_SEARCH_HISTORY_SIZE + 10 {
+            history.add(None, &index.to_string());
+        }
+        let terms = history.terms(None);
+        assert_eq!(terms.len(), MAX_SEARCH_HISTORY_SIZE);
+        assert_eq!(terms[0], (MAX_SEARCH_HISTORY_SIZE + 9).to_string());
+    }
+
+    #[test]
+    fn test_search_history_per_directory() {
+        let dir = std::env::temp_dir().join(format!(
+            "rio-test-{}-search-history-per-directory",
+            std::process::id()
+        ));
+        let path = dir.join("search_history");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut history = SearchHistory::new(Some(path.clone()), true);
+        history.add(Some(Path::new("/home/rio/project")), "error");
+        history.add(Some(Path::new("/home/rio")), "warning");
+        history.add(None, "todo");
+
+        // Another window reads what was saved.
+        let mut other = SearchHistory::new(Some(path.clone()), true);
+        assert_eq!(other.terms(Some(Path::new("/home/rio/project"))), ["error"]);
+        assert_eq!(other.terms(Some(Path::new("/home/rio"))), ["warning"]);
+        assert_eq!(other.terms(None), ["todo"]);
+
+        // Without per-directory history every term is shared.
+        let mut shared = SearchHistory::new(Some(path.clone()), false);
+        shared.add(Some(Path::new("/home/rio")), "panic");
+        assert_eq!(
+            shared.terms(Some(Path::new("/home/rio"))),
+            ["panic", "todo"]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,54 @@
 use crate::event::{EventListener, RioEvent};
 use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::Path;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 const POLLING_TIMEOUT: Duration = Duration::from_secs(2);
 
-pub fn configuration_file_updates<
-    P: AsRef<Path> + std::marker::Send + 'static,
-    T: EventListener + std::marker::Send + 'static,
->(
-    path: P,
+/// Watches the config directory, and the directories of config files that
+/// live outside of it.
+pub struct ConfigWatcher {
+    watcher: RecommendedWatcher,
+    directories: Vec<PathBuf>,
+    /// Files outside of the config directory that trigger a reload.
+    files: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ConfigWatcher {
+    /// Also reload the config when one of `files` changes, the files watched
+    /// before are replaced.
+    pub fn watch_files(&mut self, files: &[PathBuf]) {
+        *self.files.lock() = files.to_vec();
+
+        for file in files {
+            let directory = match file.parent() {
+                Some(directory) => directory,
+                None => continue,
+            };
+
+            if self.directories.iter().any(|watched| watched == directory) {
+                continue;
+            }
+
+            // Files are replaced when saved by some editors, so the directory
+            // is watched instead of the file.
+            match self.watcher.watch(directory, RecursiveMode::NonRecursive) {
+                Ok(_) => self.directories.push(directory.to_path_buf()),
+                Err(err_message) => tracing::warn!(
+                    "unable to watch config directory {}: {err_message:?}",
+                    directory.display()
+                ),
+            }
+        }
+    }
+}
+
+pub fn configuration_file_updates<T: EventListener + std::marker::Send + 'static>(
+    path: PathBuf,
     event_proxy: T,
-) -> notify::Result<()> {
+) -> notify::Result<ConfigWatcher> {
     let (tx, rx) = std::sync::mpsc::channel();
 
     // Automatically select the best implementation for your platform.
@@ -21,15 +58,16 @@ pub fn configuration_file_updates<
         Config::default().with_poll_interval(POLLING_TIMEOUT),
     )?;
 
-    std::thread::spawn(move || {
-        // Add a path to be watched. All files and directories at that path and
-        // below will be monitored for changes.
-        if let Err(err_message) =
-            watcher.watch(path.as_ref(), RecursiveMode::NonRecursive)
-        {
-            tracing::warn!("unable to watch config directory {err_message:?}");
-        };
+    // Add a path to be watched. All files and directories at that path
+    // will be monitored for changes.
+    if let Err(err_message) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+        tracing::warn!("unable to watch config directory {err_message:?}");
+    };
 
+    let files = Arc::new(Mutex::new(Vec::new()));
+    let watched_files = files.clone();
+    let config_dir = path.clone();
+    std::thread::spawn(move || {
         for res in rx {
             match res {
                 Ok(event) => match event.kind {
@@ -37,6 +75,10 @@ pub fn configuration_file_updates<
                     | EventKind::Create(_)
                     | EventKind::Modify(_)
                     | EventKind::Other => {
+                        if !is_config_event(&event.paths, &config_dir, &watched_files) {
+                            continue;
+                        }
+
                         tracing::info!(
                             "config directory has dispatched an event {event:?}"
                         );
@@ -54,5 +96,26 @@ pub fn configuration_file_updates<
         }
     });
 
-    Ok(())
+    Ok(ConfigWatcher {
+        watcher,
+        directories: vec![path],
+        files,
+    })
+}
+
+/// Anything in the config directory counts, other directories only for the
+/// watched files.
+fn is_config_event(
+    paths: &[PathBuf],
+    config_dir: &Path,
+    files: &Mutex<Vec<PathBuf>>,
+) -> bool {
+    if paths.is_empty() {
+        return true;
+    }
+
+    let files = files.lock();
+    paths
+        .iter()
+        .any(|path| path.starts_with(config_dir) || files.contains(path))
 }
@@ -0,0 +1,41 @@
+#!/usr/bin/env bash
+# Runs the rio-window drag and drop test against a headless sway.
+
+set -euo pipefail
+
+runtime_dir=$(mktemp -d)
+sway_pid=""
+cleanup() {
+    if [ -n "$sway_pid" ]; then
+        kill "$sway_pid" 2>/dev/null || true
+    fi
+    rm -rf "$runtime_dir"
+}
+trap cleanup EXIT
+
+export XDG_RUNTIME_DIR="$runtime_dir"
+WLR_BACKENDS=headless WLR_LIBINPUT_NO_DEVICES=1 WLR_RENDERER=pixman \
+    sway -c /dev/null &
+sway_pid=$!
+
+socket=""
+for _ in $(seq 100); do
+    for path in "$runtime_dir"/wayland-*; do
+        case "$path" in
+            *.lock | *"wayland-*") ;;
+            *) socket=$(basename "$path") ;;
+        esac
+    done
+    if [ -n "$socket" ]; then
+        break
+    fi
+    sleep 0.1
+done
+
+if [ -z "$socket" ]; then
+    echo "sway did not create a wayland socket" >&2
+    exit 1
+fi
+
+RIO_TEST_WAYLAND_DISPLAY="$socket" \
+    cargo test -p rio-window --test wayland_dnd -- --nocapture
@@ -0,0 +1,143 @@
+// Images are decoded on a small pool of threads, so large pictures don't
+// block the PTY reader while it holds the terminal lock.
+//
+// The grid keeps a placeholder for the graphic, and the pixels are sent to
+// the renderer once the decoder is done.
+
+use crate::ansi::iterm2_image_protocol::FileTransfer;
+use crate::ansi::sixel;
+use crate::config::images::Images;
+use crate::sugarloaf::{GraphicData, GraphicId};
+use parking_lot::Mutex;
+use std::fmt;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread;
+
+/// Maximum number of decoder threads.
+const MAX_THREADS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Source of the pixels of a graphic.
+pub enum DecodeSource {
+    /// Image file sent through OSC 1337.
+    File(FileTransfer),
+    /// Sixel data, already parsed.
+    Sixel(Box<sixel::Parser>),
+}
+
+/// Graphic reserved in the grid, waiting for its pixels.
+pub struct DecodeJob {
+    pub id: GraphicId,
+    pub source: DecodeSource,
+    /// Final size of the graphic, as reserved in the grid.
+    pub width: usize,
+    pub height: usize,
+    pub limits: Images,
+}
+
+impl DecodeJob {
+    fn decode(self) -> Option<GraphicData> {
+        let graphic = match self.source {
+            DecodeSource::File(transfer) => {
+                transfer.graphic(&self.limits, (self.width, self.height))?
+            }
+            DecodeSource::Sixel(parser) => match parser.finish() {
+                Ok((graphic, _)) => graphic,
+                Err(err) => {
+                    tracing::warn!("Failed to parse Sixel data: {}", err);
+                    return None;
+                }
+            },
+        };
+
+        let graphic = if graphic.width == self.width && graphic.height == self.height {
+            graphic
+        } else {
+            graphic.resize_exact(self.width, self.height)?
+        };
+
+        Some(GraphicData {
+            id: self.id,
+            resize: None,
+            ..graphic
+        })
+    }
+}
+
+/// Graphics decoded by the pool, waiting to be sent to the renderer.
+#[derive(Default)]
+pub struct DecodedQueue {
+    /// `None` when the graphic could not be decoded.
+    graphics: Mutex<Vec<(GraphicId, Option<GraphicData>)>>,
+    /// Called when a graphic is added to the queue.
+    waker: Mutex<Option<Box<dyn Fn() + Send>>>,
+}
+
+impl fmt::Debug for DecodedQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodedQueue")
+            .field("graphics", &self.graphics.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DecodedQueue {
+    pub fn set_waker<F: Fn() + Send + 'static>(&self, waker: F) {
+        *self.waker.lock() = Some(Box::new(waker));
+    }
+
+    pub fn take(&self) -> Vec<(GraphicId, Option<GraphicData>)> {
+        std::mem::take(&mut *self.graphics.lock())
+    }
+
+    fn push(&self, id: GraphicId, graphic: Option<GraphicData>) {
+        self.graphics.lock().push((id, graphic));
+        if let Some(waker) = &*self.waker.lock() {
+            waker();
+        }
+    }
+}
+
+/// Decode the graphic in the pool, the result is pushed to `queue`.
+pub fn decode(job: DecodeJob, queue: Arc<DecodedQueue>) {
+    let id = job.id;
+    spawn(Box::new(move || queue.push(id, job.decode())));
+}
+
+fn spawn(job: Job) {
+    static POOL: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+
+    let sender = POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1)
+            .min(MAX_THREADS);
+
+        for index in 0..threads {
+            let receiver = receiver.clone();
+            let result = thread::Builder::new()
+                .name(format!("image decoder {index}"))
+                .spawn(move || loop {
+                    let job = match receiver.lock().recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    job();
+                });
+
+            if let Err(err) = result {
+                tracing::error!("Failed to spawn image decoder: {}", err);
+            }
+        }
+
+        Mutex::new(sender)
+    });
+
+    if let Err(mpsc::SendError(job)) = sender.lock().send(job) {
+        // No decoder is running, decode in this thread.
+        job();
+    }
+}
@@ -2,11 +2,14 @@
 // Alacritty is licensed under Apache 2.0 license.
 // https://github.com/alacritty/alacritty/pull/4763/files
 
+use crate::ansi::decoder::{self, DecodeJob, DecodedQueue};
 use crate::ansi::sixel;
 use crate::config::colors::ColorRgb;
+use crate::config::images::Images;
 use crate::crosswords::grid::Dimensions;
 use crate::sugarloaf::{GraphicData, GraphicId};
 use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
 use std::mem;
 use std::sync::{Arc, Weak};
@@ -147,6 +150,18 @@ pub struct Graphics {
 
     /// Current Sixel parser.
     pub sixel_parser: Option<Box<sixel::Parser>>,
+
+    /// Limits for the images sent by programs.
+    pub limits: Images,
+
+    /// Graphics decoded in the background.
+    pub decoded: Arc<DecodedQueue>,
+
+    /// Graphics reserved in the grid, still being decoded.
+    decoding: FxHashSet<GraphicId>,
+
+    /// Graphics removed from the grid before being decoded.
+    cancelled: FxHashSet<GraphicId>,
 }
 
 impl Graphics {
@@ -164,6 +179,31 @@ impl Graphics {
         GraphicId(self.last_id)
     }
 
+    /// Decode the pixels of a graphic already placed in the grid.
+    pub fn decode(&mut self, job: DecodeJob) {
+        self.decoding.insert(job.id);
+        decoder::decode(job, self.decoded.clone());
+    }
+
+    /// Whether any graphic is still being decoded.
+    #[inline]
+    pub fn is_decoding(&self) -> bool {
+        !self.decoding.is_empty()
+    }
+
+    /// Move the decoded graphics to the pending queue.
+    pub fn collect_decoded(&mut self) {
+        for (id, graphic) in self.decoded.take() {
+            if self.cancelled.remove(&id) || !self.decoding.remove(&id) {
+                continue;
+            }
+
+            if let Some(graphic) = graphic {
+                self.pending.push(graphic);
+            }
+        }
+    }
+
     /// Get queues to update graphics in the grid.
     ///
     /// If all queues are empty, it returns `None`.
@@ -177,20 +217,29 @@ impl Graphics {
             }
         };
 
-        if texture_operations.is_empty() && self.pending.is_empty() {
-            return None;
-        }
-
         let mut remove_queue = Vec::new();
         let mut clear_subregions = Vec::new();
 
         for operation in texture_operations {
             match operation {
+                // The renderer never received a graphic still being decoded.
+                TextureOperation::Remove(id) if self.decoding.remove(&id) => {
+                    self.cancelled.insert(id);
+                }
                 TextureOperation::Remove(id) => remove_queue.push(id),
                 TextureOperation::ClearSubregion(cs) => clear_subregions.push(cs),
             }
         }
 
+        self.collect_decoded();
+
+        if remove_queue.is_empty()
+            && clear_subregions.is_empty()
+            && self.pending.is_empty()
+        {
+            return None;
+        }
+
         Some(UpdateQueues {
             pending: mem::take(&mut self.pending),
             remove_queue,
@@ -216,6 +265,7 @@ fn check_opaque_region() {
         pixels: vec![255; 10 * 10 * 3],
         is_opaque: true,
         resize: None,
+        animation: None,
     };
 
     assert!(graphic.is_filled(1, 1, 3, 3));
@@ -239,6 +289,7 @@ fn check_opaque_region() {
         color_type: ColorType::Rgba,
         is_opaque: false,
         resize: None,
+        animation: None,
     };
 
     assert!(graphic.is_filled(0, 0, 3, 3));
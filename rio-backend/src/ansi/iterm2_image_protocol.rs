@@ -2,46 +2,261 @@
 // https://github.com/ayosec/alacritty/commit/661a64c2b35283c97bac71d29535393e909c7d19
 // This module implements support for the [iTerm2 images protocol](https://iterm2.com/documentation-images.html).
 //
-// iTerm2 uses the OSC 1337 for a many non-standard commands, but we only support
-// adding inline graphics.
+// iTerm2 uses the OSC 1337 for a many non-standard commands, we support file
+// transfers (inline images and downloads, in a single sequence or split with
+// `MultipartFile`), `SetUserVar` and `ReportCellSize`.
 //
 // This implementation also supports `width` and `height` parameters to resize the image.
 
-use sugarloaf::{GraphicData, GraphicId, ResizeCommand, ResizeParameter};
+use crate::config::images::Images;
+use image_rs::codecs::gif::GifDecoder;
+use image_rs::codecs::png::PngDecoder;
+use image_rs::codecs::webp::WebPDecoder;
+use image_rs::{AnimationDecoder, DynamicImage, ImageFormat};
+use sugarloaf::{Animation, GraphicData, GraphicId, ResizeCommand, ResizeParameter};
 
 use rustc_hash::FxHashMap;
-use std::str;
+use std::io::Cursor;
+use std::time::Duration;
+use std::{cmp, str};
 
 use base64::engine::general_purpose::STANDARD as Base64;
 use base64::Engine;
 
-/// Parse the OSC 1337 parameters to add a graphic to the grid.
-pub fn parse(params: &[&[u8]]) -> Option<GraphicData> {
-    let (params, contents) = param_values(params)?;
+/// `key=value` arguments of a command.
+type Arguments<'a> = FxHashMap<&'a str, &'a str>;
+
+/// Maximum size of the base64 contents of a transfer (128MiB).
+pub const MAX_TRANSFER_SIZE: usize = 0x800_0000;
+
+/// Frames shorter than this are shown for `DEFAULT_FRAME_DELAY`.
+const ZERO_DELAY_THRESHOLD: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Base64 bytes read to find the size of an image in its header.
+const HEADER_SIZE: usize = 0x1_0000;
+
+/// OSC 1337 commands.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// `File=`, a whole file in a single sequence.
+    File(FileTransfer),
+    /// `MultipartFile=`, the contents follow in `FilePart` sequences.
+    MultipartFile(FileTransfer),
+    /// `FilePart=`, base64 contents of the current multipart transfer.
+    FilePart(Vec<u8>),
+    /// `FileEnd`, completes the current multipart transfer.
+    FileEnd,
+    /// `SetUserVar=name=base64 value`.
+    SetUserVar(String, String),
+    /// `ReportCellSize`, asks for the cell size.
+    ReportCellSize,
+}
+
+/// A file sent through OSC 1337.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileTransfer {
+    /// Decoded file name, if the program sent one.
+    pub name: Option<String>,
+    /// Display the file as an image instead of downloading it.
+    pub inline: bool,
+    pub resize: Option<ResizeCommand>,
+    /// Base64 encoded file contents.
+    pub contents: Vec<u8>,
+}
 
-    if params.get("inline") != Some(&"1") {
-        return None;
+impl FileTransfer {
+    fn from_params(params: &Arguments, contents: &[u8]) -> FileTransfer {
+        let name = params
+            .get("name")
+            .and_then(|name| Base64.decode(name).ok())
+            .and_then(|name| String::from_utf8(name).ok());
+
+        FileTransfer {
+            name,
+            inline: params.get("inline") == Some(&"1"),
+            resize: resize_param(params),
+            contents: contents.to_vec(),
+        }
     }
 
-    let buffer = match Base64.decode(contents) {
-        Ok(buffer) => buffer,
-        Err(err) => {
-            tracing::warn!("Can't decode base64 data: {}", err);
-            return None;
+    /// Decode the file contents.
+    pub fn data(&self) -> Option<Vec<u8>> {
+        match Base64.decode(&self.contents) {
+            Ok(buffer) => Some(buffer),
+            Err(err) => {
+                tracing::warn!("Can't decode base64 data: {}", err);
+                None
+            }
         }
+    }
+
+    /// Size of the file once decoded.
+    pub fn decoded_len(&self) -> usize {
+        self.contents.len() / 4 * 3
+    }
+
+    /// Read the size of the image without decoding its pixels.
+    pub fn dimensions(&self) -> Option<(usize, usize)> {
+        let read = |buffer: &[u8]| {
+            image_rs::ImageReader::new(Cursor::new(buffer))
+                .with_guessed_format()
+                .ok()?
+                .into_dimensions()
+                .ok()
+        };
+
+        // Most formats store the size in the first bytes of the file.
+        let header_len = cmp::min(self.contents.len(), HEADER_SIZE) / 4 * 4;
+        let header = Base64.decode(&self.contents[..header_len]).ok();
+        let (width, height) = match header.as_deref().and_then(read) {
+            Some(dimensions) => dimensions,
+            None => read(&self.data()?)?,
+        };
+
+        Some((width as usize, height as usize))
+    }
+
+    /// Decode the file contents as an image, with all its frames if it is
+    /// an animated GIF, APNG or WebP. `size` is the size the frames are
+    /// scaled to afterwards.
+    pub fn graphic(&self, limits: &Images, size: (usize, usize)) -> Option<GraphicData> {
+        let buffer = self.data()?;
+        if let Some(mut graphics) = animated_graphic(&buffer, limits, size) {
+            graphics.resize = self.resize;
+            return Some(graphics);
+        }
+
+        let image = match image_rs::load_from_memory(&buffer) {
+            Ok(image) => image,
+            Err(err) => {
+                tracing::warn!("Can't load image: {}", err);
+                return None;
+            }
+        };
+
+        let mut graphics = GraphicData::from_dynamic_image(GraphicId(0), image);
+        graphics.resize = self.resize;
+        Some(graphics)
+    }
+}
+
+/// Decode the frames of an animated image. Returns `None` for still images.
+fn animated_graphic(
+    buffer: &[u8],
+    limits: &Images,
+    size: (usize, usize),
+) -> Option<GraphicData> {
+    let cursor = Cursor::new(buffer);
+    let frames = match image_rs::guess_format(buffer).ok()? {
+        ImageFormat::Gif => GifDecoder::new(cursor).ok()?.into_frames(),
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(cursor).ok()?;
+            if !decoder.is_apng().ok()? {
+                return None;
+            }
+            decoder.apng().ok()?.into_frames()
+        }
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(cursor).ok()?;
+            if !decoder.has_animation() {
+                return None;
+            }
+            decoder.into_frames()
+        }
+        _ => return None,
     };
 
-    let image = match image_rs::load_from_memory(&buffer) {
-        Ok(image) => image,
-        Err(err) => {
-            tracing::warn!("Can't load image: {}", err);
+    let min_delay = Duration::from_millis(1000 / cmp::max(limits.max_animation_fps, 1));
+    let mut first_frame = None;
+    let mut delays = Vec::new();
+    let mut pixels = Vec::new();
+    let mut memory = 0;
+
+    for frame in frames {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) => {
+                tracing::warn!("Can't decode animation frame: {}", err);
+                break;
+            }
+        };
+
+        // Browsers show frames without a delay for 100ms.
+        let delay = match Duration::from(frame.delay()) {
+            delay if delay < ZERO_DELAY_THRESHOLD => DEFAULT_FRAME_DELAY,
+            delay => delay,
+        };
+
+        // Frames are kept at both sizes until they are scaled.
+        let buffer = frame.into_buffer();
+        memory += cmp::max(buffer.len(), size.0 * size.1 * 4);
+        if memory > limits.max_animation_memory && first_frame.is_some() {
+            tracing::warn!("Animation exceeds max-animation-memory, frames are dropped");
+            break;
+        }
+
+        delays.push(cmp::max(delay, min_delay));
+        if first_frame.is_none() {
+            first_frame = Some(buffer);
+        } else {
+            pixels.push(buffer.into_raw());
+        }
+    }
+
+    let image = DynamicImage::ImageRgba8(first_frame?);
+    let mut graphic = GraphicData::from_dynamic_image(GraphicId(0), image);
+    if !pixels.is_empty() {
+        graphic.animation = Some(Animation {
+            delays,
+            frames: pixels,
+        });
+    }
+
+    Some(graphic)
+}
+
+/// Parse the OSC 1337 parameters.
+pub fn parse(params: &[&[u8]]) -> Option<Command> {
+    let first = params.get(1)?;
+
+    if first.starts_with(b"File=") {
+        let (args, contents) = param_values(params)?;
+        return Some(Command::File(FileTransfer::from_params(&args, contents)));
+    }
+
+    if first.starts_with(b"MultipartFile=") {
+        let (args, _) = arguments(params, b"MultipartFile=")?;
+        return Some(Command::MultipartFile(FileTransfer::from_params(
+            &args,
+            &[],
+        )));
+    }
+
+    if let Some(part) = first.strip_prefix(b"FilePart=") {
+        return Some(Command::FilePart(part.to_vec()));
+    }
+
+    match *first {
+        b"FileEnd" => return Some(Command::FileEnd),
+        b"ReportCellSize" => return Some(Command::ReportCellSize),
+        _ => (),
+    }
+
+    if let Some(variable) = first.strip_prefix(b"SetUserVar=") {
+        let separator = variable.iter().position(|&b| b == b'=')?;
+        let (name, value) = variable.split_at(separator);
+        let name = str::from_utf8(name).ok()?;
+        let value = Base64.decode(&value[1..]).ok()?;
+        let value = String::from_utf8(value).ok()?;
+        if name.is_empty() {
             return None;
         }
-    };
 
-    let mut graphics = GraphicData::from_dynamic_image(GraphicId(0), image);
-    graphics.resize = resize_param(&params);
-    Some(graphics)
+        return Some(Command::SetUserVar(name.to_owned(), value));
+    }
+
+    None
 }
 
 /// Extract parameter values.
@@ -58,20 +273,24 @@ pub fn parse(params: &[&[u8]]) -> Option<GraphicData> {
 /// The `File=` string is found in the first parameter, and the file contents are
 /// appended in the last one. We have to split these parameter to get the expected
 /// data.
-fn param_values<'a>(
+fn param_values<'a>(params: &[&'a [u8]]) -> Option<(Arguments<'a>, &'a [u8])> {
+    let (map, contents) = arguments(params, b"File=")?;
+    contents.map(|c| (map, c))
+}
+
+/// Split the `key=value` arguments of a command starting with `prefix`,
+/// along with the contents found after the first `:` of the last one.
+fn arguments<'a>(
     params: &[&'a [u8]],
-) -> Option<(FxHashMap<&'a str, &'a str>, &'a [u8])> {
+    prefix: &[u8],
+) -> Option<(Arguments<'a>, Option<&'a [u8]>)> {
     let mut map = FxHashMap::default();
     let mut contents = None;
 
     for (index, mut param) in params.iter().skip(1).copied().enumerate() {
-        // First parameter should start with "File="
+        // First parameter should start with the command prefix.
         if index == 0 {
-            if !param.starts_with(&b"File="[..]) {
-                return None;
-            }
-
-            param = &param[5..];
+            param = param.strip_prefix(prefix)?;
         }
 
         if let Some(separator) = param.iter().position(|&b| b == b'=') {
@@ -94,7 +313,7 @@ fn param_values<'a>(
         }
     }
 
-    contents.map(|c| (map, c))
+    Some((map, contents))
 }
 
 /// Compute the resize operation from the OSC parameters.
@@ -191,3 +410,125 @@ fn resize_params() {
     assert_resize!("10", "20", Cells(10), Cells(20));
     assert_resize!("10%", "50px", WindowPercent(10), Pixels(50));
 }
+
+#[test]
+fn parse_osc1337_file_transfers() {
+    // "a.txt" encoded in base64.
+    let params = [
+        b"1337".as_ref(),
+        b"File=name=YS50eHQ=".as_ref(),
+        b"size=2:aGk=".as_ref(),
+    ];
+    let transfer = match parse(&params) {
+        Some(Command::File(transfer)) => transfer,
+        command => panic!("unexpected command {command:?}"),
+    };
+    assert_eq!(transfer.name.as_deref(), Some("a.txt"));
+    assert!(!transfer.inline);
+    assert_eq!(transfer.data(), Some(b"hi".to_vec()));
+
+    let params = [
+        b"1337".as_ref(),
+        b"MultipartFile=inline=1".as_ref(),
+        b"width=2".as_ref(),
+    ];
+    let transfer = match parse(&params) {
+        Some(Command::MultipartFile(transfer)) => transfer,
+        command => panic!("unexpected command {command:?}"),
+    };
+    assert!(transfer.inline);
+    assert_eq!(transfer.name, None);
+    assert_eq!(transfer.resize.unwrap().width, ResizeParameter::Cells(2));
+    assert!(transfer.contents.is_empty());
+
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"FilePart=AAAA".as_ref()]),
+        Some(Command::FilePart(b"AAAA".to_vec()))
+    );
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"FileEnd".as_ref()]),
+        Some(Command::FileEnd)
+    );
+}
+
+#[test]
+fn parse_osc1337_commands() {
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"SetUserVar=branch=bWFpbg==".as_ref()]),
+        Some(Command::SetUserVar(
+            String::from("branch"),
+            String::from("main")
+        ))
+    );
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"SetUserVar=empty=".as_ref()]),
+        Some(Command::SetUserVar(String::from("empty"), String::new()))
+    );
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"SetUserVar=novalue".as_ref()]),
+        None
+    );
+    assert_eq!(
+        parse(&[b"1337".as_ref(), b"ReportCellSize".as_ref()]),
+        Some(Command::ReportCellSize)
+    );
+    assert_eq!(parse(&[b"1337".as_ref(), b"StealFocus".as_ref()]), None);
+    assert_eq!(parse(&[b"1337".as_ref()]), None);
+}
+
+#[test]
+fn decode_animated_gif() {
+    use image_rs::codecs::gif::GifEncoder;
+    use image_rs::{Delay, Frame, Rgba, RgbaImage};
+
+    let mut gif = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut gif);
+        for (color, delay) in [([255, 0, 0, 255], 0), ([0, 0, 255, 255], 500)] {
+            let buffer = RgbaImage::from_pixel(2, 2, Rgba(color));
+            let delay = Delay::from_numer_denom_ms(delay, 1);
+            encoder
+                .encode_frame(Frame::from_parts(buffer, 0, 0, delay))
+                .unwrap();
+        }
+    }
+
+    let limits = Images {
+        max_animation_fps: 4,
+        ..Images::default()
+    };
+    let graphic = animated_graphic(&gif, &limits, (2, 2)).unwrap();
+    assert_eq!((graphic.width, graphic.height), (2, 2));
+    assert_eq!(&graphic.pixels[..4], &[255, 0, 0, 255]);
+    let animation = graphic.animation.unwrap();
+    assert_eq!(animation.frames.len(), 1);
+    assert_eq!(&animation.frames[0][..4], &[0, 0, 255, 255]);
+    // The first delay is raised to the FPS limit.
+    assert_eq!(
+        animation.delays,
+        [Duration::from_millis(250), Duration::from_millis(500)]
+    );
+
+    // Frames beyond the memory limit are dropped.
+    let limits = Images {
+        max_animation_memory: 16,
+        ..Images::default()
+    };
+    let graphic = animated_graphic(&gif, &limits, (2, 2)).unwrap();
+    assert_eq!(graphic.animation, None);
+
+    // The limit applies to the frames once scaled up, 256 bytes each.
+    let limits = Images {
+        max_animation_memory: 300,
+        ..Images::default()
+    };
+    let graphic = animated_graphic(&gif, &limits, (2, 2)).unwrap();
+    assert!(graphic.animation.is_some());
+    let graphic = animated_graphic(&gif, &limits, (8, 8)).unwrap();
+    assert_eq!(graphic.animation, None);
+
+    let png = Base64.decode(
+        "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVR4nGP4z8AAAAMBAQDJ/pLvAAAAAElFTkSuQmCC",
+    );
+    assert_eq!(animated_graphic(&png.unwrap(), &limits, (1, 1)), None);
+}
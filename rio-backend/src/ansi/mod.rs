@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 pub mod charset;
 pub mod control;
+pub mod decoder;
 pub mod graphics;
 pub mod iterm2_image_protocol;
 pub mod mode;
@@ -111,3 +112,32 @@ pub enum KeyboardModesApplyBehavior {
     /// Remove the given flags from the active ones.
     Difference,
 }
+
+/// Color scheme reported to applications, see `CSI ? 996 n` and mode 2031.
+#[repr(u8)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Dark = 1,
+    Light = 2,
+}
+
+impl ColorScheme {
+    /// Scheme matching a background color by its relative luminance.
+    pub fn from_background(color: [f32; 4]) -> ColorScheme {
+        let luminance = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+        if luminance > 0.5 {
+            ColorScheme::Light
+        } else {
+            ColorScheme::Dark
+        }
+    }
+}
+
+/// Component of the tab color set through iTerm2's `OSC 6 ; 1 ; bg`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabColorComponent {
+    Red,
+    Green,
+    Blue,
+}
@@ -65,10 +65,12 @@ impl PrivateMode {
             1005 => Self::Named(NamedPrivateMode::Utf8Mouse),
             1006 => Self::Named(NamedPrivateMode::SgrMouse),
             1007 => Self::Named(NamedPrivateMode::AlternateScroll),
+            1016 => Self::Named(NamedPrivateMode::SgrPixelsMouse),
             1042 => Self::Named(NamedPrivateMode::UrgencyHints),
             1049 => Self::Named(NamedPrivateMode::SwapScreenAndSetRestoreCursor),
             2004 => Self::Named(NamedPrivateMode::BracketedPaste),
             2026 => Self::Named(NamedPrivateMode::SyncUpdate),
+            2031 => Self::Named(NamedPrivateMode::ColorSchemeUpdates),
             _ => Self::Unknown(mode),
         }
     }
@@ -115,11 +117,15 @@ pub enum NamedPrivateMode {
     Utf8Mouse = 1005,
     SgrMouse = 1006,
     AlternateScroll = 1007,
+    /// SGR mouse reports with coordinates in pixels instead of cells.
+    SgrPixelsMouse = 1016,
     UrgencyHints = 1042,
     SwapScreenAndSetRestoreCursor = 1049,
     BracketedPaste = 2004,
     /// The mode is handled automatically by [`Processor`].
     SyncUpdate = 2026,
+    /// Report `CSI ? 997 ; 1|2 n` whenever the color scheme changes.
+    ColorSchemeUpdates = 2031,
 }
 
 /// Mode for clearing line.
@@ -403,8 +403,10 @@ impl Parser {
         Ok(())
     }
 
+    /// Complete the active command, so the size and the palette of the
+    /// graphic are final.
     #[inline]
-    fn finish_command(&mut self) -> Result<(), Error> {
+    pub fn finish_command(&mut self) -> Result<(), Error> {
         if let Some(command_parser) = self.command_parser.take() {
             command_parser.finish(self)?;
         }
@@ -515,6 +517,16 @@ impl Parser {
         Ok(())
     }
 
+    /// Size of the graphic, in pixels.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Color registers defined so far.
+    pub fn palette(&self) -> &[ColorRgb] {
+        &self.color_registers
+    }
+
     /// Returns the final graphic to append to the grid, with the palette
     /// built in the process.
     pub fn finish(mut self) -> Result<(GraphicData, Vec<ColorRgb>), Error> {
@@ -555,6 +567,7 @@ impl Parser {
             pixels: rgba_pixels,
             is_opaque,
             resize: None,
+            animation: None,
         };
 
         Ok((data, self.color_registers))
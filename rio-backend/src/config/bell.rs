@@ -0,0 +1,103 @@
+use crate::config::colors::{deserialize_to_arr, ColorArray};
+use crate::config::Shell;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum BellAnimation {
+    #[serde(alias = "linear")]
+    Linear,
+    #[serde(alias = "easeout")]
+    EaseOut,
+    #[serde(alias = "easeoutquad")]
+    EaseOutQuad,
+    #[serde(alias = "easeoutcubic")]
+    EaseOutCubic,
+    #[default]
+    #[serde(alias = "easeoutexpo")]
+    EaseOutExpo,
+}
+
+impl BellAnimation {
+    /// Intensity of the flash at `progress` (from 0 to 1) of its duration,
+    /// starting at 1 and fading out to 0.
+    pub fn intensity(&self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        let eased = match self {
+            BellAnimation::Linear => progress,
+            BellAnimation::EaseOut => 1.0 - (1.0 - progress).powf(1.7),
+            BellAnimation::EaseOutQuad => 1.0 - (1.0 - progress).powi(2),
+            BellAnimation::EaseOutCubic => 1.0 - (1.0 - progress).powi(3),
+            BellAnimation::EaseOutExpo => {
+                if progress >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * progress)
+                }
+            }
+        };
+
+        1.0 - eased
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Bell {
+    /// Duration of the visual flash in milliseconds, 0 disables it.
+    #[serde(default = "u64::default")]
+    pub duration: u64,
+    #[serde(default = "BellAnimation::default")]
+    pub animation: BellAnimation,
+    #[serde(
+        deserialize_with = "deserialize_to_arr",
+        default = "default_bell_color",
+        skip_serializing
+    )]
+    pub color: ColorArray,
+    /// Program spawned every time the bell rings.
+    #[serde(default = "Option::default")]
+    pub command: Option<Shell>,
+}
+
+#[inline]
+fn default_bell_color() -> ColorArray {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+impl Default for Bell {
+    fn default() -> Bell {
+        Bell {
+            duration: 0,
+            animation: BellAnimation::default(),
+            color: default_bell_color(),
+            command: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bell_animation_intensity() {
+        for animation in [
+            BellAnimation::Linear,
+            BellAnimation::EaseOut,
+            BellAnimation::EaseOutQuad,
+            BellAnimation::EaseOutCubic,
+            BellAnimation::EaseOutExpo,
+        ] {
+            assert_eq!(animation.intensity(0.0), 1.0);
+            assert_eq!(animation.intensity(1.0), 0.0);
+            assert_eq!(animation.intensity(2.0), 0.0);
+            assert!(animation.intensity(0.25) > animation.intensity(0.75));
+        }
+
+        assert_eq!(BellAnimation::Linear.intensity(0.5), 0.5);
+        // Ease out curves fade faster at the beginning.
+        assert!(
+            BellAnimation::EaseOutExpo.intensity(0.5)
+                < BellAnimation::Linear.intensity(0.5)
+        );
+    }
+}
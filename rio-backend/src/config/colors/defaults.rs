@@ -147,6 +147,20 @@ pub fn split() -> ColorArray {
         .to_arr()
 }
 
+#[inline]
+pub fn split_broadcast() -> ColorArray {
+    ColorBuilder::from_hex(String::from("#ffa133"), Format::SRGB0_1)
+        .unwrap()
+        .to_arr()
+}
+
+#[inline]
+pub fn split_read_only() -> ColorArray {
+    ColorBuilder::from_hex(String::from("#ee4b4b"), Format::SRGB0_1)
+        .unwrap()
+        .to_arr()
+}
+
 #[inline]
 pub fn dim_blue() -> ColorArray {
     ColorBuilder::from_hex(String::from("#0E91B7"), Format::SRGB0_1)
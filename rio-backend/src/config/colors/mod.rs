@@ -242,6 +242,18 @@ pub struct Colors {
     pub selection_foreground: ColorArray,
     #[serde(default = "defaults::split", deserialize_with = "deserialize_to_arr")]
     pub split: ColorArray,
+    #[serde(
+        default = "defaults::split_broadcast",
+        deserialize_with = "deserialize_to_arr",
+        rename = "split-broadcast"
+    )]
+    pub split_broadcast: ColorArray,
+    #[serde(
+        default = "defaults::split_read_only",
+        deserialize_with = "deserialize_to_arr",
+        rename = "split-read-only"
+    )]
+    pub split_read_only: ColorArray,
     #[serde(
         default = "defaults::search_match_background",
         deserialize_with = "deserialize_to_arr",
@@ -285,6 +297,8 @@ impl Default for Colors {
             tabs_foreground: defaults::tabs_foreground(),
             cursor: defaults::cursor(),
             split: defaults::split(),
+            split_broadcast: defaults::split_broadcast(),
+            split_read_only: defaults::split_read_only(),
             vi_cursor: defaults::vi_cursor(),
             black: defaults::black(),
             cyan: defaults::cyan(),
@@ -633,6 +647,15 @@ where
     }
 }
 
+pub fn deserialize_to_arr_opt<'de, D>(
+    deserializer: D,
+) -> Result<Option<ColorArray>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    deserialize_to_arr(deserializer).map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,6 +10,26 @@ pub fn default_line_height() -> f32 {
     1.0
 }
 
+#[inline]
+pub fn default_inactive_split_opacity() -> f32 {
+    1.0
+}
+
+#[inline]
+pub fn default_active_split_border_width() -> f32 {
+    1.0
+}
+
+#[inline]
+pub fn default_paste_max_size() -> usize {
+    16 * 1024
+}
+
+#[inline]
+pub fn default_drop_template() -> String {
+    String::from("{paths} ")
+}
+
 #[inline]
 pub fn default_cursor_interval() -> u64 {
     800
@@ -189,6 +209,14 @@ pub fn default_config_file_content() -> String {
 # Example:
 # line-height = 1.2
 
+# Font size scope
+#
+# Panes that change along with the focused split when the font size
+# is increased, decreased or reset: 'split', 'tab' or 'window'.
+# Default is `split`
+#
+# font-size-scope = "tab"
+
 # Startup directory
 #
 # Directory the shell is started in. If this is unset the working
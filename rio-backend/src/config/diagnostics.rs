@@ -0,0 +1,216 @@
+// Config issues point at the line of the file where they were found:
+//
+// unknown key `navgation`, did you mean `navigation`?
+//   --> ~/.config/rio/config.toml:3:1
+//    |
+//  3 | [navgation]
+//    |  ^^^^^^^^^
+
+use crate::config::import::Sources;
+use crate::config::Config;
+use std::ops::Range;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// Keys still accepted under their old name, with the name to use instead.
+const DEPRECATED_KEYS: &[(&str, &str)] =
+    &[("hide-cursor-when-typing", "hide-mouse-cursor-when-typing")];
+
+/// Minimum similarity for a key to be suggested.
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// Describe `message` with an excerpt of the file at `span`.
+pub fn describe(
+    path: &Path,
+    content: &str,
+    span: Option<Range<usize>>,
+    message: &str,
+) -> String {
+    let span = match span {
+        Some(span) => span,
+        None => return format!("{message}\n  --> {}", path.display()),
+    };
+
+    let start = span.start.min(content.len());
+    let line_start = content[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |index| start + index);
+    let line = content[..start].matches('\n').count() + 1;
+    let column = content[line_start..start].chars().count() + 1;
+    let width = content[start..span.end.clamp(start, line_end)]
+        .chars()
+        .count()
+        .max(1);
+
+    let number = line.to_string();
+    let gutter = " ".repeat(number.len());
+    format!(
+        "{message}\n{gutter}--> {}:{line}:{column}\n{gutter} |\n{number} | {}\n{gutter} | {}{}",
+        path.display(),
+        content[line_start..line_end].trim_end_matches('\r'),
+        " ".repeat(column - 1),
+        "^".repeat(width),
+    )
+}
+
+pub fn toml_error(path: &Path, content: &str, err: &toml::de::Error) -> String {
+    describe(path, content, err.span(), err.message())
+}
+
+/// Describe `message` at the key `keys` of the file.
+pub fn at_key(path: &Path, content: &str, keys: &[&str], message: &str) -> String {
+    describe(path, content, key_span(content, keys), message)
+}
+
+/// The merged config loses the location of its values, so the error is
+/// searched in each file on its own.
+pub fn merge_error(err: &toml::de::Error, sources: &Sources) -> String {
+    for (path, content) in sources.files.iter().rev() {
+        if let Err(file_err) = toml::from_str::<Config>(content) {
+            if file_err.message() == err.message() {
+                return toml_error(path, content, &file_err);
+            }
+        }
+    }
+
+    format!("error parsing: {}", err.message())
+}
+
+/// Warnings for the keys of `table` that are ignored when it is decoded
+/// into `config`, and for deprecated keys.
+pub fn warnings(table: &Table, config: &Config, sources: &Sources) -> Vec<String> {
+    let mut unknown = Vec::new();
+    find_unknown_keys(&mut table.clone(), &mut Vec::new(), config, &mut unknown);
+
+    let known = toml::Value::try_from(Config::default()).ok();
+    let mut warnings = Vec::new();
+    for keys in unknown {
+        let (last, parents) = match keys.split_last() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        let mut message = format!("unknown key `{}`", keys.join("."));
+        let siblings = match (known.as_ref().and_then(Value::as_table), parents) {
+            (Some(known), []) => Some(known),
+            (Some(known), parents) => get(known, parents).and_then(Value::as_table),
+            (None, _) => None,
+        };
+        if let Some(suggestion) = siblings.and_then(|siblings| suggest(last, siblings)) {
+            message.push_str(&format!(", did you mean `{suggestion}`?"));
+        }
+
+        warnings.push(locate(sources, &keys, &message));
+    }
+
+    for (old, new) in DEPRECATED_KEYS {
+        let keys: Vec<&str> = old.split('.').collect();
+        if get(table, &keys).is_some() {
+            let message = format!("`{old}` is deprecated, use `{new}` instead");
+            warnings.push(locate(sources, &keys, &message));
+        }
+    }
+
+    warnings
+}
+
+/// A key is unknown when replacing its value doesn't change the config.
+fn find_unknown_keys(
+    root: &mut Table,
+    keys: &mut Vec<String>,
+    config: &Config,
+    unknown: &mut Vec<Vec<String>>,
+) {
+    let names: Vec<String> = match get_mut(root, keys) {
+        Some(Value::Table(table)) => table.keys().cloned().collect(),
+        _ if keys.is_empty() => root.keys().cloned().collect(),
+        _ => return,
+    };
+
+    for name in names {
+        keys.push(name);
+
+        // No option accepts an array with an empty table and stays the same.
+        let probe = Value::Array(vec![Value::Table(Table::new())]);
+        let original = match replace(root, keys, probe) {
+            Some(original) => original,
+            None => {
+                keys.pop();
+                continue;
+            }
+        };
+
+        let ignored = match Value::Table(root.clone()).try_into::<Config>() {
+            Ok(probed) => probed == *config,
+            Err(_) => false,
+        };
+
+        let is_table = original.is_table();
+        replace(root, keys, original);
+        if ignored {
+            unknown.push(keys.clone());
+        } else if is_table {
+            find_unknown_keys(root, keys, config, unknown);
+        }
+
+        keys.pop();
+    }
+}
+
+fn suggest<'a>(key: &str, siblings: &'a Table) -> Option<&'a str> {
+    siblings
+        .keys()
+        .map(|sibling| (strsim::jaro_winkler(key, sibling), sibling))
+        .filter(|(similarity, _)| *similarity >= SUGGESTION_THRESHOLD)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, sibling)| sibling.as_str())
+}
+
+/// Describe `message` in the last file that sets `keys`, the key might
+/// also come from a command line override.
+fn locate<S: AsRef<str>>(sources: &Sources, keys: &[S], message: &str) -> String {
+    let keys: Vec<&str> = keys.iter().map(|key| key.as_ref()).collect();
+    for (path, content) in sources.files.iter().rev() {
+        if let Some(span) = key_span(content, &keys) {
+            return describe(path, content, Some(span), message);
+        }
+    }
+
+    format!("{message}\n  --> command line option")
+}
+
+fn key_span(content: &str, keys: &[&str]) -> Option<Range<usize>> {
+    let document = toml_edit::ImDocument::parse(content).ok()?;
+    let (last, parents) = keys.split_last()?;
+    let mut table: &dyn toml_edit::TableLike = document.as_table();
+    for key in parents {
+        table = table.get(key)?.as_table_like()?;
+    }
+
+    table.get_key_value(last)?.0.span()
+}
+
+fn get<'a, S: AsRef<str>>(table: &'a Table, keys: &[S]) -> Option<&'a Value> {
+    let (last, parents) = keys.split_last()?;
+    let mut table = table;
+    for key in parents {
+        table = table.get(key.as_ref())?.as_table()?;
+    }
+
+    table.get(last.as_ref())
+}
+
+fn get_mut<'a>(table: &'a mut Table, keys: &[String]) -> Option<&'a mut Value> {
+    let (last, parents) = keys.split_last()?;
+    let mut table = table;
+    for key in parents {
+        table = table.get_mut(key)?.as_table_mut()?;
+    }
+
+    table.get_mut(last)
+}
+
+fn replace(table: &mut Table, keys: &[String], value: Value) -> Option<Value> {
+    get_mut(table, keys).map(|current| std::mem::replace(current, value))
+}
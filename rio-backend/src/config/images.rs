@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct Images {
+    /// Images with more pixels are not displayed.
+    #[serde(default = "default_max_pixels", rename = "max-pixels")]
+    pub max_pixels: usize,
+    /// Image files larger than this, in bytes, are not displayed.
+    #[serde(default = "default_max_bytes", rename = "max-bytes")]
+    pub max_bytes: usize,
+    /// Memory used by the frames of an animated image, in bytes. Frames
+    /// beyond it are dropped.
+    #[serde(
+        default = "default_max_animation_memory",
+        rename = "max-animation-memory"
+    )]
+    pub max_animation_memory: usize,
+    /// Maximum frames per second of animated images.
+    #[serde(default = "default_max_animation_fps", rename = "max-animation-fps")]
+    pub max_animation_fps: u64,
+}
+
+#[inline]
+fn default_max_pixels() -> usize {
+    // 10000x5000, or a bit less than 200MiB of RGBA pixels.
+    50_000_000
+}
+
+#[inline]
+fn default_max_bytes() -> usize {
+    // 64MiB.
+    0x400_0000
+}
+
+#[inline]
+fn default_max_animation_memory() -> usize {
+    // 256MiB.
+    0x1000_0000
+}
+
+#[inline]
+fn default_max_animation_fps() -> u64 {
+    30
+}
+
+impl Default for Images {
+    fn default() -> Images {
+        Images {
+            max_pixels: default_max_pixels(),
+            max_bytes: default_max_bytes(),
+            max_animation_memory: default_max_animation_memory(),
+            max_animation_fps: default_max_animation_fps(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// Config files can import other files, the imports are merged in order and
+// the importing file is merged on top of them:
+//
+// import = ["~/.config/rio/colors.toml", "local.toml"]
+//
+// Tables are merged key by key, any other value (arrays included) replaces
+// the previous one.
+
+use crate::config::diagnostics;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Maximum depth of nested imports.
+const MAX_IMPORT_DEPTH: usize = 5;
+
+/// Files read while loading the config.
+#[derive(Debug, Default)]
+pub struct Sources {
+    /// Every imported file, found or not, so they can be watched for changes.
+    pub imported: Vec<PathBuf>,
+    /// Path and content of the files read, in the order they were merged.
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Read `path` with its imports merged in.
+pub fn read_with_imports(path: &Path, sources: &mut Sources) -> Result<Table, String> {
+    read(path, sources, &mut Vec::new())
+}
+
+/// `chain` holds the canonical paths of the files importing `path`.
+fn read(
+    path: &Path,
+    sources: &mut Sources,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Table, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {err}", path.display()))?;
+    let mut table = match toml::from_str::<Table>(&content) {
+        Ok(table) => table,
+        Err(err) => return Err(diagnostics::toml_error(path, &content, &err)),
+    };
+
+    let imports = match table.remove("import") {
+        Some(Value::Array(imports)) => imports,
+        Some(_) => {
+            let message = "import must be an array of paths";
+            return Err(diagnostics::at_key(path, &content, &["import"], message));
+        }
+        None => {
+            sources.files.push((path.to_path_buf(), content));
+            return Ok(table);
+        }
+    };
+
+    if chain.len() >= MAX_IMPORT_DEPTH {
+        let message = format!("imports are nested more than {MAX_IMPORT_DEPTH} levels");
+        return Err(diagnostics::at_key(path, &content, &["import"], &message));
+    }
+
+    chain.push(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+    let mut merged = Table::new();
+    for import in imports {
+        let import = match import {
+            Value::String(import) => import,
+            _ => {
+                let message = "import must be an array of paths";
+                return Err(diagnostics::at_key(path, &content, &["import"], message));
+            }
+        };
+
+        let import_path = resolve(path, &import);
+        sources.imported.push(import_path.clone());
+
+        // A missing import is not an error, so machine specific files can be
+        // listed everywhere.
+        if !import_path.exists() {
+            tracing::warn!("config import not found: {}", import_path.display());
+            continue;
+        }
+
+        let canonical = import_path.canonicalize().unwrap_or(import_path.clone());
+        if chain.contains(&canonical) {
+            let mut files = chain
+                .iter()
+                .skip_while(|file| **file != canonical)
+                .map(|file| file.display().to_string())
+                .collect::<Vec<String>>();
+            files.push(canonical.display().to_string());
+            let message = format!("import cycle: {}", files.join(" -> "));
+            return Err(diagnostics::at_key(path, &content, &["import"], &message));
+        }
+
+        merge(&mut merged, read(&import_path, sources, chain)?);
+    }
+    chain.pop();
+
+    merge(&mut merged, table);
+    sources.files.push((path.to_path_buf(), content));
+    Ok(merged)
+}
+
+/// Paths are relative to the importing file, `~/` is the home directory.
+fn resolve(path: &Path, import: &str) -> PathBuf {
+    let import_path = match import.strip_prefix("~/") {
+        Some(relative) => match dirs::home_dir() {
+            Some(home) => home.join(relative),
+            None => PathBuf::from(import),
+        },
+        None => PathBuf::from(import),
+    };
+
+    match path.parent() {
+        Some(parent) if import_path.is_relative() => parent.join(import_path),
+        _ => import_path,
+    }
+}
+
+/// Merge `other` into `base`, values from `other` take precedence.
+pub fn merge(base: &mut Table, other: Table) {
+    for (key, value) in other {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => {
+                merge(base_table, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Parse a `key=value` override, dotted keys set nested tables. Values that
+/// are not valid TOML are taken as strings, so `theme=dracula` works.
+pub fn parse_override(option: &str) -> Result<Table, String> {
+    let (key, value) = match option.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => (key.trim(), value.trim()),
+        _ => return Err(format!("invalid override {option}, expected key=value")),
+    };
+
+    toml::from_str::<Table>(&format!("{key} = {value}"))
+        .or_else(|_| {
+            let value = Value::String(value.to_owned());
+            toml::from_str::<Table>(&format!("{key} = {value}"))
+        })
+        .map_err(|_| format!("invalid override {option}, expected key=value"))
+}
@@ -1,25 +1,34 @@
+pub mod bell;
 pub mod bindings;
 pub mod colors;
 pub mod defaults;
+pub mod diagnostics;
+pub mod images;
+pub mod import;
 pub mod keyboard;
 pub mod navigation;
+pub mod profile;
 pub mod renderer;
 pub mod theme;
 pub mod title;
 pub mod window;
 
 use crate::ansi::CursorShape;
+use crate::config::bell::Bell;
 use crate::config::bindings::Bindings;
 use crate::config::defaults::*;
+use crate::config::images::Images;
 use crate::config::keyboard::Keyboard;
 use crate::config::navigation::Navigation;
+use crate::config::profile::Profile;
 use crate::config::renderer::Renderer;
 use crate::config::title::Title;
 use crate::config::window::Window;
 use colors::Colors;
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::{default::Default, fs::File};
 use sugarloaf::font::fonts::SugarloafFonts;
 use theme::{AdaptiveColors, AdaptiveTheme, Theme};
@@ -59,6 +68,9 @@ pub struct PlatformConfig {
 pub struct Scroll {
     pub multiplier: f64,
     pub divider: f64,
+    /// Draw a scrollbar on each split while scrolling.
+    #[serde(default)]
+    pub scrollbar: bool,
 }
 
 impl Default for Scroll {
@@ -66,6 +78,7 @@ impl Default for Scroll {
         Scroll {
             multiplier: 3.0,
             divider: 1.0,
+            scrollbar: false,
         }
     }
 }
@@ -90,6 +103,84 @@ impl Default for Developer {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Paste {
+    /// Ask for confirmation before pasting multiple lines, control
+    /// characters or more than `max-size` bytes.
+    #[serde(default = "bool::default")]
+    pub guard: bool,
+    #[serde(default = "default_paste_max_size", rename = "max-size")]
+    pub max_size: usize,
+    /// How dropped files are written, as paths or `file://` URIs.
+    #[serde(default, rename = "drop-format")]
+    pub drop_format: DropFormat,
+    /// Text pasted for dropped files, `{paths}` is replaced by the
+    /// shell-quoted list.
+    #[serde(default = "default_drop_template", rename = "drop-template")]
+    pub drop_template: String,
+}
+
+impl Default for Paste {
+    fn default() -> Paste {
+        Paste {
+            guard: false,
+            max_size: default_paste_max_size(),
+            drop_format: DropFormat::default(),
+            drop_template: default_drop_template(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Search {
+    /// Save the search history in the data directory.
+    #[serde(default = "default_bool_true", rename = "persist-history")]
+    pub persist_history: bool,
+    /// Keep a history for each working directory reported through OSC 7.
+    #[serde(default = "bool::default", rename = "per-directory-history")]
+    pub per_directory_history: bool,
+    /// Named regexes recalled from the search bar.
+    #[serde(default = "Vec::default")]
+    pub presets: Vec<SearchPreset>,
+}
+
+impl Default for Search {
+    fn default() -> Search {
+        Search {
+            persist_history: true,
+            per_directory_history: false,
+            presets: Vec::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SearchPreset {
+    pub name: String,
+    pub regex: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum DropFormat {
+    #[default]
+    #[serde(alias = "path")]
+    Path,
+    #[serde(alias = "uri")]
+    Uri,
+}
+
+/// Panes that follow a font size change of the focused split.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum FontSizeScope {
+    #[default]
+    #[serde(alias = "split")]
+    Split,
+    #[serde(alias = "tab")]
+    Tab,
+    #[serde(alias = "window")]
+    Window,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Config {
     #[serde(default)]
@@ -110,8 +201,12 @@ pub struct Config {
     pub title: Title,
     #[serde(default = "default_working_dir", rename = "working-dir")]
     pub working_dir: Option<String>,
+    #[serde(default = "Option::default", rename = "download-dir")]
+    pub download_dir: Option<String>,
     #[serde(rename = "line-height", default = "default_line_height")]
     pub line_height: f32,
+    #[serde(rename = "font-size-scope", default)]
+    pub font_size_scope: FontSizeScope,
     #[serde(default = "String::default")]
     pub theme: String,
     #[serde(default = "Scroll::default")]
@@ -157,6 +252,39 @@ pub struct Config {
     pub hide_cursor_when_typing: bool,
     #[serde(default = "Renderer::default")]
     pub renderer: Renderer,
+    #[serde(default = "Bell::default")]
+    pub bell: Bell,
+    #[serde(default = "Images::default")]
+    pub images: Images,
+    #[serde(default = "Paste::default")]
+    pub paste: Paste,
+    #[serde(default = "Search::default")]
+    pub search: Search,
+    #[serde(default = "Vec::default")]
+    pub profiles: Vec<Profile>,
+    #[serde(
+        default = "default_inactive_split_opacity",
+        rename = "inactive-split-opacity"
+    )]
+    pub inactive_split_opacity: f32,
+    #[serde(
+        default = "Option::default",
+        rename = "active-split-border",
+        deserialize_with = "colors::deserialize_to_arr_opt",
+        skip_serializing
+    )]
+    pub active_split_border: Option<colors::ColorArray>,
+    #[serde(
+        default = "default_active_split_border_width",
+        rename = "active-split-border-width"
+    )]
+    pub active_split_border_width: f32,
+    /// Files imported by the config, watched for changes.
+    #[serde(skip)]
+    pub imported_files: Vec<PathBuf>,
+    /// Unknown and deprecated keys found in the config.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -183,9 +311,94 @@ pub fn config_dir_path() -> PathBuf {
     home_dir.join("AppData").join("Local").join("rio")
 }
 
+/// Config file and `key=value` overrides passed through the command line.
+#[derive(Debug, Default)]
+struct LoadOptions {
+    config_file: Option<PathBuf>,
+    overrides: Vec<String>,
+}
+
+static LOAD_OPTIONS: OnceLock<LoadOptions> = OnceLock::new();
+
+fn load_overrides() -> &'static [String] {
+    match LOAD_OPTIONS.get() {
+        Some(options) => &options.overrides,
+        None => &[],
+    }
+}
+
+/// Load the config from `config_file` instead of the default location and
+/// apply `overrides` on top of it, every time the config is loaded.
+pub fn set_load_options(config_file: Option<PathBuf>, overrides: Vec<String>) {
+    let config_file = config_file.map(|path| std::path::absolute(&path).unwrap_or(path));
+    let _ = LOAD_OPTIONS.set(LoadOptions {
+        config_file,
+        overrides,
+    });
+}
+
 #[inline]
 pub fn config_file_path() -> PathBuf {
-    config_dir_path().join("config.toml")
+    match LOAD_OPTIONS
+        .get()
+        .and_then(|options| options.config_file.clone())
+    {
+        Some(path) => path,
+        None => config_dir_path().join("config.toml"),
+    }
+}
+
+/// Make `theme` the theme of the config file, the default theme removes
+/// the `theme` key so the colors of the config are used again.
+pub fn save_theme(theme: &str) -> Result<(), String> {
+    let path = config_file_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("unable to read {}: {err}", path.display())),
+    };
+
+    let content = match set_theme(&content, theme) {
+        Ok(content) => content,
+        Err(err) => {
+            return Err(diagnostics::describe(
+                &path,
+                &content,
+                err.span(),
+                err.message(),
+            ))
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    std::fs::write(&path, content)
+        .map_err(|err| format!("unable to write {}: {err}", path.display()))
+}
+
+/// Set the top level `theme` of `content`, keeping the rest of the file
+/// (comments included) untouched.
+fn set_theme(content: &str, theme: &str) -> Result<String, toml_edit::TomlError> {
+    let mut document = content.parse::<toml_edit::DocumentMut>()?;
+    if theme.is_empty() || theme == theme::DEFAULT_THEME {
+        document.remove("theme");
+        return Ok(document.to_string());
+    }
+
+    match document
+        .get_mut("theme")
+        .and_then(|item| item.as_value_mut())
+    {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = theme.into();
+            *value.decor_mut() = decor;
+        }
+        None => document["theme"] = toml_edit::value(theme),
+    }
+
+    Ok(document.to_string())
 }
 
 #[inline]
@@ -252,18 +465,19 @@ impl Config {
         }
     }
     #[cfg(test)]
-    fn load_from_path_without_fallback(path: &PathBuf) -> Result<Self, String> {
+    fn load_from_path_without_fallback(path: &Path) -> Result<Self, String> {
         if path.exists() {
-            let content = std::fs::read_to_string(path).unwrap();
-            match toml::from_str::<Config>(&content) {
+            match Config::parse(path, &[]) {
                 Ok(mut decoded) => {
+                    let tmp = std::env::temp_dir();
+                    decoded.load_profile_themes(&tmp);
+
                     let theme = &decoded.theme;
                     if theme.is_empty() {
                         return Ok(decoded);
                     }
 
-                    let tmp = std::env::temp_dir();
-                    let path = tmp.join(theme).with_extension("toml");
+                    let path = theme::theme_file(&tmp, theme);
                     if let Ok(loaded_theme) = Config::load_theme(&path) {
                         decoded.colors = loaded_theme.colors;
                     } else {
@@ -272,7 +486,7 @@ impl Config {
 
                     if let Some(adaptive_theme) = &decoded.adaptive_theme {
                         let light_theme = &adaptive_theme.light;
-                        let path = tmp.join(light_theme).with_extension("toml");
+                        let path = theme::theme_file(&tmp, light_theme);
                         let mut adaptive_colors = AdaptiveColors {
                             dark: None,
                             light: None,
@@ -285,7 +499,7 @@ impl Config {
                         }
 
                         let dark_theme = &adaptive_theme.dark;
-                        let path = tmp.join(dark_theme).with_extension("toml");
+                        let path = theme::theme_file(&tmp, dark_theme);
                         if let Ok(dark_loaded_theme) = Config::load_theme(&path) {
                             adaptive_colors.dark = Some(dark_loaded_theme.colors);
                         } else {
@@ -301,25 +515,61 @@ impl Config {
 
                     Ok(decoded)
                 }
-                Err(err_message) => Err(format!("error parsing: {:?}", err_message)),
+                Err(err_message) => Err(err_message),
             }
         } else {
             Err(String::from("filepath does not exist"))
         }
     }
 
-    fn load_theme(path: &PathBuf) -> Result<Theme, String> {
+    /// Parse the config file at `path` with its imports and the `overrides`
+    /// merged in, a missing file only gets the overrides.
+    fn parse(path: &Path, overrides: &[String]) -> Result<Self, String> {
+        let mut sources = import::Sources::default();
+        let mut table = if path.exists() {
+            import::read_with_imports(path, &mut sources)?
+        } else {
+            toml::Table::new()
+        };
+
+        for option in overrides {
+            import::merge(&mut table, import::parse_override(option)?);
+        }
+
+        let mut config = match toml::Value::Table(table.clone()).try_into::<Config>() {
+            Ok(config) => config,
+            Err(err) => return Err(diagnostics::merge_error(&err, &sources)),
+        };
+        config.warnings = diagnostics::warnings(&table, &config, &sources);
+        config.imported_files = sources.imported;
+        Ok(config)
+    }
+
+    fn load_theme(path: &Path) -> Result<Theme, String> {
         if path.exists() {
-            let content = std::fs::read_to_string(path).unwrap();
-            match toml::from_str::<Theme>(&content) {
-                Ok(decoded) => Ok(decoded),
-                Err(err_message) => Err(format!("error parsing: {:?}", err_message)),
-            }
+            theme::load(path)
         } else {
             Err(String::from("filepath does not exist"))
         }
     }
 
+    /// Resolves the `theme` of each profile into colors, profiles with a
+    /// theme that fails to load keep the global colors.
+    fn load_profile_themes(&mut self, theme_path: &std::path::Path) {
+        for profile in self.profiles.iter_mut() {
+            if let Some(theme) = &profile.theme {
+                let path = theme::theme_file(theme_path, theme);
+                match Config::load_theme(&path) {
+                    Ok(loaded_theme) => profile.colors = Some(loaded_theme.colors),
+                    Err(..) => warn!(
+                        "failed to load theme {} for profile {}",
+                        theme, profile.name
+                    ),
+                }
+            }
+        }
+    }
+
     pub fn to_string(&self) -> Result<String, toml::ser::Error> {
         toml::to_string(self)
     }
@@ -327,19 +577,22 @@ impl Config {
     pub fn load() -> Self {
         let config_path = config_dir_path();
         let path = config_file_path();
-        if path.exists() {
-            let content = std::fs::read_to_string(path).unwrap();
-            match toml::from_str::<Config>(&content) {
+        let overrides = load_overrides();
+        if path.exists() || !overrides.is_empty() {
+            match Config::parse(&path, overrides) {
                 Ok(mut decoded) => {
+                    for warning in &decoded.warnings {
+                        warn!("{warning}");
+                    }
+
+                    decoded.load_profile_themes(&config_path.join("themes"));
+
                     let theme = &decoded.theme;
                     if theme.is_empty() {
                         return decoded;
                     }
 
-                    let path = config_path
-                        .join("themes")
-                        .join(theme)
-                        .with_extension("toml");
+                    let path = theme::theme_file(&config_path.join("themes"), theme);
                     if let Ok(loaded_theme) = Config::load_theme(&path) {
                         decoded.colors = loaded_theme.colors;
                     } else {
@@ -349,7 +602,7 @@ impl Config {
                     decoded
                 }
                 Err(err_message) => {
-                    warn!("failure to parse config file, falling back to default...\n{err_message:?}");
+                    warn!("failure to parse config file, falling back to default...\n{err_message}");
                     Config::default()
                 }
             }
@@ -360,78 +613,66 @@ impl Config {
 
     pub fn try_load() -> Result<Self, ConfigError> {
         let path = config_file_path();
-        if path.exists() {
-            match std::fs::read_to_string(path) {
-                Ok(content) => match toml::from_str::<Config>(&content) {
-                    Ok(mut decoded) => {
-                        let theme = &decoded.theme;
-                        let theme_path = config_dir_path().join("themes");
-                        if !theme.is_empty() {
-                            let path = theme_path.join(theme).with_extension("toml");
-                            match Config::load_theme(&path) {
-                                Ok(loaded_theme) => {
-                                    decoded.colors = loaded_theme.colors;
-                                }
-                                Err(err_message) => {
-                                    return Err(ConfigError::ErrLoadingTheme(
-                                        err_message,
-                                    ));
-                                }
+        let overrides = load_overrides();
+        // Overrides apply on top of the defaults when there is no config file.
+        if path.exists() || !overrides.is_empty() {
+            match Config::parse(&path, overrides) {
+                Ok(mut decoded) => {
+                    let theme_path = config_dir_path().join("themes");
+                    decoded.load_profile_themes(&theme_path);
+                    let theme = &decoded.theme;
+                    if !theme.is_empty() {
+                        let path = theme::theme_file(&theme_path, theme);
+                        match Config::load_theme(&path) {
+                            Ok(loaded_theme) => {
+                                decoded.colors = loaded_theme.colors;
+                            }
+                            Err(err_message) => {
+                                return Err(ConfigError::ErrLoadingTheme(err_message));
                             }
                         }
+                    }
 
-                        if let Some(adaptive_theme) = &decoded.adaptive_theme {
-                            let mut adaptive_colors = AdaptiveColors {
-                                dark: None,
-                                light: None,
-                            };
-
-                            let light_theme = &adaptive_theme.light;
-                            let path =
-                                theme_path.join(light_theme).with_extension("toml");
-                            match Config::load_theme(&path) {
-                                Ok(light_loaded_theme) => {
-                                    adaptive_colors.light =
-                                        Some(light_loaded_theme.colors)
-                                }
-                                Err(err_message) => {
-                                    warn!("failed to load light theme: {}", light_theme);
-                                    return Err(ConfigError::ErrLoadingTheme(
-                                        err_message,
-                                    ));
-                                }
-                            }
+                    if let Some(adaptive_theme) = &decoded.adaptive_theme {
+                        let mut adaptive_colors = AdaptiveColors {
+                            dark: None,
+                            light: None,
+                        };
 
-                            let dark_theme = &adaptive_theme.dark;
-                            let path = theme_path.join(dark_theme).with_extension("toml");
-                            match Config::load_theme(&path) {
-                                Ok(dark_loaded_theme) => {
-                                    adaptive_colors.dark = Some(dark_loaded_theme.colors)
-                                }
-                                Err(err_message) => {
-                                    warn!("failed to load dark theme: {}", dark_theme);
-                                    return Err(ConfigError::ErrLoadingTheme(
-                                        err_message,
-                                    ));
-                                }
+                        let light_theme = &adaptive_theme.light;
+                        let path = theme::theme_file(&theme_path, light_theme);
+                        match Config::load_theme(&path) {
+                            Ok(light_loaded_theme) => {
+                                adaptive_colors.light = Some(light_loaded_theme.colors)
                             }
+                            Err(err_message) => {
+                                warn!("failed to load light theme: {}", light_theme);
+                                return Err(ConfigError::ErrLoadingTheme(err_message));
+                            }
+                        }
 
-                            if adaptive_colors.light.is_some()
-                                && adaptive_colors.dark.is_some()
-                            {
-                                decoded.adaptive_colors = Some(adaptive_colors);
+                        let dark_theme = &adaptive_theme.dark;
+                        let path = theme::theme_file(&theme_path, dark_theme);
+                        match Config::load_theme(&path) {
+                            Ok(dark_loaded_theme) => {
+                                adaptive_colors.dark = Some(dark_loaded_theme.colors)
+                            }
+                            Err(err_message) => {
+                                warn!("failed to load dark theme: {}", dark_theme);
+                                return Err(ConfigError::ErrLoadingTheme(err_message));
                             }
                         }
 
-                        Ok(decoded)
-                    }
-                    Err(err_message) => {
-                        Err(ConfigError::ErrLoadingConfig(err_message.to_string()))
+                        if adaptive_colors.light.is_some()
+                            && adaptive_colors.dark.is_some()
+                        {
+                            decoded.adaptive_colors = Some(adaptive_colors);
+                        }
                     }
-                },
-                Err(err_message) => {
-                    Err(ConfigError::ErrLoadingConfig(err_message.to_string()))
+
+                    Ok(decoded)
                 }
+                Err(err_message) => Err(ConfigError::ErrLoadingConfig(err_message)),
             }
         } else {
             Err(ConfigError::PathNotFound)
@@ -490,20 +731,32 @@ impl Default for Config {
             env_vars: vec![],
             fonts: SugarloafFonts::default(),
             line_height: default_line_height(),
+            font_size_scope: FontSizeScope::default(),
             navigation: Navigation::default(),
             option_as_alt: default_option_as_alt(),
             padding_x: f32::default(),
             padding_y: default_padding_y(),
             renderer: Renderer::default(),
+            bell: Bell::default(),
+            images: Images::default(),
+            paste: Paste::default(),
+            search: Search::default(),
+            profiles: Vec::default(),
+            inactive_split_opacity: default_inactive_split_opacity(),
+            active_split_border: None,
+            active_split_border_width: default_active_split_border_width(),
             shell: default_shell(),
             platform: Platform::default(),
             theme: String::default(),
             use_fork: default_use_fork(),
             window: Window::default(),
             working_dir: default_working_dir(),
+            download_dir: None,
             ignore_selection_fg_color: false,
             confirm_before_quit: true,
             hide_cursor_when_typing: false,
+            imported_files: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 }
@@ -649,11 +902,13 @@ mod tests {
             [renderer]
             performance = "Low"
             backend = "Vulkan"
+            atlas-memory-limit = 64
         "#,
         );
 
         assert_eq!(result.renderer.performance, renderer::Performance::Low);
         assert_eq!(result.renderer.backend, renderer::Backend::Vulkan);
+        assert_eq!(result.renderer.atlas_memory_limit, 64);
         assert_eq!(result.fonts, SugarloafFonts::default());
         assert_eq!(result.theme, String::default());
         // Colors
@@ -842,6 +1097,27 @@ mod tests {
         assert_eq!(result.colors.cursor, colors::defaults::cursor());
     }
 
+    #[test]
+    fn test_change_theme_from_other_terminal() {
+        std::fs::write(
+            tmp_dir().join("test-rio-kitty-theme.conf"),
+            "background #2B3E50\nforeground #F8F8F2\ncolor1 #FF6541\n",
+        )
+        .unwrap();
+
+        let result = create_temporary_config(
+            "change-theme-kitty",
+            r#"
+            theme = "test-rio-kitty-theme.conf"
+        "#,
+        );
+
+        assert_eq!(result.colors.background.0, hex_to_color_arr("#2B3E50"));
+        assert_eq!(result.colors.foreground, hex_to_color_arr("#F8F8F2"));
+        assert_eq!(result.colors.red, hex_to_color_arr("#FF6541"));
+        assert_eq!(result.colors.cursor, colors::defaults::cursor());
+    }
+
     #[test]
     fn test_change_theme_with_colors_overwrite() {
         create_temporary_theme(
@@ -1029,6 +1305,403 @@ mod tests {
         assert_eq!(result.shell.args, Vec::<&str>::new());
     }
 
+    #[test]
+    fn test_split_focus_config() {
+        let result = create_temporary_config(
+            "split-focus",
+            r#"
+            inactive-split-opacity = 0.6
+            active-split-border = '#ff00ff'
+            active-split-border-width = 2
+        "#,
+        );
+
+        assert_eq!(result.inactive_split_opacity, 0.6);
+        assert_eq!(
+            result.active_split_border,
+            Some(hex_to_color_arr("#ff00ff"))
+        );
+        assert_eq!(result.active_split_border_width, 2.);
+
+        let result = create_temporary_config("split-focus-default", "");
+        assert_eq!(result.inactive_split_opacity, 1.0);
+        assert_eq!(result.active_split_border, None);
+        assert_eq!(result.active_split_border_width, 1.0);
+    }
+
+    #[test]
+    fn test_bell_config() {
+        let result = create_temporary_config(
+            "bell",
+            r#"
+            [bell]
+            duration = 150
+            animation = 'Linear'
+            color = '#ff0000'
+            command = { program = 'paplay', args = ['bell.oga'] }
+        "#,
+        );
+
+        assert_eq!(result.bell.duration, 150);
+        assert_eq!(result.bell.animation, bell::BellAnimation::Linear);
+        assert_eq!(result.bell.color, hex_to_color_arr("#ff0000"));
+        assert_eq!(
+            result.bell.command,
+            Some(Shell {
+                program: String::from("paplay"),
+                args: vec![String::from("bell.oga")],
+            })
+        );
+
+        let result = create_temporary_config("bell-default", "");
+        assert_eq!(result.bell, Bell::default());
+        assert_eq!(result.bell.duration, 0);
+        assert_eq!(result.bell.command, None);
+    }
+
+    #[test]
+    fn test_images_config() {
+        let result = create_temporary_config(
+            "images",
+            r#"
+            [images]
+            max-pixels = 1000
+            max-animation-fps = 10
+        "#,
+        );
+
+        assert_eq!(result.images.max_pixels, 1000);
+        assert_eq!(result.images.max_animation_fps, 10);
+        assert_eq!(result.images.max_bytes, Images::default().max_bytes);
+
+        let result = create_temporary_config("images-default", "");
+        assert_eq!(result.images, Images::default());
+    }
+
+    #[test]
+    fn test_search_config() {
+        let result = create_temporary_config(
+            "search",
+            r#"
+            [search]
+            per-directory-history = true
+            presets = [
+                { name = "IPv4", regex = '\d+\.\d+\.\d+\.\d+' },
+            ]
+        "#,
+        );
+
+        assert!(result.search.persist_history);
+        assert!(result.search.per_directory_history);
+        assert_eq!(
+            result.search.presets,
+            vec![SearchPreset {
+                name: String::from("IPv4"),
+                regex: String::from("\\d+\\.\\d+\\.\\d+\\.\\d+"),
+            }]
+        );
+
+        let result = create_temporary_config("search-default", "");
+        assert_eq!(result.search, Search::default());
+    }
+
+    #[test]
+    fn test_import_merge_precedence() {
+        let dir = tmp_dir().join("test-rio-import");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(
+            dir.join("nested").join("first.toml"),
+            r#"
+            theme = "first"
+            padding-x = 5.0
+            [window]
+            width = 800
+            opacity = 0.5
+            [fonts]
+            size = 20
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("second.toml"),
+            r#"
+            theme = "second"
+            [window]
+            opacity = 0.7
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            r#"
+            import = ["nested/first.toml", "second.toml", "missing.toml"]
+            [window]
+            height = 300
+            [fonts]
+            size = 16
+        "#,
+        )
+        .unwrap();
+
+        let config = Config::parse(&dir.join("config.toml"), &[]).unwrap();
+        // Later imports override earlier ones, tables are merged.
+        assert_eq!(config.theme, "second");
+        assert_eq!(config.padding_x, 5.0);
+        assert_eq!(config.window.width, 800);
+        assert_eq!(config.window.opacity, 0.7);
+        // The importing file overrides its imports.
+        assert_eq!(config.window.height, 300);
+        assert_eq!(config.fonts.size, 16.0);
+        assert_eq!(
+            config.imported_files,
+            vec![
+                dir.join("nested").join("first.toml"),
+                dir.join("second.toml"),
+                dir.join("missing.toml"),
+            ]
+        );
+
+        // Command line overrides win over every file.
+        let overrides = [
+            String::from("window.opacity=0.9"),
+            String::from("theme=dracula"),
+            String::from("fonts.size = 18"),
+        ];
+        let config = Config::parse(&dir.join("config.toml"), &overrides).unwrap();
+        assert_eq!(config.window.opacity, 0.9);
+        assert_eq!(config.window.height, 300);
+        assert_eq!(config.window.width, 800);
+        assert_eq!(config.theme, "dracula");
+        assert_eq!(config.fonts.size, 18.0);
+
+        // Overrides also apply without a config file.
+        let config =
+            Config::parse(&dir.join("it-should-never-exist"), &overrides).unwrap();
+        assert_eq!(config.window.opacity, 0.9);
+        assert_eq!(config.window.height, Window::default().height);
+
+        assert!(
+            Config::parse(&dir.join("config.toml"), &[String::from("theme")]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_diagnostics() {
+        let dir = tmp_dir().join("test-rio-diagnostics");
+        std::fs::create_dir_all(&dir).unwrap();
+        let parse = |name: &str, content: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, content).unwrap();
+            (Config::parse(&path, &[]), path.display().to_string())
+        };
+
+        let (result, path) =
+            parse("syntax.toml", "theme = \"dracula\"\nline-height = \n");
+        let err = result.unwrap_err();
+        assert!(err.contains(&format!("{path}:2:15")), "{err}");
+
+        let (result, path) =
+            parse("type.toml", "[window]\nwidth = 600\nopacity = \"high\"\n");
+        let err = result.unwrap_err();
+        assert!(err.contains(&format!("{path}:3:11")), "{err}");
+        assert!(err.contains("3 | opacity = \"high\""), "{err}");
+
+        let (result, path) = parse(
+            "unknown.toml",
+            r##"hide-cursor-when-typing = true
+theme = "dracula"
+fonts.family = "Menlo"
+[navgation]
+mode = "Plain"
+[window]
+opcity = 0.5
+width = 600
+[colors]
+background = "#000000"
+[platform]
+linux.shell.program = "fish"
+[[profiles]]
+name = "work"
+"##,
+        );
+        let warnings = result.unwrap().warnings;
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+        assert!(warnings[0]
+            .starts_with("unknown key `navgation`, did you mean `navigation`?"));
+        assert!(warnings[0].contains(&format!("{path}:4:2")));
+        assert!(warnings[1]
+            .starts_with("unknown key `window.opcity`, did you mean `opacity`?"));
+        assert!(warnings[1].contains(&format!("{path}:7:1")));
+        assert!(warnings[2].starts_with(
+            "`hide-cursor-when-typing` is deprecated, use `hide-mouse-cursor-when-typing` instead"
+        ));
+
+        let (result, _) = parse("default.toml", &default_config_file_content());
+        assert_eq!(result.unwrap().warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let dir = tmp_dir().join("test-rio-import-cycle");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.toml"), "import = [\"b.toml\"]").unwrap();
+        std::fs::write(dir.join("b.toml"), "import = [\"a.toml\"]").unwrap();
+
+        let err = Config::parse(&dir.join("a.toml"), &[]).unwrap_err();
+        assert!(err.contains("import cycle"));
+        assert!(err.contains("a.toml -> "));
+        assert!(err.contains("b.toml -> "));
+        assert!(!err.contains("nested more than"));
+
+        // The same file imported twice is not a cycle.
+        std::fs::write(dir.join("c.toml"), "[fonts]\nsize = 20").unwrap();
+        std::fs::write(dir.join("d.toml"), "import = [\"c.toml\"]").unwrap();
+        std::fs::write(dir.join("e.toml"), "import = [\"c.toml\", \"d.toml\"]").unwrap();
+        assert!(Config::parse(&dir.join("e.toml"), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_set_theme() {
+        let content = "# colors\ntheme = \"dracula\" # dark\n\n[fonts]\nsize = 14\n";
+        assert_eq!(
+            set_theme(content, "nord").unwrap(),
+            "# colors\ntheme = \"nord\" # dark\n\n[fonts]\nsize = 14\n"
+        );
+        assert_eq!(
+            set_theme(content, theme::DEFAULT_THEME).unwrap(),
+            "\n[fonts]\nsize = 14\n"
+        );
+        assert_eq!(
+            set_theme("[fonts]\nsize = 14\n", "nord").unwrap(),
+            "theme = \"nord\"\n[fonts]\nsize = 14\n"
+        );
+        assert!(set_theme("theme = ", "nord").is_err());
+    }
+
+    #[test]
+    fn test_font_size_scope() {
+        let result = create_temporary_config("font-size-scope-default", "");
+        assert_eq!(result.font_size_scope, FontSizeScope::Split);
+
+        let result =
+            create_temporary_config("font-size-scope", "font-size-scope = \"tab\"");
+        assert_eq!(result.font_size_scope, FontSizeScope::Tab);
+    }
+
+    #[test]
+    fn test_paste_config() {
+        let result = create_temporary_config(
+            "paste",
+            r#"
+            [paste]
+            guard = true
+            max-size = 1024
+        "#,
+        );
+
+        assert!(result.paste.guard);
+        assert_eq!(result.paste.max_size, 1024);
+        assert_eq!(result.paste.drop_format, DropFormat::Path);
+        assert_eq!(result.paste.drop_template, "{paths} ");
+
+        let result = create_temporary_config(
+            "paste-drop",
+            r#"
+            [paste]
+            drop-format = "uri"
+            drop-template = "open {paths}"
+        "#,
+        );
+
+        assert_eq!(result.paste.drop_format, DropFormat::Uri);
+        assert_eq!(result.paste.drop_template, "open {paths}");
+
+        let result = create_temporary_config("paste-default", "");
+        assert_eq!(result.paste, Paste::default());
+        assert!(!result.paste.guard);
+    }
+
+    #[test]
+    fn test_font_path_and_variations() {
+        let result = create_temporary_config(
+            "font-path-variations",
+            r#"
+            [fonts.regular]
+            family = "Recursive"
+            path = "~/fonts/Recursive.ttf"
+            variations = [{ axis = "wght", value = 450 }, { axis = "CASL", value = 0.5 }]
+        "#,
+        );
+
+        let regular = &result.fonts.regular;
+        assert_eq!(regular.path, Some(String::from("~/fonts/Recursive.ttf")));
+        assert_eq!(regular.variations.len(), 2);
+        assert_eq!(regular.variations[0].axis, "wght");
+        assert_eq!(regular.variations[0].value, 450.0);
+        assert_eq!(regular.variations[1].axis, "CASL");
+        assert_eq!(regular.variations[1].value, 0.5);
+        assert_eq!(result.fonts.bold.path, None);
+        assert!(result.fonts.bold.variations.is_empty());
+    }
+
+    #[test]
+    fn test_profiles() {
+        create_temporary_theme(
+            "profile-staging",
+            r#"
+            [colors]
+            background       = '#2B3E50'
+        "#,
+        );
+
+        let result = create_temporary_config(
+            "profiles",
+            r#"
+            [[profiles]]
+            name = "Prod"
+            shell = { program = "/bin/zsh", args = ["-l"] }
+            working-dir = "/srv/prod"
+            env-vars = ["STAGE=prod", "KUBECONFIG=/etc/kube=prod"]
+            tab-color = '#ff0000'
+            font-size = 20
+
+            [[profiles]]
+            name = "staging"
+            theme = "profile-staging"
+        "#,
+        );
+
+        assert_eq!(result.profiles.len(), 2);
+        let prod = result.profile("prod").unwrap();
+        assert_eq!(prod.shell.as_ref().unwrap().program, "/bin/zsh");
+        assert_eq!(prod.working_dir, Some(String::from("/srv/prod")));
+        assert_eq!(prod.tab_color, Some(hex_to_color_arr("#ff0000")));
+        assert_eq!(prod.font_size, Some(20.));
+        assert_eq!(prod.colors, None);
+        assert_eq!(
+            profile::parse_env_vars(&prod.env_vars),
+            [
+                (String::from("STAGE"), String::from("prod")),
+                (String::from("KUBECONFIG"), String::from("/etc/kube=prod")),
+            ]
+        );
+
+        let prod_config = prod.apply(&result);
+        assert_eq!(prod_config.shell.args, ["-l"]);
+        assert_eq!(prod_config.fonts.size, 20.);
+        assert!(!prod_config.use_fork);
+
+        let staging = result.profile("staging").unwrap();
+        assert_eq!(staging.shell, None);
+        assert_eq!(staging.tab_color, None);
+        let colors = staging.colors.unwrap();
+        assert_eq!(colors.background.0, hex_to_color_arr("#2B3E50"));
+        assert_eq!(colors.foreground, colors::defaults::foreground());
+        assert_eq!(result.colors.background, colors::defaults::background());
+
+        assert!(result.profile("missing").is_none());
+    }
+
     #[test]
     fn test_change_developer_and_performance() {
         let result = create_temporary_config(
@@ -117,6 +117,10 @@ pub struct Navigation {
     pub clickable: bool,
     #[serde(default = "bool::default", rename = "use-current-path")]
     pub use_current_path: bool,
+    /// Command used by new tabs and splits when the shell reported (OSC 7)
+    /// a remote working directory, `{host}` and `{path}` are replaced.
+    #[serde(default = "Option::default", rename = "remote-command")]
+    pub remote_command: Option<String>,
     #[serde(default = "bool::default", rename = "use-terminal-title")]
     pub use_terminal_title: bool,
     #[serde(default = "default_bool_true", rename = "hide-if-single")]
@@ -134,6 +138,7 @@ impl Default for Navigation {
             color_automation: Vec::default(),
             clickable: false,
             use_current_path: false,
+            remote_command: None,
             use_terminal_title: false,
             hide_if_single: true,
             use_split: true,
@@ -202,6 +207,23 @@ mod tests {
         assert!(decoded.navigation.color_automation.is_empty());
     }
 
+    #[test]
+    fn test_remote_command() {
+        let content = r#"
+            [navigation]
+            use-current-path = true
+            remote-command = 'ssh {host} -t "cd {path}; exec $SHELL"'
+        "#;
+
+        let decoded = toml::from_str::<Root>(content).unwrap();
+        assert!(decoded.navigation.use_current_path);
+        assert_eq!(
+            decoded.navigation.remote_command,
+            Some(String::from(r#"ssh {host} -t "cd {path}; exec $SHELL""#))
+        );
+        assert_eq!(Navigation::default().remote_command, None);
+    }
+
     #[test]
     fn test_top_tab() {
         let content = r#"
@@ -0,0 +1,77 @@
+use crate::config::colors::{deserialize_to_arr_opt, ColorArray, Colors};
+use crate::config::{Config, Shell};
+use serde::{Deserialize, Serialize};
+
+/// A named set of overrides used to spawn tabs and splits,
+/// declared as `[[profiles]]` in the configuration file.
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default = "Option::default")]
+    pub shell: Option<Shell>,
+    #[serde(default = "Option::default", rename = "working-dir")]
+    pub working_dir: Option<String>,
+    #[serde(default = "Vec::default", rename = "env-vars")]
+    pub env_vars: Vec<String>,
+    #[serde(default = "Option::default")]
+    pub theme: Option<String>,
+    #[serde(default = "Option::default", rename = "font-size")]
+    pub font_size: Option<f32>,
+    #[serde(
+        default = "Option::default",
+        deserialize_with = "deserialize_to_arr_opt",
+        rename = "tab-color",
+        skip_serializing
+    )]
+    pub tab_color: Option<ColorArray>,
+    // Colors from `theme`, filled once the configuration is loaded.
+    #[serde(skip)]
+    pub colors: Option<Colors>,
+}
+
+impl Profile {
+    /// Configuration used to spawn this profile in a new window or native tab.
+    pub fn apply(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if let Some(shell) = &self.shell {
+            config.shell = shell.clone();
+        }
+        if self.working_dir.is_some() {
+            config.working_dir = self.working_dir.clone();
+        }
+        config.env_vars.extend(self.env_vars.iter().cloned());
+        if let Some(colors) = self.colors {
+            config.colors = colors;
+        }
+        if let Some(font_size) = self.font_size {
+            config.fonts.size = font_size;
+        }
+        // Arguments, working directory and environment are only
+        // honored when the shell is spawned.
+        config.use_fork = false;
+        config
+    }
+}
+
+impl Config {
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        find_profile(&self.profiles, name)
+    }
+}
+
+/// Finds a profile by name, ignoring case since binding actions are
+/// lowercased when parsed.
+pub fn find_profile<'a>(profiles: &'a [Profile], name: &str) -> Option<&'a Profile> {
+    profiles
+        .iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name))
+}
+
+/// Parses `KEY=VALUE` entries, ignoring the ones without `=`.
+pub fn parse_env_vars(env_vars: &[String]) -> Vec<(String, String)> {
+    env_vars
+        .iter()
+        .filter_map(|env_var| env_var.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
@@ -15,6 +15,14 @@ pub struct Renderer {
     pub filters: Vec<String>,
     #[serde(default = "RendererStategy::default")]
     pub strategy: RendererStategy,
+    /// Maximum size of the glyph atlas in megabytes.
+    #[serde(default = "default_atlas_memory_limit", rename = "atlas-memory-limit")]
+    pub atlas_memory_limit: usize,
+}
+
+#[inline]
+fn default_atlas_memory_limit() -> usize {
+    16
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -48,6 +56,7 @@ impl Default for Renderer {
             target_fps: None,
             filters: Vec::default(),
             strategy: RendererStategy::Events,
+            atlas_memory_limit: default_atlas_memory_limit(),
         }
     }
 }
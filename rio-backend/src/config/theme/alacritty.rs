@@ -0,0 +1,90 @@
+// Alacritty keeps its colors in the `colors` table of the config, either in
+// TOML or in the YAML used before 0.13.
+
+use super::{yaml, ImportedTheme, ANSI_COLORS};
+use toml::{Table, Value};
+
+const COLORS: [(&[&str], &str); 12] = [
+    (&["primary", "background"], "background"),
+    (&["primary", "foreground"], "foreground"),
+    (&["primary", "dim_foreground"], "dim-foreground"),
+    (&["primary", "bright_foreground"], "light-foreground"),
+    (&["cursor", "cursor"], "cursor"),
+    (&["vi_mode_cursor", "cursor"], "vi-cursor"),
+    (&["selection", "background"], "selection-background"),
+    (&["selection", "text"], "selection-foreground"),
+    (
+        &["search", "matches", "background"],
+        "search-match-background",
+    ),
+    (
+        &["search", "matches", "foreground"],
+        "search-match-foreground",
+    ),
+    (
+        &["search", "focused_match", "background"],
+        "search-focused-match-background",
+    ),
+    (
+        &["search", "focused_match", "foreground"],
+        "search-focused-match-foreground",
+    ),
+];
+
+const DIM_COLORS: [&str; 8] = [
+    "dim-black",
+    "dim-red",
+    "dim-green",
+    "dim-yellow",
+    "dim-blue",
+    "dim-magenta",
+    "dim-cyan",
+    "dim-white",
+];
+
+pub fn convert(content: &str) -> Result<ImportedTheme, String> {
+    let table = toml::from_str::<Table>(content).map_err(|err| err.to_string())?;
+    Ok(from_table(&table))
+}
+
+pub fn convert_yaml(content: &str) -> Result<ImportedTheme, String> {
+    Ok(from_table(&yaml::parse(content)?))
+}
+
+fn from_table(table: &Table) -> ImportedTheme {
+    let mut theme = ImportedTheme::default();
+    let colors = match table.get("colors").and_then(Value::as_table) {
+        Some(colors) => colors,
+        None => return theme,
+    };
+
+    for (keys, name) in COLORS {
+        if let Some(color) = get(colors, keys) {
+            theme.set(name, color);
+        }
+    }
+
+    for (index, color) in ANSI_COLORS[..8].iter().enumerate() {
+        let names = [
+            ("normal", ANSI_COLORS[index]),
+            ("bright", ANSI_COLORS[index + 8]),
+            ("dim", DIM_COLORS[index]),
+        ];
+        for (group, name) in names {
+            if let Some(value) = get(colors, &[group, color]) {
+                theme.set(name, value);
+            }
+        }
+    }
+
+    theme
+}
+
+fn get<'a>(table: &'a Table, keys: &[&str]) -> Option<&'a str> {
+    let (last, parents) = keys.split_last()?;
+    let mut table = table;
+    for key in parents {
+        table = table.get(*key)?.as_table()?;
+    }
+    table.get(*last)?.as_str()
+}
@@ -0,0 +1,50 @@
+// base16 schemes define 16 colors, `base00` to `base0F`, mapped to the
+// terminal colors like base16-shell does. The tinted-theming format keeps
+// them in a `palette` table.
+
+use super::{yaml, ImportedTheme, ANSI_COLORS};
+use toml::Value;
+
+/// base16 color used for each ANSI color.
+const ANSI_BASES: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05",
+    "base03", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+const COLORS: [(&str, &str); 5] = [
+    ("base00", "background"),
+    ("base05", "foreground"),
+    ("base05", "cursor"),
+    ("base02", "selection-background"),
+    ("base05", "selection-foreground"),
+];
+
+pub fn convert(content: &str) -> Result<ImportedTheme, String> {
+    let table = yaml::parse(content)?;
+    let palette = match table.get("palette").and_then(Value::as_table) {
+        Some(palette) => palette,
+        None => &table,
+    };
+
+    let get = |base: &str| {
+        palette
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(base))
+            .and_then(|(_, value)| value.as_str())
+    };
+
+    let mut theme = ImportedTheme::default();
+    for (base, name) in COLORS {
+        if let Some(color) = get(base) {
+            theme.set(name, color);
+        }
+    }
+
+    for (base, name) in ANSI_BASES.iter().zip(ANSI_COLORS) {
+        if let Some(color) = get(base) {
+            theme.set(name, color);
+        }
+    }
+
+    Ok(theme)
+}
@@ -0,0 +1,45 @@
+// Ghostty themes use the config syntax, `key = value` per line, and set the
+// ANSI colors with `palette = index=color`.
+
+use super::{ImportedTheme, ANSI_COLORS};
+
+pub fn convert(content: &str) -> ImportedTheme {
+    let mut theme = ImportedTheme::default();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+
+        let (name, value) = match key {
+            "background" => ("background", value),
+            "foreground" => ("foreground", value),
+            "cursor-color" => ("cursor", value),
+            "selection-background" => ("selection-background", value),
+            "selection-foreground" => ("selection-foreground", value),
+            "palette" => {
+                let (index, color) = match value.split_once('=') {
+                    Some((index, color)) => (index.trim(), color.trim()),
+                    None => continue,
+                };
+                match index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| ANSI_COLORS.get(index))
+                {
+                    Some(name) => (*name, color),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        theme.set(name, value);
+    }
+
+    theme
+}
@@ -0,0 +1,81 @@
+// iTerm2 `.itermcolors` files are property lists, a dictionary of colors
+// with their components as reals between 0 and 1.
+
+use super::{ImportedTheme, ANSI_COLORS};
+use roxmltree::{Document, Node, ParsingOptions};
+
+pub fn convert(content: &str) -> Result<ImportedTheme, String> {
+    // Property lists start with a doctype.
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document =
+        Document::parse_with_options(content, options).map_err(|err| err.to_string())?;
+    let colors = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("dict"))
+        .ok_or_else(|| String::from("missing the dictionary of colors"))?;
+
+    let mut theme = ImportedTheme::default();
+    for (key, value) in entries(colors) {
+        let name = match key {
+            "Background Color" => "background",
+            "Foreground Color" => "foreground",
+            "Cursor Color" => "cursor",
+            "Selection Color" => "selection-background",
+            "Selected Text Color" => "selection-foreground",
+            key => {
+                let index = key
+                    .strip_prefix("Ansi ")
+                    .and_then(|key| key.strip_suffix(" Color"))
+                    .and_then(|index| index.parse::<usize>().ok());
+                match index.and_then(|index| ANSI_COLORS.get(index)) {
+                    Some(name) => name,
+                    None => continue,
+                }
+            }
+        };
+
+        if let Some(color) = color(value) {
+            theme.set(name, &color);
+        }
+    }
+
+    Ok(theme)
+}
+
+/// Pairs of `<key>` and the element that follows it.
+fn entries<'a>(dict: Node<'a, 'a>) -> Vec<(&'a str, Node<'a, 'a>)> {
+    let mut entries = Vec::new();
+    let mut key = None;
+    for node in dict.children().filter(Node::is_element) {
+        if node.has_tag_name("key") {
+            key = node.text();
+        } else if let Some(key) = key.take() {
+            entries.push((key, node));
+        }
+    }
+    entries
+}
+
+fn color(dict: Node) -> Option<String> {
+    let mut components = [None; 3];
+    for (key, value) in entries(dict) {
+        let index = match key {
+            "Red Component" => 0,
+            "Green Component" => 1,
+            "Blue Component" => 2,
+            _ => continue,
+        };
+        components[index] = value
+            .text()
+            .and_then(|text| text.trim().parse::<f64>().ok());
+    }
+
+    let [red, green, blue] = components.map(|component| {
+        component.map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8)
+    });
+    Some(format!("#{:02x}{:02x}{:02x}", red?, green?, blue?))
+}
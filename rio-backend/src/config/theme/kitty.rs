@@ -0,0 +1,42 @@
+// kitty themes are `.conf` files with a `name value` option per line.
+
+use super::{ImportedTheme, ANSI_COLORS};
+
+pub fn convert(content: &str) -> ImportedTheme {
+    let mut theme = ImportedTheme::default();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = match line.split_once(char::is_whitespace) {
+            Some((key, value)) => (key, value.trim()),
+            None => continue,
+        };
+
+        let name = match key {
+            "background" => "background",
+            "foreground" => "foreground",
+            "cursor" => "cursor",
+            "selection_background" => "selection-background",
+            "selection_foreground" => "selection-foreground",
+            "active_tab_background" => "tabs-active",
+            "active_tab_foreground" => "tabs-active-foreground",
+            "inactive_tab_background" => "tabs",
+            "inactive_tab_foreground" => "tabs-foreground",
+            key => {
+                let index = key
+                    .strip_prefix("color")
+                    .and_then(|index| index.parse::<usize>().ok());
+                match index.and_then(|index| ANSI_COLORS.get(index)) {
+                    Some(name) => name,
+                    None => continue,
+                }
+            }
+        };
+
+        theme.set(name, value);
+    }
+
+    theme
+}
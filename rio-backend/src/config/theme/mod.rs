@@ -0,0 +1,340 @@
+mod alacritty;
+mod base16;
+mod ghostty;
+mod iterm2;
+mod kitty;
+mod windows_terminal;
+mod yaml;
+
+use crate::config::colors::Colors;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct AdaptiveColors {
+    #[serde(default = "Option::default", skip_serializing)]
+    pub dark: Option<Colors>,
+    #[serde(default = "Option::default", skip_serializing)]
+    pub light: Option<Colors>,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct AdaptiveTheme {
+    pub dark: String,
+    pub light: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct Theme {
+    #[serde(default = "Colors::default")]
+    pub colors: Colors,
+}
+
+/// Extensions of the theme files that `theme` can point to.
+const THEME_EXTENSIONS: [&str; 6] =
+    ["toml", "itermcolors", "json", "yml", "yaml", "conf"];
+
+/// Rio names of the 16 ANSI colors, in palette order.
+pub const ANSI_COLORS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "light-black",
+    "light-red",
+    "light-green",
+    "light-yellow",
+    "light-blue",
+    "light-magenta",
+    "light-cyan",
+    "light-white",
+];
+
+/// Order of the colors in imported themes.
+const COLOR_ORDER: [&str; 16] = [
+    "background",
+    "foreground",
+    "dim-foreground",
+    "light-foreground",
+    "cursor",
+    "vi-cursor",
+    "selection-background",
+    "selection-foreground",
+    "tabs",
+    "tabs-foreground",
+    "tabs-active",
+    "tabs-active-foreground",
+    "search-match-background",
+    "search-match-foreground",
+    "search-focused-match-background",
+    "search-focused-match-foreground",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeFormat {
+    Rio,
+    Iterm2,
+    Alacritty,
+    AlacrittyYaml,
+    Kitty,
+    WindowsTerminal,
+    Base16,
+    Ghostty,
+}
+
+impl ThemeFormat {
+    /// Guess the format from the extension, and from the content when the
+    /// extension is shared by several formats.
+    pub fn detect(path: &Path, content: &str) -> ThemeFormat {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("itermcolors") => ThemeFormat::Iterm2,
+            Some("json") => ThemeFormat::WindowsTerminal,
+            Some("yml" | "yaml") => {
+                if content.lines().any(|line| {
+                    line.trim_start()
+                        .trim_start_matches(['"', '\''])
+                        .starts_with("base00")
+                }) {
+                    ThemeFormat::Base16
+                } else {
+                    ThemeFormat::AlacrittyYaml
+                }
+            }
+            Some("toml") => {
+                let colors = toml::from_str::<toml::Table>(content)
+                    .ok()
+                    .and_then(|table| table.get("colors").cloned());
+                match colors {
+                    Some(toml::Value::Table(colors))
+                        if colors.contains_key("primary")
+                            || colors.contains_key("normal") =>
+                    {
+                        ThemeFormat::Alacritty
+                    }
+                    _ => ThemeFormat::Rio,
+                }
+            }
+            // Ghostty themes have no extension and use `key = value`, kitty
+            // uses `key value`.
+            _ => {
+                let is_ghostty = content
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty() && !line.starts_with('#'))
+                    .and_then(|line| line.split_once('='))
+                    .is_some_and(|(key, _)| !key.trim().contains(char::is_whitespace));
+                if is_ghostty {
+                    ThemeFormat::Ghostty
+                } else {
+                    ThemeFormat::Kitty
+                }
+            }
+        }
+    }
+}
+
+/// Colors converted from the theme of another terminal, as Rio color names
+/// and `#rrggbb` values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportedTheme {
+    colors: Vec<(&'static str, String)>,
+}
+
+impl ImportedTheme {
+    /// Set the color `name`, colors that are not hex values (like named or
+    /// special colors) are skipped.
+    fn set(&mut self, name: &'static str, color: &str) {
+        let color = match normalize(color) {
+            Some(color) => color,
+            None => {
+                tracing::debug!("skipping theme color {name} = {color}");
+                return;
+            }
+        };
+
+        match self.colors.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, value)) => *value = color,
+            None => self.colors.push((name, color)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.colors
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, color)| color.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The theme as a Rio theme file.
+    pub fn to_toml(&self) -> String {
+        let mut colors: Vec<_> = self.colors.iter().collect();
+        colors.sort_by_key(|(name, _)| {
+            COLOR_ORDER
+                .iter()
+                .chain(ANSI_COLORS.iter())
+                .position(|color| color == name)
+                .unwrap_or(usize::MAX)
+        });
+
+        let mut content = String::from("[colors]\n");
+        for (name, color) in colors {
+            content.push_str(&format!("{name} = \"{color}\"\n"));
+        }
+        content
+    }
+
+    pub fn theme(&self) -> Result<Theme, String> {
+        toml::from_str::<Theme>(&self.to_toml())
+            .map_err(|err_message| format!("error parsing: {:?}", err_message))
+    }
+}
+
+/// `#rgb`, `#rrggbb`, `#rrggbbaa`, `0xrrggbb` or bare hex into `#rrggbb`.
+fn normalize(color: &str) -> Option<String> {
+    let color = color.trim().trim_matches(['"', '\'']);
+    let hex = color
+        .strip_prefix('#')
+        .or_else(|| color.strip_prefix("0x"))
+        .or_else(|| color.strip_prefix("0X"))
+        .unwrap_or(color);
+    if !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|digit| [digit, digit]).collect(),
+        6 => hex.to_owned(),
+        8 => hex[..6].to_owned(),
+        _ => return None,
+    };
+    Some(format!("#{}", hex.to_ascii_lowercase()))
+}
+
+/// Convert the theme file at `path` from the format of another terminal.
+pub fn import(path: &Path) -> Result<ImportedTheme, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {err}", path.display()))?;
+
+    let theme = match ThemeFormat::detect(path, &content) {
+        ThemeFormat::Rio => {
+            return Err(format!("{} is already a Rio theme", path.display()))
+        }
+        ThemeFormat::Iterm2 => iterm2::convert(&content),
+        ThemeFormat::Alacritty => alacritty::convert(&content),
+        ThemeFormat::AlacrittyYaml => alacritty::convert_yaml(&content),
+        ThemeFormat::Kitty => Ok(kitty::convert(&content)),
+        ThemeFormat::WindowsTerminal => windows_terminal::convert(&content),
+        ThemeFormat::Base16 => base16::convert(&content),
+        ThemeFormat::Ghostty => Ok(ghostty::convert(&content)),
+    }
+    .map_err(|err| format!("error parsing {}: {err}", path.display()))?;
+
+    if theme.is_empty() {
+        return Err(format!("no colors found in {}", path.display()));
+    }
+
+    Ok(theme)
+}
+
+/// Load a Rio theme, or a theme of another terminal converted on the fly.
+pub fn load(path: &Path) -> Result<Theme, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {err}", path.display()))?;
+    match ThemeFormat::detect(path, &content) {
+        ThemeFormat::Rio => toml::from_str::<Theme>(&content)
+            .map_err(|err_message| format!("error parsing: {:?}", err_message)),
+        _ => import(path)?.theme(),
+    }
+}
+
+/// File of the theme `name`: a Rio theme in `dir`, or the path of a theme
+/// file (relative to `dir`) when it has the extension of a known format.
+pub fn theme_file(dir: &Path, name: &str) -> PathBuf {
+    let path = match name.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((relative, home)) => home.join(relative),
+        None => PathBuf::from(name),
+    };
+
+    let has_theme_extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            THEME_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        });
+    if has_theme_extension {
+        return dir.join(path);
+    }
+
+    // Ghostty themes have no extension.
+    let rio_theme = dir.join(name).with_extension("toml");
+    if !rio_theme.exists() && dir.join(name).is_file() {
+        return dir.join(name);
+    }
+
+    rio_theme
+}
+
+/// Built-in theme, Rio's default colors.
+pub const DEFAULT_THEME: &str = "default";
+
+/// Names of the themes in `dir`, as `theme` would point to them.
+pub fn list(dir: &Path) -> Vec<String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            if name.starts_with('.') {
+                return None;
+            }
+
+            let extension = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| extension.to_ascii_lowercase());
+            match extension.as_deref() {
+                // `theme` drops everything after the first dot of a Rio theme.
+                Some("toml") => match path.file_stem()?.to_str()? {
+                    stem if stem.contains('.') => Some(name.to_owned()),
+                    stem => Some(stem.to_owned()),
+                },
+                Some(extension) if THEME_EXTENSIONS.contains(&extension) => {
+                    Some(name.to_owned())
+                }
+                Some(_) => None,
+                None => Some(name.to_owned()),
+            }
+        })
+        .collect();
+
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+    names
+}
+
+/// Colors of the theme `name` of `dir`, or of a built-in theme.
+pub fn colors(dir: &Path, name: &str) -> Result<Colors, String> {
+    if name == DEFAULT_THEME {
+        return Ok(Colors::default());
+    }
+
+    load(&theme_file(dir, name)).map(|theme| theme.colors)
+}
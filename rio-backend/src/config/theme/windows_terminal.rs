@@ -0,0 +1,53 @@
+// Windows Terminal color schemes are JSON objects, either on their own or
+// in the `schemes` array of `settings.json`.
+
+use super::ImportedTheme;
+use serde_json::Value;
+
+const COLORS: [(&str, &str); 20] = [
+    ("background", "background"),
+    ("foreground", "foreground"),
+    ("cursorColor", "cursor"),
+    ("selectionBackground", "selection-background"),
+    ("black", "black"),
+    ("red", "red"),
+    ("green", "green"),
+    ("yellow", "yellow"),
+    ("blue", "blue"),
+    ("purple", "magenta"),
+    ("cyan", "cyan"),
+    ("white", "white"),
+    ("brightBlack", "light-black"),
+    ("brightRed", "light-red"),
+    ("brightGreen", "light-green"),
+    ("brightYellow", "light-yellow"),
+    ("brightBlue", "light-blue"),
+    ("brightPurple", "light-magenta"),
+    ("brightCyan", "light-cyan"),
+    ("brightWhite", "light-white"),
+];
+
+pub fn convert(content: &str) -> Result<ImportedTheme, String> {
+    let value = serde_json::from_str::<Value>(content).map_err(|err| err.to_string())?;
+
+    // The first scheme of a settings file or of a list of schemes.
+    let scheme = match &value {
+        Value::Object(object) => match object.get("schemes") {
+            Some(Value::Array(schemes)) => schemes.first(),
+            _ => Some(&value),
+        },
+        Value::Array(schemes) => schemes.first(),
+        _ => None,
+    }
+    .and_then(Value::as_object)
+    .ok_or_else(|| String::from("missing a color scheme"))?;
+
+    let mut theme = ImportedTheme::default();
+    for (key, name) in COLORS {
+        if let Some(color) = scheme.get(key).and_then(Value::as_str) {
+            theme.set(name, color);
+        }
+    }
+
+    Ok(theme)
+}
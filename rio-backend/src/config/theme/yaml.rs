@@ -0,0 +1,99 @@
+// Theme files in YAML only use nested mappings of scalars, which is all this
+// parser reads. Sequences and flow collections are skipped.
+
+use toml::{Table, Value};
+
+/// Parse the mappings of `content` into a table of strings.
+pub fn parse(content: &str) -> Result<Table, String> {
+    let mut root = Table::new();
+    // Indentation and key of the mappings that contain the current line.
+    let mut parents: Vec<(usize, String)> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = strip_comment(line).trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed == "---" || trimmed == "..." {
+            continue;
+        }
+
+        let indent = line.len() - trimmed.len();
+        while parents.last().is_some_and(|(parent, _)| *parent >= indent) {
+            parents.pop();
+        }
+
+        if trimmed.starts_with('-') {
+            continue;
+        }
+
+        let (key, value) = match split_key(trimmed) {
+            Some(pair) => pair,
+            None => return Err(format!("line {}: expected `key: value`", index + 1)),
+        };
+
+        let table = parents.iter().try_fold(&mut root, |table, (_, parent)| {
+            table.get_mut(parent).and_then(Value::as_table_mut)
+        });
+        let table = match table {
+            Some(table) => table,
+            None => continue,
+        };
+
+        if value.is_empty() {
+            table.insert(key.clone(), Value::Table(Table::new()));
+            parents.push((indent, key));
+        } else {
+            table.insert(key, Value::String(unquote(value)));
+        }
+    }
+
+    Ok(root)
+}
+
+/// Split `key: value` at the first colon followed by a space or the end of
+/// the line, outside of quotes.
+fn split_key(line: &str) -> Option<(String, &str)> {
+    let mut quote = None;
+    for (index, character) in line.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if character == open => quote = None,
+            (None, ':') => {
+                let value = &line[index + 1..];
+                if value.is_empty() || value.starts_with(char::is_whitespace) {
+                    return Some((unquote(&line[..index]), value.trim()));
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Comments start with `#` after a space, outside of quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, character) in line.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if character == open => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..index],
+            _ => (),
+        }
+        previous = character;
+    }
+    line
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner.to_owned();
+        }
+    }
+    value.to_owned()
+}
@@ -21,17 +21,19 @@ pub mod search;
 pub mod square;
 pub mod vi_mode;
 
+use crate::ansi::decoder::{DecodeJob, DecodeSource};
 use crate::ansi::graphics::GraphicCell;
 use crate::ansi::graphics::Graphics;
 use crate::ansi::graphics::TextureRef;
 use crate::ansi::graphics::UpdateQueues;
+use crate::ansi::iterm2_image_protocol::FileTransfer;
 use crate::ansi::mode::NamedMode;
 use crate::ansi::mode::NamedPrivateMode;
 use crate::ansi::mode::PrivateMode;
 use crate::ansi::sixel;
 use crate::ansi::{
-    mode::Mode as AnsiMode, ClearMode, CursorShape, KeyboardModes,
-    KeyboardModesApplyBehavior, LineClearMode, TabulationClearMode,
+    mode::Mode as AnsiMode, ClearMode, ColorScheme, CursorShape, KeyboardModes,
+    KeyboardModesApplyBehavior, LineClearMode, TabColorComponent, TabulationClearMode,
 };
 use crate::clipboard::ClipboardType;
 use crate::config::colors::{
@@ -52,6 +54,7 @@ use grid::row::Row;
 use pos::{
     Boundary, CharsetIndex, Column, Cursor, CursorState, Direction, Line, Pos, Side,
 };
+use rustc_hash::FxHashMap;
 use square::{Hyperlink, LineLength, Square};
 use std::collections::HashSet;
 use std::mem;
@@ -59,7 +62,7 @@ use std::ops::{Index, IndexMut, Range};
 use std::option::Option;
 use std::ptr;
 use std::sync::Arc;
-use sugarloaf::{GraphicData, MAX_GRAPHIC_DIMENSIONS};
+use sugarloaf::{GraphicData, GraphicId, ResizeCommand, MAX_GRAPHIC_DIMENSIONS};
 use tracing::{debug, info, trace, warn};
 use unicode_width::UnicodeWidthChar;
 use vi_mode::{ViModeCursor, ViMotion};
@@ -99,6 +102,9 @@ bitflags! {
         const REPORT_ALTERNATE_KEYS   = 1 << 20;
         const REPORT_ALL_KEYS_AS_ESC  = 1 << 21;
         const REPORT_ASSOCIATED_TEXT  = 1 << 22;
+        const COLOR_SCHEME_UPDATES    = 1 << 23;
+        const SGR_PIXELS_MOUSE        = 1 << 24;
+        const MOUSE_SHIFT_REPORT      = 1 << 25;
         const MOUSE_MODE = Self::MOUSE_REPORT_CLICK.bits() | Self::MOUSE_MOTION.bits() | Self::MOUSE_DRAG.bits();
         const KITTY_KEYBOARD_PROTOCOL = Self::DISAMBIGUATE_ESC_CODES.bits()
                                       | Self::REPORT_EVENT_TYPES.bits()
@@ -380,6 +386,12 @@ const TITLE_STACK_MAX_DEPTH: usize = 4096;
 // Max size of the keyboard modes.
 const KEYBOARD_MODE_STACK_MAX_DEPTH: usize = 16384;
 
+// Max number of user variables.
+const USER_VARS_MAX_COUNT: usize = 256;
+
+// Max length of a user variable name and value.
+const USER_VAR_MAX_LEN: usize = 4096;
+
 #[derive(Debug)]
 pub struct Crosswords<U>
 where
@@ -407,6 +419,10 @@ where
     pub route_id: usize,
     title_stack: Vec<String>,
     current_directory: Option<std::path::PathBuf>,
+    current_host: Option<String>,
+    user_vars: FxHashMap<String, String>,
+    color_scheme: ColorScheme,
+    tab_color: Option<ColorRgb>,
     hyperlink_re: regex::Regex,
 
     // The stack for the keyboard modes.
@@ -414,6 +430,9 @@ where
 
     // Currently inactive keyboard mode stack.
     inactive_keyboard_mode_stack: Vec<KeyboardModes>,
+
+    // Changed whenever the grid content may have changed.
+    content_generation: u64,
 }
 
 impl<U: EventListener> Crosswords<U> {
@@ -463,11 +482,28 @@ impl<U: EventListener> Crosswords<U> {
             route_id,
             title_stack: Default::default(),
             current_directory: None,
+            current_host: None,
+            user_vars: FxHashMap::default(),
+            color_scheme: ColorScheme::default(),
+            tab_color: None,
             keyboard_mode_stack: Default::default(),
             inactive_keyboard_mode_stack: Default::default(),
+            content_generation: 0,
         }
     }
 
+    /// Changes when the content may have changed, used to cache work done
+    /// over the whole grid.
+    #[inline]
+    pub fn content_generation(&self) -> u64 {
+        self.content_generation
+    }
+
+    #[inline]
+    pub fn mark_content_changed(&mut self) {
+        self.content_generation = self.content_generation.wrapping_add(1);
+    }
+
     pub fn mark_fully_damaged(&mut self) {
         self.damage.is_fully_damaged = true;
     }
@@ -485,6 +521,7 @@ impl<U: EventListener> Crosswords<U> {
     #[inline]
     pub fn clear_saved_history(&mut self) {
         self.clear_screen(ClearMode::Saved);
+        self.mark_content_changed();
     }
 
     #[inline]
@@ -538,6 +575,7 @@ impl<U: EventListener> Crosswords<U> {
     }
 
     pub fn resize<S: Dimensions>(&mut self, size: S) {
+        self.mark_content_changed();
         let old_cols = self.grid.columns();
         let old_lines = self.grid.screen_lines();
         let num_cols = size.columns();
@@ -1010,6 +1048,52 @@ impl<U: EventListener> Crosswords<U> {
         self.mode
     }
 
+    #[inline]
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.color_scheme
+    }
+
+    /// Updates the color scheme, applications that enabled mode 2031 are
+    /// notified when it changes.
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        if self.color_scheme == color_scheme {
+            return;
+        }
+
+        self.color_scheme = color_scheme;
+        if self.mode.contains(Mode::COLOR_SCHEME_UPDATES) {
+            self.report_color_scheme();
+        }
+    }
+
+    #[inline]
+    fn report_color_scheme(&mut self) {
+        let text = format!("\x1b[?997;{}n", self.color_scheme as u8);
+        self.event_proxy.send_event(
+            RioEvent::PtyWriteOnRoute(self.route_id, text),
+            self.window_id,
+        );
+    }
+
+    /// Working directory reported by the shell through OSC 7.
+    #[inline]
+    pub fn current_directory(&self) -> Option<&std::path::Path> {
+        self.current_directory.as_deref()
+    }
+
+    /// Host of the working directory reported through OSC 7, `None` when
+    /// the shell did not include one.
+    #[inline]
+    pub fn current_host(&self) -> Option<&str> {
+        self.current_host.as_deref()
+    }
+
+    /// User variable set through OSC 1337 `SetUserVar`.
+    #[inline]
+    pub fn user_var(&self, name: &str) -> Option<&str> {
+        self.user_vars.get(name).map(String::as_str)
+    }
+
     #[inline]
     pub fn cursor(&self) -> CursorState {
         let mut content = self.cursor_shape;
@@ -1378,11 +1462,15 @@ impl<U: EventListener> Handler for Crosswords<U> {
             NamedPrivateMode::BracketedPaste => self.mode.insert(Mode::BRACKETED_PASTE),
             // Mouse encodings are mutually exclusive.
             NamedPrivateMode::SgrMouse => {
-                self.mode.remove(Mode::UTF8_MOUSE);
+                self.mode.remove(Mode::UTF8_MOUSE | Mode::SGR_PIXELS_MOUSE);
                 self.mode.insert(Mode::SGR_MOUSE);
             }
+            NamedPrivateMode::SgrPixelsMouse => {
+                self.mode.remove(Mode::UTF8_MOUSE | Mode::SGR_MOUSE);
+                self.mode.insert(Mode::SGR_PIXELS_MOUSE);
+            }
             NamedPrivateMode::Utf8Mouse => {
-                self.mode.remove(Mode::SGR_MOUSE);
+                self.mode.remove(Mode::SGR_MOUSE | Mode::SGR_PIXELS_MOUSE);
                 self.mode.insert(Mode::UTF8_MOUSE);
             }
             NamedPrivateMode::AlternateScroll => self.mode.insert(Mode::ALTERNATE_SCROLL),
@@ -1395,6 +1483,9 @@ impl<U: EventListener> Handler for Crosswords<U> {
                     .send_event(RioEvent::CursorBlinkingChange, self.window_id);
             }
             NamedPrivateMode::SyncUpdate => (),
+            NamedPrivateMode::ColorSchemeUpdates => {
+                self.mode.insert(Mode::COLOR_SCHEME_UPDATES)
+            }
         }
     }
 
@@ -1456,6 +1547,7 @@ impl<U: EventListener> Handler for Crosswords<U> {
             NamedPrivateMode::ReportFocusInOut => self.mode.remove(Mode::FOCUS_IN_OUT),
             NamedPrivateMode::BracketedPaste => self.mode.remove(Mode::BRACKETED_PASTE),
             NamedPrivateMode::SgrMouse => self.mode.remove(Mode::SGR_MOUSE),
+            NamedPrivateMode::SgrPixelsMouse => self.mode.remove(Mode::SGR_PIXELS_MOUSE),
             NamedPrivateMode::Utf8Mouse => self.mode.remove(Mode::UTF8_MOUSE),
             NamedPrivateMode::AlternateScroll => self.mode.remove(Mode::ALTERNATE_SCROLL),
             NamedPrivateMode::LineWrap => self.mode.remove(Mode::LINE_WRAP),
@@ -1468,6 +1560,9 @@ impl<U: EventListener> Handler for Crosswords<U> {
                 // .send_event(RioEvent::CursorBlinkingChange, self.window_id);
             }
             NamedPrivateMode::SyncUpdate => (),
+            NamedPrivateMode::ColorSchemeUpdates => {
+                self.mode.remove(Mode::COLOR_SCHEME_UPDATES)
+            }
         }
     }
 
@@ -1501,6 +1596,9 @@ impl<U: EventListener> Handler for Crosswords<U> {
                     self.mode.contains(Mode::UTF8_MOUSE).into()
                 }
                 NamedPrivateMode::SgrMouse => self.mode.contains(Mode::SGR_MOUSE).into(),
+                NamedPrivateMode::SgrPixelsMouse => {
+                    self.mode.contains(Mode::SGR_PIXELS_MOUSE).into()
+                }
                 NamedPrivateMode::AlternateScroll => {
                     self.mode.contains(Mode::ALTERNATE_SCROLL).into()
                 }
@@ -1514,6 +1612,9 @@ impl<U: EventListener> Handler for Crosswords<U> {
                     self.mode.contains(Mode::BRACKETED_PASTE).into()
                 }
                 NamedPrivateMode::SyncUpdate => ModeState::Reset,
+                NamedPrivateMode::ColorSchemeUpdates => {
+                    self.mode.contains(Mode::COLOR_SCHEME_UPDATES).into()
+                }
                 NamedPrivateMode::ColumnMode => ModeState::NotSupported,
             },
             PrivateMode::Unknown(_) => ModeState::NotSupported,
@@ -1909,9 +2010,55 @@ impl<U: EventListener> Handler for Crosswords<U> {
         self.title = title.unwrap_or_default();
     }
 
-    fn set_current_directory(&mut self, path: std::path::PathBuf) {
-        trace!("Setting working directory {:?}", path);
+    fn set_current_directory(&mut self, host: Option<String>, path: std::path::PathBuf) {
+        trace!("Setting working directory {:?} on host {:?}", path, host);
         self.current_directory = Some(path);
+        self.current_host = host;
+    }
+
+    fn set_user_var(&mut self, name: String, value: String) {
+        trace!("Setting user variable {:?} to {:?}", name, value);
+        if name.len() + value.len() > USER_VAR_MAX_LEN {
+            warn!("Ignoring user variable {:?}, it is too long", name);
+            return;
+        }
+
+        if self.user_vars.len() >= USER_VARS_MAX_COUNT
+            && !self.user_vars.contains_key(&name)
+        {
+            warn!("Ignoring user variable {:?}, too many are set", name);
+            return;
+        }
+
+        self.user_vars.insert(name, value);
+    }
+
+    fn download_file(&mut self, name: Option<String>, data: Vec<u8>) {
+        self.event_proxy.send_event(
+            RioEvent::ConfirmDownload(name, Arc::new(data)),
+            self.window_id,
+        );
+    }
+
+    fn set_tab_color_component(&mut self, component: TabColorComponent, value: u8) {
+        let color = self.tab_color.get_or_insert_with(ColorRgb::default);
+        match component {
+            TabColorComponent::Red => color.r = value,
+            TabColorComponent::Green => color.g = value,
+            TabColorComponent::Blue => color.b = value,
+        }
+
+        let color = color.to_arr();
+        self.event_proxy.send_event(
+            RioEvent::TabColor(self.route_id, Some(color)),
+            self.window_id,
+        );
+    }
+
+    fn reset_tab_color(&mut self) {
+        self.tab_color = None;
+        self.event_proxy
+            .send_event(RioEvent::TabColor(self.route_id, None), self.window_id);
     }
 
     #[inline]
@@ -2163,6 +2310,27 @@ impl<U: EventListener> Handler for Crosswords<U> {
         };
     }
 
+    #[inline]
+    fn set_shift_escape(&mut self, value: u16) {
+        trace!("Setting shift escape: {}", value);
+        match value {
+            // Shift overrides mouse capture, selecting text.
+            0 | 3 => self.mode.remove(Mode::MOUSE_SHIFT_REPORT),
+            // Shift is reported as a modifier to the application.
+            1 | 2 => self.mode.insert(Mode::MOUSE_SHIFT_REPORT),
+            _ => debug!("unknown shift escape value: {}", value),
+        }
+    }
+
+    #[inline]
+    fn private_device_status(&mut self, arg: usize) {
+        trace!("Reporting private device status: {}", arg);
+        match arg {
+            996 => self.report_color_scheme(),
+            _ => debug!("unknown private device status query: {}", arg),
+        };
+    }
+
     #[inline]
     fn newline(&mut self) {
         self.linefeed();
@@ -2318,7 +2486,10 @@ impl<U: EventListener> Handler for Crosswords<U> {
 
     #[inline]
     fn bell(&mut self) {
-        warn!("[unimplemented] Bell");
+        trace!("Bell");
+        let urgent = self.mode.contains(Mode::URGENCY_HINTS);
+        self.event_proxy
+            .send_event(RioEvent::Bell(self.route_id, urgent), self.window_id);
     }
 
     #[inline]
@@ -2506,6 +2677,20 @@ impl<U: EventListener> Handler for Crosswords<U> {
             .send_event(RioEvent::PtyWrite(text), self.window_id);
     }
 
+    #[inline]
+    fn report_cell_size(&mut self, terminator: &str) {
+        // Cells are measured in pixels, so the scale is always one.
+        let text = format!(
+            "\x1b]1337;ReportCellSize={:.1};{:.1};1.0{terminator}",
+            self.graphics.cell_height, self.graphics.cell_width
+        );
+        debug!("report_cell_size {:?}", text);
+        self.event_proxy.send_event(
+            RioEvent::PtyWriteOnRoute(self.route_id, text),
+            self.window_id,
+        );
+    }
+
     #[inline]
     fn text_area_size_chars(&mut self) {
         let text = format!(
@@ -2646,15 +2831,79 @@ impl<U: EventListener> Handler for Crosswords<U> {
 
     #[inline]
     fn sixel_graphic_finish(&mut self) {
-        let parser = self.graphics.sixel_parser.take();
-        if let Some(parser) = parser {
-            match parser.finish() {
-                Ok((graphic, palette)) => self.insert_graphic(graphic, Some(palette)),
-                Err(err) => warn!("Failed to parse Sixel data: {}", err),
+        let mut parser = match self.graphics.sixel_parser.take() {
+            Some(parser) => parser,
+            None => {
+                warn!("Failed to sixel_graphic_finish");
+                return;
             }
-        } else {
-            warn!("Failed to sixel_graphic_finish");
+        };
+
+        if let Err(err) = parser.finish_command() {
+            warn!("Failed to parse Sixel data: {}", err);
+            return;
+        }
+
+        // Store the palette for the next graphic, if it is shared.
+        if !self.mode.contains(Mode::SIXEL_PRIV_PALETTE) {
+            self.graphics.sixel_shared_palette = Some(parser.palette().to_vec());
+        }
+
+        let (width, height) = parser.dimensions();
+        if width * height > self.graphics.limits.max_pixels {
+            warn!("Sixel graphic is too large: {}x{}", width, height);
+            return;
+        }
+
+        let (width, height) = match self.graphic_size(width, height, None) {
+            Some(size) => size,
+            None => return,
+        };
+
+        let graphic_id = self.place_graphic(width, height, None);
+        self.graphics.decode(DecodeJob {
+            id: graphic_id,
+            source: DecodeSource::Sixel(parser),
+            width,
+            height,
+            limits: self.graphics.limits,
+        });
+    }
+
+    #[inline]
+    fn insert_image_file(&mut self, transfer: FileTransfer) {
+        let limits = self.graphics.limits;
+        if transfer.decoded_len() > limits.max_bytes {
+            warn!("Image is too large: {} bytes", transfer.decoded_len());
+            return;
+        }
+
+        let (width, height) = match transfer.dimensions() {
+            Some(dimensions) => dimensions,
+            None => {
+                warn!("Can't read the size of the image");
+                return;
+            }
+        };
+
+        if width * height > limits.max_pixels {
+            warn!("Image is too large: {}x{}", width, height);
+            return;
         }
+
+        let (width, height) = match self.graphic_size(width, height, transfer.resize) {
+            Some(size) => size,
+            None => return,
+        };
+
+        let graphic_id = self.place_graphic(width, height, None);
+        self.graphics.decode(DecodeJob {
+            id: graphic_id,
+            source: DecodeSource::File(transfer),
+            width,
+            height,
+            limits,
+        });
     }
 
     #[inline]
@@ -2679,19 +2928,77 @@ impl<U: EventListener> Handler for Crosswords<U> {
             None => return,
         };
 
-        if graphic.width > MAX_GRAPHIC_DIMENSIONS[0]
-            || graphic.height > MAX_GRAPHIC_DIMENSIONS[1]
+        let (width, height) = match self.graphic_size(graphic.width, graphic.height, None)
         {
-            return;
-        }
+            Some(size) => size,
+            None => return,
+        };
 
-        let width = graphic.width as u16;
-        let height = graphic.height as u16;
+        let graphic_id = self.place_graphic(width, height, Some(&graphic));
 
-        if width == 0 || height == 0 {
-            return;
+        // Add the graphic data to the pending queue.
+        self.graphics.pending.push(GraphicData {
+            id: graphic_id,
+            ..graphic
+        });
+
+        self.event_proxy
+            .send_event(RioEvent::UpdateGraphicLibrary, self.window_id);
+    }
+}
+
+impl<U: EventListener> Crosswords<U> {
+    /// Size of a graphic once placed in the grid, `None` if it can't be
+    /// displayed.
+    fn graphic_size(
+        &self,
+        width: usize,
+        height: usize,
+        resize: Option<ResizeCommand>,
+    ) -> Option<(usize, usize)> {
+        let cell_width = self.graphics.cell_width as usize;
+        let cell_height = self.graphics.cell_height as usize;
+
+        let (width, height) = match resize {
+            Some(resize) => resize.dimensions(
+                width,
+                height,
+                cell_width,
+                cell_height,
+                cell_width * self.grid.columns(),
+                cell_height * self.grid.screen_lines(),
+            )?,
+            None => (width, height),
+        };
+
+        if width > MAX_GRAPHIC_DIMENSIONS[0]
+            || height > MAX_GRAPHIC_DIMENSIONS[1]
+            || width == 0
+            || height == 0
+        {
+            return None;
         }
 
+        Some((width, height))
+    }
+
+    /// Reserve the cells of a `width` x `height` graphic at the cursor and
+    /// return its identifier.
+    ///
+    /// Without its pixels, the graphic is assumed to be transparent, so the
+    /// graphics below it are kept.
+    fn place_graphic(
+        &mut self,
+        width: usize,
+        height: usize,
+        graphic: Option<&GraphicData>,
+    ) -> GraphicId {
+        let cell_width = self.graphics.cell_width as usize;
+        let cell_height = self.graphics.cell_height as usize;
+        let graphic_columns = width.div_ceil(cell_width);
+        let width = width as u16;
+        let height = height as u16;
+
         let graphic_id = self.graphics.next_id();
 
         // If SIXEL_DISPLAY is disabled, the start of the graphic is the
@@ -2722,7 +3029,7 @@ impl<U: EventListener> Handler for Crosswords<U> {
         // graphic.
 
         let skip_textures = {
-            if graphic.maybe_transparent() {
+            if graphic.is_none_or(|graphic| graphic.maybe_transparent()) {
                 HashSet::new()
             } else {
                 let mut set = HashSet::new();
@@ -2802,12 +3109,14 @@ impl<U: EventListener> Handler for Crosswords<U> {
                     Some(mut old_graphics)
                         if old_graphics.iter().any(|graphic| {
                             !skip_textures.contains(&graphic.texture.id)
-                        }) && !graphic.is_filled(
-                            offset_x as usize,
-                            offset_y as usize,
-                            cell_width,
-                            cell_height,
-                        ) =>
+                        }) && !graphic.is_some_and(|graphic| {
+                            graphic.is_filled(
+                                offset_x as usize,
+                                offset_y as usize,
+                                cell_width,
+                                cell_height,
+                            )
+                        }) =>
                     {
                         // Ensure that we don't exceed the graphics limit per cell.
                         while old_graphics.len() >= MAX_GRAPHICS_PER_CELL {
@@ -2833,21 +3142,13 @@ impl<U: EventListener> Handler for Crosswords<U> {
         }
 
         if self.mode.contains(Mode::SIXEL_CURSOR_TO_THE_RIGHT) {
-            let graphic_columns = graphic.width.div_ceil(cell_width);
             self.move_forward(Column(graphic_columns));
         } else if scrolling {
             self.linefeed();
             self.carriage_return();
         }
 
-        // Add the graphic data to the pending queue.
-        self.graphics.pending.push(GraphicData {
-            id: graphic_id,
-            ..graphic
-        });
-
-        self.event_proxy
-            .send_event(RioEvent::UpdateGraphicLibrary, self.window_id);
+        graphic_id
     }
 }
 
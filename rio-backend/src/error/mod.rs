@@ -20,6 +20,18 @@ impl RioError {
             report: RioErrorType::ConfigurationNotFound,
         }
     }
+
+    /// Unknown or deprecated keys found in a config that was loaded.
+    pub fn configuration_warnings(warnings: &[String]) -> Option<Self> {
+        if warnings.is_empty() {
+            return None;
+        }
+
+        Some(RioError {
+            level: RioErrorLevel::Warning,
+            report: RioErrorType::ConfigurationWarnings(warnings.join("\n\n")),
+        })
+    }
 }
 
 impl From<ConfigError> for RioError {
@@ -56,6 +68,8 @@ pub enum RioErrorType {
     InvalidConfigurationFormat(String),
     // configuration invalid theme
     InvalidConfigurationTheme(String),
+    // configuration has unknown or deprecated keys
+    ConfigurationWarnings(String),
 
     // reports that are ignored by RioErrorType
     IgnoredReport,
@@ -98,6 +112,9 @@ impl std::fmt::Display for RioErrorType {
             RioErrorType::InvalidConfigurationTheme(message) => {
                 write!(f, "Found an issue in the configured theme:\n\n{message}")
             }
+            RioErrorType::ConfigurationWarnings(message) => {
+                write!(f, "Found issues in the configuration file:\n\n{message}")
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 pub mod sync;
 
 use crate::clipboard::ClipboardType;
-use crate::config::colors::ColorRgb;
+use crate::config::colors::{ColorArray, ColorRgb};
 use crate::crosswords::grid::Scroll;
 use crate::crosswords::pos::{Direction, Pos};
 use crate::crosswords::search::{Match, RegexSearch};
@@ -65,7 +65,14 @@ pub enum RioEvent {
     CreateWindow,
     CloseWindow,
     CreateNativeTab(Option<String>),
+    CreateNativeTabWithProfile(String),
     CreateConfigEditor,
+    OpenProfilePicker,
+    OpenThemePicker,
+    /// Paste is waiting for the user to confirm it.
+    ConfirmPaste(String),
+    /// File sent through OSC 1337 waiting to be confirmed for download.
+    ConfirmDownload(Option<String>, Arc<Vec<u8>>),
     SelectNativeTabByIndex(usize),
     SelectNativeTabLast,
     SelectNativeTabNext,
@@ -85,6 +92,9 @@ pub enum RioEvent {
     /// Reset to the default window title.
     ResetTitle,
 
+    /// Tab color of a route set through OSC 6, `None` resets it.
+    TabColor(usize, Option<ColorArray>),
+
     /// Request to store a text string in the clipboard.
     ClipboardStore(ClipboardType, String),
 
@@ -109,6 +119,9 @@ pub enum RioEvent {
     /// Write some text to the PTY.
     PtyWrite(String),
 
+    /// Write some text to the PTY of a route, which may not be focused.
+    PtyWriteOnRoute(usize, String),
+
     /// Request to write the text area size.
     TextAreaSizeRequest(Arc<dyn Fn(WinsizeBuilder) -> String + Sync + Send + 'static>),
 
@@ -117,8 +130,9 @@ pub enum RioEvent {
 
     CursorBlinkingChangeOnRoute(usize),
 
-    /// Terminal bell ring.
-    Bell,
+    /// Terminal bell ring on a route, the flag tells whether urgency
+    /// hints (DEC mode 1042) are enabled.
+    Bell(usize, bool),
 
     /// Shutdown request.
     Exit,
@@ -147,7 +161,13 @@ impl Debug for RioEvent {
             RioEvent::TextAreaSizeRequest(_) => write!(f, "TextAreaSizeRequest"),
             RioEvent::ColorRequest(index, _) => write!(f, "ColorRequest({index})"),
             RioEvent::PtyWrite(text) => write!(f, "PtyWrite({text})"),
+            RioEvent::PtyWriteOnRoute(route_id, text) => {
+                write!(f, "PtyWriteOnRoute({route_id}, {text})")
+            }
             RioEvent::Title(title) => write!(f, "Title({title})"),
+            RioEvent::TabColor(route_id, color) => {
+                write!(f, "TabColor({route_id}, {color:?})")
+            }
             RioEvent::TitleWithSubtitle(title, subtitle) => {
                 write!(f, "TitleWithSubtitle({title}, {subtitle})")
             }
@@ -168,13 +188,16 @@ impl Debug for RioEvent {
             RioEvent::Render => write!(f, "Render"),
             RioEvent::RenderRoute(route) => write!(f, "Render route {route}"),
             RioEvent::Scroll(scroll) => write!(f, "Scroll {scroll:?}"),
-            RioEvent::Bell => write!(f, "Bell"),
+            RioEvent::Bell(route_id, urgent) => write!(f, "Bell({route_id}, {urgent})"),
             RioEvent::Exit => write!(f, "Exit"),
             RioEvent::Quit => write!(f, "Quit"),
             RioEvent::CloseTerminal(route) => write!(f, "CloseTerminal {route}"),
             RioEvent::CreateWindow => write!(f, "CreateWindow"),
             RioEvent::CloseWindow => write!(f, "CloseWindow"),
             RioEvent::CreateNativeTab(_) => write!(f, "CreateNativeTab"),
+            RioEvent::CreateNativeTabWithProfile(profile) => {
+                write!(f, "CreateNativeTabWithProfile({profile})")
+            }
             RioEvent::SelectNativeTabByIndex(tab_index) => {
                 write!(f, "SelectNativeTabByIndex({tab_index})")
             }
@@ -182,6 +205,12 @@ impl Debug for RioEvent {
             RioEvent::SelectNativeTabNext => write!(f, "SelectNativeTabNext"),
             RioEvent::SelectNativeTabPrev => write!(f, "SelectNativeTabPrev"),
             RioEvent::CreateConfigEditor => write!(f, "CreateConfigEditor"),
+            RioEvent::OpenProfilePicker => write!(f, "OpenProfilePicker"),
+            RioEvent::OpenThemePicker => write!(f, "OpenThemePicker"),
+            RioEvent::ConfirmPaste(message) => write!(f, "ConfirmPaste({message})"),
+            RioEvent::ConfirmDownload(name, data) => {
+                write!(f, "ConfirmDownload({name:?}, {} bytes)", data.len())
+            }
             RioEvent::UpdateConfig => write!(f, "ReloadConfiguration"),
             RioEvent::ReportToAssistant(error_report) => {
                 write!(f, "ReportToAssistant({})", error_report.report)
@@ -0,0 +1,326 @@
+// Headless terminal emulation.
+//
+// Drives `Crosswords` through the `ParserProcessor` without a window, PTY or
+// renderer. Used by the snapshot test suite and by anyone embedding Rio's
+// emulation core (e.g. for recording or replaying sessions).
+
+use crate::ansi::CursorShape;
+use crate::config::colors::{AnsiColor, NamedColor};
+use crate::crosswords::grid::Dimensions;
+use crate::crosswords::pos::{Column, Line, Pos};
+use crate::crosswords::square::{Flags, Square};
+use crate::crosswords::{Crosswords, CrosswordsSize, Mode};
+use crate::event::{EventListener, RioEvent, WindowId};
+use crate::performer::handler::ParserProcessor;
+use parking_lot::Mutex;
+use std::fmt;
+use std::sync::Arc;
+
+/// Flags that only describe cell layout and are already visible in the text.
+const LAYOUT_FLAGS: Flags = Flags::WRAPLINE
+    .union(Flags::WIDE_CHAR)
+    .union(Flags::WIDE_CHAR_SPACER)
+    .union(Flags::LEADING_WIDE_CHAR_SPACER);
+
+/// Event listener that stores every event sent by the terminal.
+#[derive(Clone, Default)]
+pub struct HeadlessListener {
+    events: Arc<Mutex<Vec<RioEvent>>>,
+}
+
+impl HeadlessListener {
+    /// Take all events received since the last call.
+    pub fn take_events(&self) -> Vec<RioEvent> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+impl EventListener for HeadlessListener {
+    fn event(&self) -> (Option<RioEvent>, bool) {
+        (None, false)
+    }
+
+    fn send_event(&self, event: RioEvent, _id: WindowId) {
+        self.events.lock().push(event);
+    }
+
+    fn send_event_with_high_priority(&self, event: RioEvent, _id: WindowId) {
+        self.events.lock().push(event);
+    }
+
+    fn send_global_event(&self, event: RioEvent) {
+        self.events.lock().push(event);
+    }
+}
+
+/// A terminal without a window: feed it bytes and read back its state.
+pub struct HeadlessTerminal {
+    terminal: Crosswords<HeadlessListener>,
+    parser: ParserProcessor,
+    listener: HeadlessListener,
+}
+
+impl HeadlessTerminal {
+    pub fn new(columns: usize, screen_lines: usize) -> Self {
+        let listener = HeadlessListener::default();
+        let terminal = Crosswords::new(
+            CrosswordsSize::new(columns, screen_lines),
+            CursorShape::Block,
+            listener.clone(),
+            WindowId::from(0),
+            0,
+        );
+
+        Self {
+            terminal,
+            parser: ParserProcessor::new(),
+            listener,
+        }
+    }
+
+    /// Process bytes as if they were read from the PTY.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.parser.advance(&mut self.terminal, *byte);
+        }
+        self.terminal.mark_content_changed();
+    }
+
+    /// Apply any pending synchronized update (`CSI ? 2026 h`) immediately,
+    /// as the PTY reader does once the sync timeout expires.
+    pub fn flush_sync(&mut self) {
+        if self.parser.sync_timeout().is_some() {
+            self.parser.stop_sync(&mut self.terminal);
+            self.terminal.mark_content_changed();
+        }
+    }
+
+    /// Wait for the images being decoded in the background and move them
+    /// to the pending queue, as the renderer does when it is woken up.
+    pub fn wait_for_graphics(&mut self) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        self.terminal.graphics.collect_decoded();
+        while self.terminal.graphics.is_decoding() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out decoding graphics"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            self.terminal.graphics.collect_decoded();
+        }
+    }
+
+    pub fn resize(&mut self, columns: usize, screen_lines: usize) {
+        self.terminal
+            .resize(CrosswordsSize::new(columns, screen_lines));
+    }
+
+    pub fn columns(&self) -> usize {
+        self.terminal.columns()
+    }
+
+    pub fn screen_lines(&self) -> usize {
+        self.terminal.screen_lines()
+    }
+
+    pub fn cursor(&self) -> Pos {
+        self.terminal.grid.cursor.pos
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.terminal.cursor().content
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.terminal.mode()
+    }
+
+    pub fn title(&self) -> &str {
+        &self.terminal.title
+    }
+
+    /// Underlying terminal, for state not covered by this API.
+    pub fn terminal(&self) -> &Crosswords<HeadlessListener> {
+        &self.terminal
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Crosswords<HeadlessListener> {
+        &mut self.terminal
+    }
+
+    /// Take all events emitted by the terminal since the last call.
+    pub fn take_events(&mut self) -> Vec<RioEvent> {
+        self.listener.take_events()
+    }
+
+    /// Take everything the terminal wrote back to the PTY (e.g. DA or DSR
+    /// replies) since the last call, concatenated.
+    pub fn take_pty_writes(&mut self) -> String {
+        self.take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                RioEvent::PtyWrite(text) | RioEvent::PtyWriteOnRoute(_, text) => {
+                    Some(text)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Text of a visible line, with trailing blanks removed.
+    pub fn line_text(&self, line: usize) -> String {
+        let row = &self.terminal.grid[self.viewport_line(line)];
+        let mut text = String::new();
+        for square in row[..].iter() {
+            if square
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            text.push(square.c);
+            if let Some(zerowidth) = square.zerowidth() {
+                text.extend(zerowidth);
+            }
+        }
+        text.truncate(text.trim_end_matches([' ', '\t']).len());
+        text
+    }
+
+    /// Text of the visible screen, one line per row.
+    pub fn screen_text(&self) -> String {
+        (0..self.screen_lines())
+            .map(|line| self.line_text(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let columns = self.columns();
+        let mut lines = Vec::with_capacity(self.screen_lines());
+        let mut attributes = Vec::new();
+
+        for line in 0..self.screen_lines() {
+            lines.push(self.line_text(line));
+
+            let row = &self.terminal.grid[self.viewport_line(line)];
+            let mut column = 0;
+            while column < columns {
+                let style = CellStyle::from(&row[Column(column)]);
+                let start = column;
+                while column < columns && CellStyle::from(&row[Column(column)]) == style {
+                    column += 1;
+                }
+
+                if !style.is_default() {
+                    attributes.push(AttributeRun {
+                        line,
+                        columns: start..column,
+                        style,
+                    });
+                }
+            }
+        }
+
+        let cursor = self.cursor();
+        Snapshot {
+            columns,
+            screen_lines: self.screen_lines(),
+            cursor: (cursor.row.0 as usize, cursor.col.0),
+            cursor_shape: self.cursor_shape(),
+            mode: self.mode(),
+            title: self.title().to_owned(),
+            lines,
+            attributes,
+        }
+    }
+
+    #[inline]
+    fn viewport_line(&self, line: usize) -> Line {
+        Line(line as i32 - self.terminal.display_offset() as i32)
+    }
+}
+
+/// Visual attributes of a single cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellStyle {
+    pub fg: AnsiColor,
+    pub bg: AnsiColor,
+    pub flags: Flags,
+}
+
+impl CellStyle {
+    fn is_default(&self) -> bool {
+        self.fg == AnsiColor::Named(NamedColor::Foreground)
+            && self.bg == AnsiColor::Named(NamedColor::Background)
+            && self.flags.is_empty()
+    }
+}
+
+impl From<&Square> for CellStyle {
+    fn from(square: &Square) -> Self {
+        Self {
+            fg: square.fg,
+            bg: square.bg,
+            flags: square.flags.difference(LAYOUT_FLAGS),
+        }
+    }
+}
+
+/// Consecutive cells of a line sharing the same non-default style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeRun {
+    pub line: usize,
+    pub columns: std::ops::Range<usize>,
+    pub style: CellStyle,
+}
+
+/// Serializable view of the visible terminal state.
+///
+/// The `Display` implementation is stable and meant to be stored as a
+/// golden file.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub columns: usize,
+    pub screen_lines: usize,
+    /// Cursor position as (line, column).
+    pub cursor: (usize, usize),
+    pub cursor_shape: CursorShape,
+    pub mode: Mode,
+    pub title: String,
+    pub lines: Vec<String>,
+    pub attributes: Vec<AttributeRun>,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "size: {}x{}", self.columns, self.screen_lines)?;
+        writeln!(
+            f,
+            "cursor: {},{} {:?}",
+            self.cursor.0, self.cursor.1, self.cursor_shape
+        )?;
+        let modes: Vec<&str> = self.mode.iter_names().map(|(name, _)| name).collect();
+        writeln!(f, "modes: {}", modes.join(" | "))?;
+        writeln!(f, "title: {}", self.title)?;
+        writeln!(f, "--- screen")?;
+        for line in &self.lines {
+            writeln!(f, "|{line}")?;
+        }
+        writeln!(f, "--- attributes")?;
+        for run in &self.attributes {
+            write!(
+                f,
+                "{}:{}..{} fg={:?} bg={:?}",
+                run.line, run.columns.start, run.columns.end, run.style.fg, run.style.bg
+            )?;
+            if !run.style.flags.is_empty() {
+                let flags: Vec<&str> =
+                    run.style.flags.iter_names().map(|(name, _)| name).collect();
+                write!(f, " flags={}", flags.join("|"))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ pub mod config;
 pub mod crosswords;
 pub mod error;
 pub mod event;
+pub mod headless;
 pub mod performer;
 pub mod selection;
 
@@ -1,4 +1,6 @@
-use crate::ansi::iterm2_image_protocol;
+use crate::ansi::iterm2_image_protocol::{
+    self, Command, FileTransfer, MAX_TRANSFER_SIZE,
+};
 use crate::ansi::CursorShape;
 use crate::ansi::{sixel, KeyboardModes, KeyboardModesApplyBehavior};
 use crate::config::colors::{AnsiColor, ColorRgb, NamedColor};
@@ -16,7 +18,7 @@ use crate::crosswords::attr::Attr;
 use crate::ansi::control::C0;
 use crate::ansi::{
     mode::{Mode, NamedPrivateMode, PrivateMode},
-    ClearMode, LineClearMode, TabulationClearMode,
+    ClearMode, LineClearMode, TabColorComponent, TabulationClearMode,
 };
 use std::fmt::Write;
 
@@ -137,8 +139,14 @@ pub trait Handler {
     /// OSC to set window title.
     fn set_title(&mut self, _: Option<String>) {}
 
-    /// OSC to set current directory.
-    fn set_current_directory(&mut self, _: std::path::PathBuf) {}
+    /// OSC to set current directory and the host it belongs to.
+    fn set_current_directory(&mut self, _: Option<String>, _: std::path::PathBuf) {}
+
+    /// OSC 6 to set one component of the tab color.
+    fn set_tab_color_component(&mut self, _: TabColorComponent, _: u8) {}
+
+    /// OSC 6 to reset the tab color to its default.
+    fn reset_tab_color(&mut self) {}
 
     /// Set the cursor style.
     fn set_cursor_style(&mut self, _style: Option<CursorShape>, _blinking: bool) {}
@@ -173,6 +181,9 @@ pub trait Handler {
     /// Report device status.
     fn device_status(&mut self, _: usize) {}
 
+    /// Report DEC private device status.
+    fn private_device_status(&mut self, _: usize) {}
+
     /// Move cursor forward `cols`.
     fn move_forward(&mut self, _: Column) {}
 
@@ -287,6 +298,10 @@ pub trait Handler {
     /// DECRPM - report private mode.
     fn report_private_mode(&mut self, _mode: PrivateMode) {}
 
+    /// XTSHIFTESCAPE - Set whether shift is reported to applications in mouse
+    /// mode instead of overriding mouse capture.
+    fn set_shift_escape(&mut self, _: u16) {}
+
     /// DECSTBM - Set the terminal scrolling region.
     fn set_scrolling_region(&mut self, _top: usize, _bottom: Option<usize>) {}
 
@@ -358,6 +373,18 @@ pub trait Handler {
     /// Insert a new graphic item.
     fn insert_graphic(&mut self, _data: GraphicData, _palette: Option<Vec<ColorRgb>>) {}
 
+    /// Display an image file sent through OSC 1337.
+    fn insert_image_file(&mut self, _transfer: FileTransfer) {}
+
+    /// Offer a file sent through OSC 1337 for download.
+    fn download_file(&mut self, _name: Option<String>, _data: Vec<u8>) {}
+
+    /// Set a user variable through OSC 1337.
+    fn set_user_var(&mut self, _name: String, _value: String) {}
+
+    /// Report the cell size through OSC 1337.
+    fn report_cell_size(&mut self, _terminator: &str) {}
+
     /// Set hyperlink.
     fn set_hyperlink(&mut self, _: Option<Hyperlink>) {}
 
@@ -393,6 +420,9 @@ struct ProcessorState {
 
     /// State for synchronized terminal updates.
     sync_state: SyncState,
+
+    /// OSC 1337 multipart file transfer in progress.
+    file_transfer: Option<FileTransfer>,
 }
 
 #[derive(Debug)]
@@ -520,6 +550,15 @@ impl<'a, H: Handler + 'a> Performer<'a, H> {
     ) -> Performer<'b, H> {
         Performer { state, handler }
     }
+
+    /// Display a complete OSC 1337 file, or offer to download it.
+    fn finish_file_transfer(&mut self, transfer: FileTransfer) {
+        if transfer.inline {
+            self.handler.insert_image_file(transfer);
+        } else if let Some(data) = transfer.data() {
+            self.handler.download_file(transfer.name, data);
+        }
+    }
 }
 
 impl<U: Handler> copa::Perform for Performer<'_, U> {
@@ -654,18 +693,49 @@ impl<U: Handler> copa::Perform for Performer<'_, U> {
             // Inform current directory.
             b"7" => {
                 if let Ok(s) = std::str::from_utf8(params[1]) {
-                    if let Ok(url) = url::Url::parse(s) {
-                        let path = url.path();
+                    if let Ok(mut url) = url::Url::parse(s) {
+                        let host = url
+                            .host_str()
+                            .filter(|host| !host.is_empty())
+                            .map(String::from);
+
+                        // Without the host the url can be converted to a path
+                        // even when it belongs to a remote machine, this also
+                        // percent decodes it and drops the leading slash on
+                        // windows.
+                        let _ = url.set_host(None);
+                        let path = match url.to_file_path() {
+                            Ok(path) => path,
+                            Err(..) => url.path().into(),
+                        };
+
+                        self.handler.set_current_directory(host, path);
+                    }
+                }
+            }
 
-                        // NB the path coming from Url has a leading slash; must slice that off
-                        // in windows.
-                        #[cfg(windows)]
-                        let path = &path[1..];
+            // Set tab color (iTerm2).
+            // `OSC 6 ; 1 ; bg ; red|green|blue ; brightness ; N` sets a component
+            // and `OSC 6 ; 1 ; bg ; * ; default` resets it.
+            b"6" => match params {
+                [_, b"1", b"bg", b"*", b"default"] => self.handler.reset_tab_color(),
+                [_, b"1", b"bg", component, b"brightness", value] => {
+                    let component = match *component {
+                        b"red" => TabColorComponent::Red,
+                        b"green" => TabColorComponent::Green,
+                        b"blue" => TabColorComponent::Blue,
+                        _ => return unhandled(params),
+                    };
 
-                        self.handler.set_current_directory(path.into());
+                    match parse_number(value) {
+                        Some(value) => {
+                            self.handler.set_tab_color_component(component, value)
+                        }
+                        None => unhandled(params),
                     }
                 }
-            }
+                _ => unhandled(params),
+            },
 
             // Hyperlink.
             b"8" if params.len() > 2 => {
@@ -792,11 +862,37 @@ impl<U: Handler> copa::Perform for Performer<'_, U> {
 
             // OSC 1337 is not necessarily only used by iTerm2 protocol
             // OSC 1337 is equal to xterm OSC 50
-            b"1337" => {
-                if let Some(graphic) = iterm2_image_protocol::parse(params) {
-                    self.handler.insert_graphic(graphic, None);
+            b"1337" => match iterm2_image_protocol::parse(params) {
+                Some(Command::File(transfer)) => self.finish_file_transfer(transfer),
+                Some(Command::MultipartFile(transfer)) => {
+                    self.state.file_transfer = Some(transfer);
                 }
-            }
+                Some(Command::FilePart(part)) => {
+                    let transfer = match self.state.file_transfer.as_mut() {
+                        Some(transfer) => transfer,
+                        None => return unhandled(params),
+                    };
+
+                    if transfer.contents.len() + part.len() > MAX_TRANSFER_SIZE {
+                        warn!("OSC 1337 file transfer is too large, discarding it");
+                        self.state.file_transfer = None;
+                        return;
+                    }
+
+                    transfer.contents.extend_from_slice(&part);
+                }
+                Some(Command::FileEnd) => match self.state.file_transfer.take() {
+                    Some(transfer) => self.finish_file_transfer(transfer),
+                    None => unhandled(params),
+                },
+                Some(Command::SetUserVar(name, value)) => {
+                    self.handler.set_user_var(name, value);
+                }
+                Some(Command::ReportCellSize) => {
+                    self.handler.report_cell_size(terminator);
+                }
+                None => unhandled(params),
+            },
 
             _ => unhandled(params),
         }
@@ -822,7 +918,10 @@ impl<U: Handler> copa::Perform for Performer<'_, U> {
             }};
         }
 
-        if should_ignore || intermediates.len() > 1 {
+        // DECRQM for private modes, `CSI ? Ps $ p`, is the only sequence
+        // handled with two intermediates.
+        let is_private_decrqm = action == 'p' && intermediates == [b'?', b'$'];
+        if should_ignore || (intermediates.len() > 1 && !is_private_decrqm) {
             return;
         }
 
@@ -946,6 +1045,7 @@ impl<U: Handler> copa::Perform for Performer<'_, U> {
                 }
             }
             ('n', []) => handler.device_status(next_param_or(0) as usize),
+            ('n', [b'?']) => handler.private_device_status(next_param_or(0) as usize),
             ('P', []) => handler.delete_chars(next_param_or(1) as usize),
             ('p', [b'$']) => {
                 let mode = next_param_or(0);
@@ -985,6 +1085,7 @@ impl<U: Handler> copa::Perform for Performer<'_, U> {
                 handler.graphics_attribute(next_param_or(0), next_param_or(0))
             }
             ('s', []) => handler.save_cursor_position(),
+            ('s', [b'>']) => handler.set_shift_escape(next_param_or(0)),
             ('T', []) => handler.scroll_down(next_param_or(1) as usize),
             ('t', []) => match next_param_or(1) as usize {
                 14 => handler.text_area_size_pixels(),
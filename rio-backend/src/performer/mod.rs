@@ -180,6 +180,7 @@ where
             for byte in &buf[..unprocessed] {
                 state.parser.advance(&mut **terminal, *byte);
             }
+            terminal.mark_content_changed();
 
             processed += unprocessed;
             unprocessed = 0;
@@ -307,7 +308,10 @@ where
 
                 // Handle synchronized update timeout.
                 if events.is_empty() {
-                    state.parser.stop_sync(&mut *self.terminal.lock());
+                    let mut terminal = self.terminal.lock();
+                    state.parser.stop_sync(&mut *terminal);
+                    terminal.mark_content_changed();
+                    drop(terminal);
                     self.event_proxy
                         .send_event(RioEvent::RenderRoute(self.route_id), self.window_id);
 
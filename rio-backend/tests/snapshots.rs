@@ -0,0 +1,433 @@
+// Golden-file tests for terminal emulation.
+//
+// Each test replays an escape-sequence fixture from `tests/fixtures` through
+// `HeadlessTerminal` and compares the serialized screen with the matching
+// file in `tests/snapshots`. The `vttest_*` fixtures reproduce the sequences
+// vttest emits for its cursor, autowrap, rendition, insert/delete and
+// scrolling region screens.
+//
+// To regenerate snapshots after an intended behavior change run:
+// RIO_UPDATE_SNAPSHOTS=1 cargo test -p rio-backend --test snapshots
+
+use rio_backend::event::RioEvent;
+use rio_backend::headless::HeadlessTerminal;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read(&path).unwrap_or_else(|err| panic!("reading {path:?}: {err}"))
+}
+
+fn assert_snapshot(name: &str, terminal: &HeadlessTerminal) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.snap"));
+    let actual = terminal.snapshot().to_string();
+
+    if std::env::var_os("RIO_UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!("reading {path:?}: {err} (run with RIO_UPDATE_SNAPSHOTS=1 to create it)")
+    });
+    assert!(
+        expected == actual,
+        "snapshot {name} does not match\n--- expected\n{expected}\n--- actual\n{actual}"
+    );
+}
+
+fn replay(name: &str, columns: usize, lines: usize) -> HeadlessTerminal {
+    let mut terminal = HeadlessTerminal::new(columns, lines);
+    terminal.feed(&fixture(&format!("{name}.vt")));
+    terminal
+}
+
+#[test]
+fn vttest_cursor_box() {
+    assert_snapshot("vttest_cursor_box", &replay("vttest_cursor_box", 80, 24));
+}
+
+#[test]
+fn vttest_autowrap() {
+    assert_snapshot("vttest_autowrap", &replay("vttest_autowrap", 80, 24));
+}
+
+#[test]
+fn vttest_sgr() {
+    assert_snapshot("vttest_sgr", &replay("vttest_sgr", 80, 24));
+}
+
+#[test]
+fn vttest_insert_delete() {
+    assert_snapshot(
+        "vttest_insert_delete",
+        &replay("vttest_insert_delete", 80, 24),
+    );
+}
+
+#[test]
+fn vttest_scroll_region() {
+    assert_snapshot(
+        "vttest_scroll_region",
+        &replay("vttest_scroll_region", 80, 24),
+    );
+}
+
+#[test]
+fn title_and_modes() {
+    assert_snapshot("title_and_modes", &replay("title_and_modes", 60, 4));
+}
+
+#[test]
+fn wide_chars() {
+    assert_snapshot("wide_chars", &replay("wide_chars", 20, 6));
+}
+
+#[test]
+fn alt_screen() {
+    let terminal = replay("alt_screen", 30, 4);
+    assert_snapshot("alt_screen", &terminal);
+}
+
+#[test]
+fn resize_reflow() {
+    let mut terminal = replay("resize_reflow", 30, 6);
+    assert_snapshot("resize_reflow", &terminal);
+
+    terminal.resize(12, 6);
+    assert_snapshot("resize_reflow_narrow", &terminal);
+
+    terminal.resize(40, 6);
+    assert_snapshot("resize_reflow_wide", &terminal);
+}
+
+#[test]
+fn ansicode_document() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../resources/ansicode.txt");
+    let text = std::fs::read(path).unwrap();
+
+    // Translate newlines the way the PTY line discipline does (onlcr).
+    let mut bytes = Vec::with_capacity(text.len());
+    for byte in text {
+        if byte == b'\n' {
+            bytes.push(b'\r');
+        }
+        bytes.push(byte);
+    }
+
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(&bytes);
+    assert_snapshot("ansicode", &terminal);
+}
+
+#[test]
+fn device_attributes_reply() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b[6n");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[1;1R");
+
+    terminal.feed(b"abc\x1b[6n");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[1;4R");
+}
+
+#[test]
+fn tab_color_osc() {
+    use rio_backend::event::RioEvent;
+
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b]6;1;bg;red;brightness;255\x07");
+    terminal.feed(b"\x1b]6;1;bg;blue;brightness;51\x1b\\");
+    let colors: Vec<_> = terminal
+        .take_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            RioEvent::TabColor(_, color) => Some(color),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        colors,
+        vec![Some([1.0, 0.0, 0.0, 1.0]), Some([1.0, 0.0, 0.2, 1.0])]
+    );
+
+    terminal.feed(b"\x1b]6;1;bg;*;default\x07");
+    assert!(matches!(
+        terminal.take_events().as_slice(),
+        [RioEvent::TabColor(_, None)]
+    ));
+}
+
+#[test]
+#[cfg(not(windows))]
+fn current_directory_osc() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b]7;file:///home/user/my%20project\x07");
+    assert_eq!(
+        terminal.terminal().current_directory(),
+        Some(std::path::Path::new("/home/user/my project"))
+    );
+    assert_eq!(terminal.terminal().current_host(), None);
+
+    terminal.feed(b"\x1b]7;file://remote.example.com/srv/app\x1b\\");
+    assert_eq!(
+        terminal.terminal().current_directory(),
+        Some(std::path::Path::new("/srv/app"))
+    );
+    assert_eq!(
+        terminal.terminal().current_host(),
+        Some("remote.example.com")
+    );
+}
+
+#[test]
+fn private_mode_reports() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b[?25$p");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?25;1$y");
+
+    terminal.feed(b"\x1b[?25l\x1b[?25$p");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?25;2$y");
+
+    // Other sequences with two intermediates are still ignored.
+    terminal.feed(b"\x1b[?25$h\x1b[?25$p");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?25;2$y");
+}
+
+#[test]
+fn color_scheme_reports() {
+    use rio_backend::ansi::ColorScheme;
+
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b[?996n");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?997;1n");
+
+    // Changes are not reported until mode 2031 is enabled.
+    terminal.terminal_mut().set_color_scheme(ColorScheme::Light);
+    assert_eq!(terminal.take_pty_writes(), "");
+    terminal.feed(b"\x1b[?996n");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?997;2n");
+
+    terminal.feed(b"\x1b[?2031h\x1b[?2031$p");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?2031;1$y");
+
+    terminal.terminal_mut().set_color_scheme(ColorScheme::Dark);
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?997;1n");
+    // Setting the same scheme again does not notify.
+    terminal.terminal_mut().set_color_scheme(ColorScheme::Dark);
+    assert_eq!(terminal.take_pty_writes(), "");
+
+    terminal.feed(b"\x1b[?2031l");
+    terminal.terminal_mut().set_color_scheme(ColorScheme::Light);
+    assert_eq!(terminal.take_pty_writes(), "");
+}
+
+/// 1x1 red PNG, encoded in base64.
+const PNG_BASE64: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVR4nGP4z8AAAAMBAQDJ/pLvAAAAAElFTkSuQmCC";
+
+#[test]
+fn iterm2_download_file() {
+    use rio_backend::event::RioEvent;
+
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    // name=notes.txt, contents "hello".
+    terminal.feed(b"\x1b]1337;File=name=bm90ZXMudHh0;size=5:aGVsbG8=\x07");
+    match terminal.take_events().as_slice() {
+        [RioEvent::ConfirmDownload(name, data)] => {
+            assert_eq!(name.as_deref(), Some("notes.txt"));
+            assert_eq!(data.as_slice(), b"hello");
+        }
+        events => panic!("unexpected events {events:?}"),
+    }
+
+    // Multipart transfers download once complete.
+    terminal.feed(b"\x1b]1337;MultipartFile=size=5\x07");
+    terminal.feed(b"\x1b]1337;FilePart=aGVs\x07\x1b]1337;FilePart=bG8=\x07");
+    assert!(terminal.take_events().is_empty());
+    terminal.feed(b"\x1b]1337;FileEnd\x07");
+    match terminal.take_events().as_slice() {
+        [RioEvent::ConfirmDownload(None, data)] => assert_eq!(data.as_slice(), b"hello"),
+        events => panic!("unexpected events {events:?}"),
+    }
+
+    // Parts without a multipart transfer are ignored.
+    terminal.feed(b"\x1b]1337;FilePart=aGVs\x07\x1b]1337;FileEnd\x07");
+    assert!(terminal.take_events().is_empty());
+}
+
+#[test]
+fn iterm2_multipart_image() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.terminal_mut().graphics.cell_width = 8.;
+    terminal.terminal_mut().graphics.cell_height = 16.;
+
+    terminal.feed(b"\x1b]1337;MultipartFile=inline=1\x07");
+    // Parts may split the base64 contents anywhere.
+    let (first, second) = PNG_BASE64.split_at(21);
+    for part in [first, second] {
+        terminal.feed(format!("\x1b]1337;FilePart={part}\x07").as_bytes());
+    }
+    assert!(terminal.terminal().graphics.pending.is_empty());
+
+    terminal.feed(b"\x1b]1337;FileEnd\x1b\\");
+    terminal.wait_for_graphics();
+    let pending = &terminal.terminal().graphics.pending;
+    assert_eq!(pending.len(), 1);
+    assert_eq!((pending[0].width, pending[0].height), (1, 1));
+}
+
+#[test]
+fn iterm2_image_decoded_in_background() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.terminal_mut().graphics.cell_width = 8.;
+    terminal.terminal_mut().graphics.cell_height = 16.;
+
+    // The cells are reserved before the image is decoded, so the text sent
+    // after it is written below the placeholder.
+    terminal.feed(
+        format!("\x1b]1337;File=inline=1;width=2;height=2:{PNG_BASE64}\x07after")
+            .as_bytes(),
+    );
+    assert!(terminal.terminal().graphics.pending.is_empty());
+    assert!(terminal.line_text(0).trim().is_empty());
+    assert!(terminal.line_text(1).starts_with("after"));
+
+    terminal.wait_for_graphics();
+    let pending = &terminal.terminal().graphics.pending;
+    assert_eq!(pending.len(), 1);
+    // Fitted inside the 2x2 cells keeping the aspect ratio.
+    assert_eq!((pending[0].width, pending[0].height), (16, 16));
+}
+
+#[test]
+fn sixel_decoded_in_background() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.terminal_mut().graphics.cell_width = 8.;
+    terminal.terminal_mut().graphics.cell_height = 16.;
+
+    terminal.feed(b"\x1bPq#1;2;100;0;0#1~~\x1b\\after");
+    // The palette is shared with the next graphic right away.
+    let palette = terminal.terminal().graphics.sixel_shared_palette.as_ref();
+    assert!(palette.is_some_and(|palette| palette.len() > 1));
+    assert!(terminal.line_text(1).starts_with("after"));
+
+    terminal.wait_for_graphics();
+    let pending = &terminal.terminal().graphics.pending;
+    assert_eq!(pending.len(), 1);
+    assert_eq!((pending[0].width, pending[0].height), (2, 6));
+}
+
+#[test]
+fn iterm2_image_limits() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.terminal_mut().graphics.cell_width = 8.;
+    terminal.terminal_mut().graphics.cell_height = 16.;
+    terminal.terminal_mut().graphics.limits.max_bytes = 16;
+
+    let image = format!("\x1b]1337;File=inline=1:{PNG_BASE64}\x07");
+    terminal.feed(image.as_bytes());
+    terminal.wait_for_graphics();
+    assert!(terminal.terminal().graphics.pending.is_empty());
+
+    terminal.terminal_mut().graphics.limits.max_bytes = 1024;
+    terminal.terminal_mut().graphics.limits.max_pixels = 0;
+    terminal.feed(image.as_bytes());
+    terminal.wait_for_graphics();
+    assert!(terminal.terminal().graphics.pending.is_empty());
+
+    terminal.terminal_mut().graphics.limits.max_pixels = 1;
+    terminal.feed(image.as_bytes());
+    terminal.wait_for_graphics();
+    assert_eq!(terminal.terminal().graphics.pending.len(), 1);
+}
+
+#[test]
+fn iterm2_user_vars() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b]1337;SetUserVar=gitBranch=bWFpbg==\x07");
+    assert_eq!(terminal.terminal().user_var("gitBranch"), Some("main"));
+    assert_eq!(terminal.terminal().user_var("gitbranch"), None);
+
+    terminal.feed(b"\x1b]1337;SetUserVar=gitBranch=ZmVhdHVyZQ==\x1b\\");
+    assert_eq!(terminal.terminal().user_var("gitBranch"), Some("feature"));
+}
+
+#[test]
+fn iterm2_user_vars_limits() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    for index in 0..300 {
+        terminal.feed(format!("\x1b]1337;SetUserVar=var{index}=eA==\x07").as_bytes());
+    }
+    assert_eq!(terminal.terminal().user_var("var255"), Some("x"));
+    assert_eq!(terminal.terminal().user_var("var256"), None);
+
+    // Variables already set can still be updated.
+    terminal.feed(b"\x1b]1337;SetUserVar=var0=eQ==\x07");
+    assert_eq!(terminal.terminal().user_var("var0"), Some("y"));
+
+    let value = "YWFh".repeat(1400);
+    terminal.feed(format!("\x1b]1337;SetUserVar=var1={value}\x07").as_bytes());
+    assert_eq!(terminal.terminal().user_var("var1"), Some("x"));
+}
+
+#[test]
+fn iterm2_report_cell_size() {
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.terminal_mut().graphics.cell_width = 8.;
+    terminal.terminal_mut().graphics.cell_height = 17.5;
+
+    terminal.feed(b"\x1b]1337;ReportCellSize\x07");
+    assert_eq!(
+        terminal.take_pty_writes(),
+        "\x1b]1337;ReportCellSize=17.5;8.0;1.0\x07"
+    );
+
+    terminal.feed(b"\x1b]1337;ReportCellSize\x1b\\");
+    assert_eq!(
+        terminal.take_pty_writes(),
+        "\x1b]1337;ReportCellSize=17.5;8.0;1.0\x1b\\"
+    );
+
+    // The reply goes to the split that asked, which may not be focused.
+    terminal.feed(b"\x1b]1337;ReportCellSize\x07");
+    let route_id = terminal.terminal().route_id;
+    assert!(matches!(
+        terminal.take_events().as_slice(),
+        [RioEvent::PtyWriteOnRoute(id, _)] if *id == route_id
+    ));
+}
+
+#[test]
+fn mouse_encodings_and_shift_escape() {
+    use rio_backend::crosswords::Mode;
+
+    let mut terminal = HeadlessTerminal::new(80, 24);
+    terminal.feed(b"\x1b[?1000h\x1b[?1006h\x1b[?1016h");
+    assert!(terminal.mode().contains(Mode::SGR_PIXELS_MOUSE));
+    assert!(!terminal.mode().contains(Mode::SGR_MOUSE));
+
+    terminal.feed(b"\x1b[?1016$p");
+    assert_eq!(terminal.take_pty_writes(), "\x1b[?1016;1$y");
+
+    // Mouse encodings are mutually exclusive.
+    terminal.feed(b"\x1b[?1006h");
+    assert!(terminal.mode().contains(Mode::SGR_MOUSE));
+    assert!(!terminal.mode().contains(Mode::SGR_PIXELS_MOUSE));
+    terminal.feed(b"\x1b[?1016h\x1b[?1016l");
+    assert!(!terminal.mode().contains(Mode::SGR_PIXELS_MOUSE));
+
+    // XTSHIFTESCAPE, shift overrides mouse capture by default.
+    assert!(!terminal.mode().contains(Mode::MOUSE_SHIFT_REPORT));
+    terminal.feed(b"\x1b[>1s");
+    assert!(terminal.mode().contains(Mode::MOUSE_SHIFT_REPORT));
+    terminal.feed(b"\x1b[>0s");
+    assert!(!terminal.mode().contains(Mode::MOUSE_SHIFT_REPORT));
+    terminal.feed(b"\x1b[>2s");
+    assert!(terminal.mode().contains(Mode::MOUSE_SHIFT_REPORT));
+    terminal.feed(b"\x1b[>s");
+    assert!(!terminal.mode().contains(Mode::MOUSE_SHIFT_REPORT));
+}
@@ -0,0 +1,155 @@
+// Golden-file tests for theme conversion.
+//
+// Each theme in `tests/fixtures/themes` is converted into a Rio theme and
+// compared with the matching file in `tests/snapshots/themes`.
+//
+// To regenerate snapshots after an intended behavior change run:
+// RIO_UPDATE_SNAPSHOTS=1 cargo test -p rio-backend --test themes
+
+use rio_backend::config::theme::{self, ThemeFormat};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/themes")
+        .join(name)
+}
+
+fn assert_theme(name: &str, fixture_name: &str, format: ThemeFormat) {
+    let path = fixture(fixture_name);
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(ThemeFormat::detect(&path, &content), format);
+
+    let imported = theme::import(&path).unwrap_or_else(|err| panic!("{err}"));
+    // The converted theme is a valid Rio theme.
+    imported.theme().unwrap();
+    let actual = imported.to_toml();
+
+    let snapshot = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots/themes")
+        .join(format!("{name}.toml"));
+    if std::env::var_os("RIO_UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&snapshot, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&snapshot).unwrap_or_else(|err| {
+        panic!(
+            "reading {snapshot:?}: {err} (run with RIO_UPDATE_SNAPSHOTS=1 to create it)"
+        )
+    });
+    assert!(
+        expected == actual,
+        "theme {name} does not match\n--- expected\n{expected}\n--- actual\n{actual}"
+    );
+}
+
+#[test]
+fn iterm2() {
+    assert_theme("iterm2", "iterm2.itermcolors", ThemeFormat::Iterm2);
+}
+
+#[test]
+fn alacritty_toml() {
+    assert_theme("alacritty_toml", "alacritty.toml", ThemeFormat::Alacritty);
+}
+
+#[test]
+fn alacritty_yaml() {
+    assert_theme(
+        "alacritty_yaml",
+        "alacritty.yml",
+        ThemeFormat::AlacrittyYaml,
+    );
+}
+
+#[test]
+fn kitty() {
+    assert_theme("kitty", "kitty.conf", ThemeFormat::Kitty);
+}
+
+#[test]
+fn windows_terminal() {
+    assert_theme(
+        "windows_terminal",
+        "windows-terminal.json",
+        ThemeFormat::WindowsTerminal,
+    );
+}
+
+#[test]
+fn base16() {
+    assert_theme("base16", "base16.yaml", ThemeFormat::Base16);
+}
+
+#[test]
+fn base16_palette() {
+    assert_theme("base16_palette", "base16-palette.yaml", ThemeFormat::Base16);
+}
+
+#[test]
+fn ghostty() {
+    assert_theme("ghostty", "ghostty", ThemeFormat::Ghostty);
+}
+
+#[test]
+fn rio_theme_is_not_imported() {
+    let dir = std::env::temp_dir().join("rio-test-themes");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("rio.toml");
+    std::fs::write(&path, "[colors]\nbackground = '#000000'\n").unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(ThemeFormat::detect(&path, &content), ThemeFormat::Rio);
+    assert!(theme::import(&path).is_err());
+    assert!(theme::load(&path).is_ok());
+}
+
+#[test]
+fn theme_file() {
+    let dir = PathBuf::from("/themes");
+    assert_eq!(theme::theme_file(&dir, "dracula"), dir.join("dracula.toml"));
+    assert_eq!(
+        theme::theme_file(&dir, "catppuccin.mocha"),
+        dir.join("catppuccin.toml")
+    );
+    assert_eq!(
+        theme::theme_file(&dir, "imported/dracula.itermcolors"),
+        dir.join("imported/dracula.itermcolors")
+    );
+    assert_eq!(
+        theme::theme_file(&dir, "/tmp/kitty.conf"),
+        PathBuf::from("/tmp/kitty.conf")
+    );
+}
+
+#[test]
+fn list() {
+    let dir = std::env::temp_dir().join("rio-test-themes-list");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    for name in [
+        "dracula.toml",
+        "catppuccin.mocha.toml",
+        "Nord.itermcolors",
+        "ghostty-theme",
+        ".hidden",
+        "notes.txt",
+    ] {
+        std::fs::write(dir.join(name), "").unwrap();
+    }
+
+    assert_eq!(
+        theme::list(&dir),
+        vec![
+            "catppuccin.mocha.toml",
+            "dracula",
+            "ghostty-theme",
+            "Nord.itermcolors"
+        ]
+    );
+    assert!(theme::list(&dir.join("missing")).is_empty());
+    for name in theme::list(&dir) {
+        assert!(theme::theme_file(&dir, &name).is_file(), "{name}");
+    }
+}
@@ -473,6 +473,7 @@ impl ApplicationHandler<UserEvent> for Application {
             | WindowEvent::CursorEntered { .. }
             | WindowEvent::AxisMotion { .. }
             | WindowEvent::DroppedFile(_)
+            | WindowEvent::DroppedText(_)
             | WindowEvent::HoveredFile(_)
             | WindowEvent::Destroyed
             | WindowEvent::Touch(_)
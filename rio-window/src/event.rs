@@ -233,6 +233,11 @@ pub enum WindowEvent {
     /// hovered.
     HoveredFileCancelled,
 
+    /// Plain text has been dropped into the window.
+    ///
+    /// Only emitted on Wayland.
+    DroppedText(String),
+
     /// The window gained or lost focus.
     ///
     /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -1109,6 +1114,7 @@ mod tests {
                 with_window_event(DroppedFile("x.txt".into()));
                 with_window_event(HoveredFile("x.txt".into()));
                 with_window_event(HoveredFileCancelled);
+                with_window_event(DroppedText("x".into()));
                 with_window_event(Ime(Enabled));
                 with_window_event(CursorMoved {
                     device_id: did,
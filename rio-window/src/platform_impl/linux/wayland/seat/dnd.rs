@@ -0,0 +1,449 @@
+//! Drag and drop handling through `wl_data_device`.
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use percent_encoding::percent_decode_str;
+use tracing::warn;
+
+use sctk::data_device_manager::data_device::{DataDeviceData, DataDeviceHandler};
+use sctk::data_device_manager::data_offer::{DataOfferHandler, DragOffer};
+use sctk::data_device_manager::data_source::DataSourceHandler;
+use sctk::data_device_manager::WritePipe;
+use sctk::reexports::calloop::PostAction;
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+
+use crate::event::WindowEvent;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, WindowId};
+
+const URI_LIST_MIME: &str = "text/uri-list";
+
+/// Plain text mime types, in order of preference.
+const TEXT_MIMES: [&str; 3] = ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DndKind {
+    Files,
+    Text,
+}
+
+/// The drag and drop session active on a seat.
+#[derive(Debug)]
+pub struct DndState {
+    window_id: WindowId,
+    offer: DragOffer,
+    mime_type: String,
+    kind: DndKind,
+    /// Paths of a file drag, `None` until the offer has been read.
+    paths: Option<Vec<PathBuf>>,
+    dropped: bool,
+}
+
+impl DataDeviceHandler for WinitState {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        _: f64,
+        _: f64,
+        surface: &WlSurface,
+    ) {
+        let data = match data_device.data::<DataDeviceData>() {
+            Some(data) => data,
+            None => return,
+        };
+        let offer = match data.drag_offer() {
+            Some(offer) => offer,
+            None => return,
+        };
+
+        let window_id = wayland::make_wid(surface);
+        let mime_type = offer.with_mime_types(preferred_mime_type);
+        let (mime_type, kind) = match mime_type {
+            Some(mime_type) if self.windows.get_mut().contains_key(&window_id) => {
+                mime_type
+            }
+            _ => {
+                offer.accept_mime_type(offer.serial, None);
+                return;
+            }
+        };
+
+        offer.accept_mime_type(offer.serial, Some(mime_type.clone()));
+        offer.set_actions(DndAction::Copy, DndAction::Copy);
+
+        let seat_id = data.seat().id();
+        match self.seats.get_mut(&seat_id) {
+            Some(seat_state) => {
+                seat_state.dnd = Some(DndState {
+                    window_id,
+                    offer: offer.clone(),
+                    mime_type: mime_type.clone(),
+                    kind,
+                    paths: None,
+                    dropped: false,
+                });
+            }
+            None => return,
+        }
+
+        // Files are read right away to report them while hovering.
+        if kind == DndKind::Files {
+            self.read_drag_offer(seat_id, &offer, mime_type);
+        }
+    }
+
+    fn leave(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+    ) {
+        let seat_state = match data_device
+            .data::<DataDeviceData>()
+            .and_then(|data| self.seats.get_mut(&data.seat().id()))
+        {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        // The session continues after a drop until the data is read.
+        if seat_state.dnd.as_ref().is_none_or(|dnd| dnd.dropped) {
+            return;
+        }
+
+        if let Some(dnd) = seat_state.dnd.take() {
+            if dnd.paths.is_some() {
+                self.events_sink
+                    .push_window_event(WindowEvent::HoveredFileCancelled, dnd.window_id);
+            }
+        }
+    }
+
+    fn motion(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataDevice,
+        _: f64,
+        _: f64,
+    ) {
+    }
+
+    fn selection(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {}
+
+    fn drop_performed(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+    ) {
+        let seat_id = match data_device.data::<DataDeviceData>() {
+            Some(data) => data.seat().id(),
+            None => return,
+        };
+        let dnd = match self
+            .seats
+            .get_mut(&seat_id)
+            .and_then(|seat_state| seat_state.dnd.as_mut())
+        {
+            Some(dnd) => dnd,
+            None => return,
+        };
+
+        dnd.dropped = true;
+        match dnd.kind {
+            DndKind::Files if dnd.paths.is_some() => self.finish_drag(&seat_id),
+            // Still reading, the drop is reported once the data arrives.
+            DndKind::Files => (),
+            DndKind::Text => {
+                let offer = dnd.offer.clone();
+                let mime_type = dnd.mime_type.clone();
+                self.read_drag_offer(seat_id, &offer, mime_type);
+            }
+        }
+    }
+}
+
+impl DataOfferHandler for WinitState {
+    fn source_actions(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        offer: &mut DragOffer,
+        _: DndAction,
+    ) {
+        offer.set_actions(DndAction::Copy, DndAction::Copy);
+    }
+
+    fn selected_action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+}
+
+// Rio never offers data through drag and drop, sources are unused.
+impl DataSourceHandler for WinitState {
+    fn accept_mime(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: Option<String>,
+    ) {
+    }
+
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: String,
+        _: WritePipe,
+    ) {
+    }
+
+    fn cancelled(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn dnd_dropped(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn dnd_finished(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {}
+
+    fn action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: DndAction,
+    ) {
+    }
+}
+
+impl WinitState {
+    /// Read the offer on the event loop, the source only writes once the request is flushed.
+    fn read_drag_offer(
+        &mut self,
+        seat_id: ObjectId,
+        offer: &DragOffer,
+        mime_type: String,
+    ) {
+        let offer_id = offer.inner().id();
+        let pipe = match offer.receive(mime_type) {
+            Ok(pipe) => pipe,
+            Err(err) => {
+                warn!("Failed to receive drag and drop data: {err}");
+                self.cancel_drag(&seat_id, &offer_id);
+                return;
+            }
+        };
+
+        let (source_seat_id, source_offer_id) = (seat_id.clone(), offer_id.clone());
+        let mut data = Vec::new();
+        let result = self.loop_handle.insert_source(pipe, move |_, file, state| {
+            let mut buf = [0u8; 4096];
+            match (&**file).read(&mut buf) {
+                Ok(0) => {
+                    state.drag_data_received(
+                        &source_seat_id,
+                        &source_offer_id,
+                        std::mem::take(&mut data),
+                    );
+                    PostAction::Remove
+                }
+                Ok(len) => {
+                    data.extend_from_slice(&buf[..len]);
+                    PostAction::Continue
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    PostAction::Continue
+                }
+                Err(err) => {
+                    warn!("Failed to read drag and drop data: {err}");
+                    state.cancel_drag(&source_seat_id, &source_offer_id);
+                    PostAction::Remove
+                }
+            }
+        });
+
+        if let Err(err) = result {
+            warn!("Failed to read drag and drop data: {err}");
+            self.cancel_drag(&seat_id, &offer_id);
+        }
+    }
+
+    fn drag_data_received(
+        &mut self,
+        seat_id: &ObjectId,
+        offer_id: &ObjectId,
+        data: Vec<u8>,
+    ) {
+        self.dispatched_events = true;
+
+        let dnd = match self
+            .seats
+            .get_mut(seat_id)
+            .and_then(|seat_state| seat_state.dnd.as_mut())
+        {
+            // Ignore data from a previous session.
+            Some(dnd) if &dnd.offer.inner().id() == offer_id => dnd,
+            _ => return,
+        };
+
+        match dnd.kind {
+            DndKind::Files => {
+                let paths = parse_uri_list(&String::from_utf8_lossy(&data));
+                if !dnd.dropped {
+                    for path in &paths {
+                        self.events_sink.push_window_event(
+                            WindowEvent::HoveredFile(path.clone()),
+                            dnd.window_id,
+                        );
+                    }
+                }
+
+                dnd.paths = Some(paths);
+                if dnd.dropped {
+                    self.finish_drag(seat_id);
+                }
+            }
+            DndKind::Text => {
+                let text = String::from_utf8_lossy(&data).into_owned();
+                if !text.is_empty() {
+                    self.events_sink
+                        .push_window_event(WindowEvent::DroppedText(text), dnd.window_id);
+                }
+                self.finish_drag(seat_id);
+            }
+        }
+    }
+
+    /// Report the dropped files and end the session.
+    fn finish_drag(&mut self, seat_id: &ObjectId) {
+        let dnd = match self
+            .seats
+            .get_mut(seat_id)
+            .and_then(|seat_state| seat_state.dnd.take())
+        {
+            Some(dnd) => dnd,
+            None => return,
+        };
+
+        for path in dnd.paths.unwrap_or_default() {
+            self.events_sink
+                .push_window_event(WindowEvent::DroppedFile(path), dnd.window_id);
+        }
+
+        dnd.offer.finish();
+        dnd.offer.destroy();
+    }
+
+    /// End the session after its data could not be read, so neither the
+    /// window nor the source wait for it.
+    fn cancel_drag(&mut self, seat_id: &ObjectId, offer_id: &ObjectId) {
+        self.dispatched_events = true;
+
+        let seat_state = match self.seats.get_mut(seat_id) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+        let dnd = match seat_state.dnd.take() {
+            Some(dnd) if &dnd.offer.inner().id() == offer_id => dnd,
+            other => {
+                seat_state.dnd = other;
+                return;
+            }
+        };
+
+        if dnd.kind == DndKind::Files {
+            self.events_sink
+                .push_window_event(WindowEvent::HoveredFileCancelled, dnd.window_id);
+        }
+
+        // Finishing is a protocol error before the drop.
+        if dnd.dropped {
+            dnd.offer.finish();
+        } else {
+            dnd.offer.accept_mime_type(dnd.offer.serial, None);
+        }
+        dnd.offer.destroy();
+    }
+}
+
+/// Pick the mime type to receive, preferring files over text.
+fn preferred_mime_type(mime_types: &[String]) -> Option<(String, DndKind)> {
+    if mime_types
+        .iter()
+        .any(|mime_type| mime_type == URI_LIST_MIME)
+    {
+        return Some((URI_LIST_MIME.to_owned(), DndKind::Files));
+    }
+
+    TEXT_MIMES
+        .iter()
+        .find(|text_mime| mime_types.iter().any(|mime_type| mime_type == *text_mime))
+        .map(|mime_type| (mime_type.to_string(), DndKind::Text))
+}
+
+/// Parse local file paths out of a `text/uri-list`.
+fn parse_uri_list(data: &str) -> Vec<PathBuf> {
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|uri| {
+            let path = uri.strip_prefix("file://")?;
+            // Only local files can be opened, the host is either empty or localhost.
+            let path = path.strip_prefix("localhost").unwrap_or(path);
+            if !path.starts_with('/') {
+                return None;
+            }
+
+            let path = percent_decode_str(path).decode_utf8().ok()?;
+            Some(PathBuf::from(path.into_owned()))
+        })
+        .collect()
+}
+
+sctk::delegate_data_device!(WinitState);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_list() {
+        let data = "# comment\r\nfile:///tmp/a%20b.txt\r\nfile://localhost/etc/hosts\r\n\
+                    https://example.com/\r\nfile://remote/tmp/c\r\n\r\n";
+        assert_eq!(
+            parse_uri_list(data),
+            vec![PathBuf::from("/tmp/a b.txt"), PathBuf::from("/etc/hosts")]
+        );
+    }
+
+    #[test]
+    fn preferred_mime() {
+        let mime_types = |list: &[&str]| -> Vec<String> {
+            list.iter().map(|mime_type| mime_type.to_string()).collect()
+        };
+
+        assert_eq!(
+            preferred_mime_type(&mime_types(&["text/plain", URI_LIST_MIME])),
+            Some((URI_LIST_MIME.to_owned(), DndKind::Files))
+        );
+        assert_eq!(
+            preferred_mime_type(&mime_types(&["text/plain", "UTF8_STRING"])),
+            Some(("UTF8_STRING".to_owned(), DndKind::Text))
+        );
+        assert_eq!(preferred_mime_type(&mime_types(&["image/png"])), None);
+    }
+}
@@ -12,6 +12,7 @@ use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 use sctk::reexports::protocols::wp::relative_pointer::zv1::client::zwp_relative_pointer_v1::ZwpRelativePointerV1;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 
+use sctk::data_device_manager::data_device::DataDevice;
 use sctk::seat::pointer::{ThemeSpec, ThemedPointer};
 use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 
@@ -19,6 +20,7 @@ use crate::event::WindowEvent;
 use crate::keyboard::ModifiersState;
 use crate::platform_impl::wayland::state::WinitState;
 
+mod dnd;
 mod keyboard;
 mod pointer;
 mod text_input;
@@ -28,6 +30,7 @@ pub use pointer::relative_pointer::RelativePointerState;
 pub use pointer::{PointerConstraintsState, WinitPointerData, WinitPointerDataExt};
 pub use text_input::{TextInputState, ZwpTextInputV3Ext};
 
+use dnd::DndState;
 use keyboard::{KeyboardData, KeyboardState};
 use text_input::TextInputData;
 use touch::TouchPoint;
@@ -57,11 +60,23 @@ pub struct WinitSeatState {
 
     /// Whether we have pending modifiers.
     modifiers_pending: bool,
+
+    /// The data device bound on the seat, used for drag and drop.
+    ///
+    /// Only held to keep receiving events, it's released on drop.
+    #[allow(dead_code)]
+    data_device: Option<DataDevice>,
+
+    /// The drag and drop session over one of our windows.
+    dnd: Option<DndState>,
 }
 
 impl WinitSeatState {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(data_device: Option<DataDevice>) -> Self {
+        Self {
+            data_device,
+            ..Default::default()
+        }
     }
 }
 
@@ -204,10 +219,15 @@ impl SeatHandler for WinitState {
     fn new_seat(
         &mut self,
         _connection: &Connection,
-        _queue_handle: &QueueHandle<Self>,
+        queue_handle: &QueueHandle<Self>,
         seat: WlSeat,
     ) {
-        self.seats.insert(seat.id(), WinitSeatState::new());
+        let data_device = self
+            .data_device_manager_state
+            .as_ref()
+            .map(|manager| manager.get_data_device(queue_handle, &seat));
+        self.seats
+            .insert(seat.id(), WinitSeatState::new(data_device));
     }
 
     fn remove_seat(
@@ -12,6 +12,7 @@ use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 
 use sctk::compositor::{CompositorHandler, CompositorState};
+use sctk::data_device_manager::DataDeviceManagerState;
 use sctk::output::{OutputHandler, OutputState};
 use sctk::registry::{ProvidesRegistryState, RegistryState};
 use sctk::seat::pointer::ThemedPointer;
@@ -54,6 +55,9 @@ pub struct WinitState {
     /// The seat state responsible for all sorts of input.
     pub seat_state: SeatState,
 
+    /// The data device manager used for drag and drop.
+    pub data_device_manager_state: Option<DataDeviceManagerState>,
+
     /// The shm for software buffers, such as cursors.
     pub shm: Shm,
 
@@ -144,10 +148,15 @@ impl WinitState {
         let monitors = output_state.outputs().map(MonitorHandle::new).collect();
 
         let seat_state = SeatState::new(globals, queue_handle);
+        let data_device_manager_state =
+            DataDeviceManagerState::bind(globals, queue_handle).ok();
 
         let mut seats = AHashMap::default();
         for seat in seat_state.seats() {
-            seats.insert(seat.id(), WinitSeatState::new());
+            let data_device = data_device_manager_state
+                .as_ref()
+                .map(|manager| manager.get_data_device(queue_handle, &seat));
+            seats.insert(seat.id(), WinitSeatState::new(data_device));
         }
 
         let (viewporter_state, fractional_scaling_manager) =
@@ -166,6 +175,7 @@ impl WinitState {
             subcompositor_state: subcompositor_state.map(Arc::new),
             output_state,
             seat_state,
+            data_device_manager_state,
             shm,
             custom_cursor_pool,
 
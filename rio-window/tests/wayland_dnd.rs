@@ -0,0 +1,489 @@
+//! Drag and drop against a real Wayland compositor.
+//!
+//! A second client maps a window next to the one of the event loop and
+//! drags data into it with `zwlr_virtual_pointer_v1`, so the test needs a
+//! compositor implementing that protocol. It is skipped unless
+//! `RIO_TEST_WAYLAND_DISPLAY` names the socket of such a compositor.
+//! `misc/scripts/test-wayland-dnd.sh` (`make test-wayland`) runs it
+//! against a headless sway.
+//!
+//! The source window is expected on the left half of the output and the
+//! event loop window on the right one, as sway tiles them.
+
+#![cfg(wayland_platform)]
+
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::AsFd;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rio_window::application::ApplicationHandler;
+use rio_window::event::WindowEvent;
+use rio_window::event_loop::{ActiveEventLoop, EventLoop};
+use rio_window::platform::pump_events::EventLoopExtPumpEvents;
+use rio_window::platform::wayland::EventLoopBuilderExtWayland;
+use rio_window::window::{Window, WindowId};
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_buffer::WlBuffer;
+use wayland_client::protocol::wl_compositor::WlCompositor;
+use wayland_client::protocol::wl_data_device::{self, WlDataDevice};
+use wayland_client::protocol::wl_data_device_manager::{DndAction, WlDataDeviceManager};
+use wayland_client::protocol::wl_data_offer::WlDataOffer;
+use wayland_client::protocol::wl_data_source::{self, WlDataSource};
+use wayland_client::protocol::wl_pointer::{self, ButtonState, WlPointer};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::{self, Capability, WlSeat};
+use wayland_client::protocol::wl_shm::{Format, WlShm};
+use wayland_client::protocol::wl_shm_pool::WlShmPool;
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_client::{
+    delegate_noop, event_created_child, Connection, Dispatch, EventQueue, Proxy,
+    QueueHandle, WEnum,
+};
+use wayland_protocols::xdg::shell::client::xdg_surface::{self, XdgSurface};
+use wayland_protocols::xdg::shell::client::xdg_toplevel::{self, XdgToplevel};
+use wayland_protocols::xdg::shell::client::xdg_wm_base::{self, XdgWmBase};
+use wayland_protocols_wlr::virtual_pointer::v1::client::zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1;
+use wayland_protocols_wlr::virtual_pointer::v1::client::zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1;
+
+#[path = "../examples/util/fill.rs"]
+mod fill;
+
+const DISPLAY_ENV: &str = "RIO_TEST_WAYLAND_DISPLAY";
+const TIMEOUT: Duration = Duration::from_secs(10);
+const BTN_LEFT: u32 = 0x110;
+
+/// Pointer positions, in thousandths of the output.
+const SOURCE_POSITION: (u32, u32) = (250, 500);
+const TARGET_POSITION: (u32, u32) = (750, 500);
+const EXTENT: u32 = 1000;
+
+#[test]
+fn wayland_drag_and_drop() {
+    let display = match std::env::var(DISPLAY_ENV) {
+        Ok(display) => display,
+        Err(_) => {
+            eprintln!("skipping wayland drag and drop test, {DISPLAY_ENV} is not set");
+            return;
+        }
+    };
+    std::env::set_var("WAYLAND_DISPLAY", &display);
+
+    // The source window is mapped first so the compositor places the event
+    // loop window after it.
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let (drag_tx, drag_rx) = mpsc::channel();
+    let source = thread::spawn(move || run_source(ready_tx, drag_rx));
+    ready_rx
+        .recv_timeout(TIMEOUT)
+        .expect("source window not mapped");
+
+    let mut event_loop = EventLoop::builder()
+        .with_wayland()
+        .with_any_thread(true)
+        .build()
+        .unwrap();
+    let mut app = App::default();
+    assert!(
+        pump_until(&mut event_loop, &mut app, |app| app.drawn),
+        "window not drawn"
+    );
+
+    let path = std::env::temp_dir().join("rio dnd test.txt");
+    let uri = format!("file://{}\r\n", path.display()).replace(' ', "%20");
+    drag_tx
+        .send(Drag {
+            mime_type: "text/uri-list",
+            data: uri,
+        })
+        .unwrap();
+    assert!(
+        pump_until(&mut event_loop, &mut app, |app| {
+            app.events.contains(&Dropped::File(path.clone()))
+        }),
+        "file not dropped, got {:?}",
+        app.events
+    );
+    assert_eq!(
+        app.events,
+        vec![Dropped::Hovered(path.clone()), Dropped::File(path)]
+    );
+
+    app.events.clear();
+    drag_tx
+        .send(Drag {
+            mime_type: "text/plain;charset=utf-8",
+            data: String::from("echo rio"),
+        })
+        .unwrap();
+    assert!(
+        pump_until(&mut event_loop, &mut app, |app| !app.events.is_empty()),
+        "text not dropped"
+    );
+    assert_eq!(app.events, vec![Dropped::Text(String::from("echo rio"))]);
+
+    drop(drag_tx);
+    source.join().unwrap();
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Dropped {
+    Hovered(PathBuf),
+    Cancelled,
+    File(PathBuf),
+    Text(String),
+}
+
+#[derive(Default)]
+struct App {
+    window: Option<Window>,
+    drawn: bool,
+    events: Vec<Dropped>,
+}
+
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_none() {
+            let attributes = Window::default_attributes().with_title("rio dnd target");
+            self.window = Some(event_loop.create_window(attributes).unwrap());
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        match event {
+            WindowEvent::RedrawRequested => {
+                if let Some(window) = self.window.as_ref() {
+                    fill::fill_window(window);
+                    self.drawn = true;
+                }
+            }
+            WindowEvent::HoveredFile(path) => self.events.push(Dropped::Hovered(path)),
+            WindowEvent::HoveredFileCancelled => self.events.push(Dropped::Cancelled),
+            WindowEvent::DroppedFile(path) => self.events.push(Dropped::File(path)),
+            WindowEvent::DroppedText(text) => self.events.push(Dropped::Text(text)),
+            _ => (),
+        }
+    }
+}
+
+fn pump_until(
+    event_loop: &mut EventLoop<()>,
+    app: &mut App,
+    done: impl Fn(&App) -> bool,
+) -> bool {
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        event_loop.pump_app_events(Some(Duration::from_millis(16)), app);
+        if done(app) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Data dragged from the source window to the event loop one.
+struct Drag {
+    mime_type: &'static str,
+    data: String,
+}
+
+#[derive(Default)]
+struct Source {
+    shm: Option<WlShm>,
+    surface: Option<WlSurface>,
+    size: (i32, i32),
+    mapped: bool,
+    has_pointer: bool,
+    button_serial: Option<u32>,
+    drag: Option<Drag>,
+    finished: bool,
+}
+
+/// Maps the source window then performs the drags sent on `drags`.
+fn run_source(ready: Sender<()>, drags: Receiver<Drag>) {
+    let conn = Connection::connect_to_env().unwrap();
+    let (globals, mut queue) = registry_queue_init::<Source>(&conn).unwrap();
+    let qh = queue.handle();
+
+    let compositor: WlCompositor = globals.bind(&qh, 1..=4, ()).unwrap();
+    let wm_base: XdgWmBase = globals.bind(&qh, 1..=1, ()).unwrap();
+    let seat: WlSeat = globals.bind(&qh, 1..=5, ()).unwrap();
+    let data_device_manager: WlDataDeviceManager = globals.bind(&qh, 3..=3, ()).unwrap();
+    let pointer_manager: ZwlrVirtualPointerManagerV1 =
+        globals.bind(&qh, 1..=1, ()).unwrap();
+
+    let mut state = Source {
+        shm: Some(globals.bind(&qh, 1..=1, ()).unwrap()),
+        size: (200, 200),
+        ..Default::default()
+    };
+
+    let surface = compositor.create_surface(&qh, ());
+    let xdg_surface = wm_base.get_xdg_surface(&surface, &qh, ());
+    let toplevel = xdg_surface.get_toplevel(&qh, ());
+    toplevel.set_title(String::from("rio dnd source"));
+    surface.commit();
+    state.surface = Some(surface.clone());
+    dispatch_until(&mut queue, &mut state, |state| state.mapped);
+
+    // The seat only gets a pointer once the virtual one exists.
+    let virtual_pointer = pointer_manager.create_virtual_pointer(Some(&seat), &qh, ());
+    dispatch_until(&mut queue, &mut state, |state| state.has_pointer);
+    let _pointer = seat.get_pointer(&qh, ());
+    let data_device = data_device_manager.get_data_device(&seat, &qh, ());
+    ready.send(()).unwrap();
+
+    let start = Instant::now();
+    let time = || start.elapsed().as_millis() as u32;
+    let move_to = |(x, y): (u32, u32)| {
+        virtual_pointer.motion_absolute(time(), x, y, EXTENT, EXTENT);
+        virtual_pointer.frame();
+    };
+    let button = |state: ButtonState| {
+        virtual_pointer.button(time(), BTN_LEFT, state);
+        virtual_pointer.frame();
+    };
+
+    for drag in drags {
+        state.button_serial = None;
+        state.finished = false;
+
+        move_to(SOURCE_POSITION);
+        button(ButtonState::Pressed);
+        dispatch_until(&mut queue, &mut state, |state| {
+            state.button_serial.is_some()
+        });
+
+        let data_source = data_device_manager.create_data_source(&qh, ());
+        data_source.offer(drag.mime_type.to_string());
+        data_source.set_actions(DndAction::Copy);
+        data_device.start_drag(
+            Some(&data_source),
+            &surface,
+            None,
+            state.button_serial.unwrap(),
+        );
+        state.drag = Some(drag);
+
+        // Move in steps so the target sees the pointer entering it.
+        for step in 1..=10 {
+            let x =
+                SOURCE_POSITION.0 + (TARGET_POSITION.0 - SOURCE_POSITION.0) * step / 10;
+            move_to((x, TARGET_POSITION.1));
+            conn.flush().unwrap();
+            queue.dispatch_pending(&mut state).unwrap();
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        button(ButtonState::Released);
+        dispatch_until(&mut queue, &mut state, |state| state.finished);
+        data_source.destroy();
+    }
+
+    virtual_pointer.destroy();
+    toplevel.destroy();
+    xdg_surface.destroy();
+    let _ = conn.roundtrip();
+}
+
+fn dispatch_until(
+    queue: &mut EventQueue<Source>,
+    state: &mut Source,
+    done: impl Fn(&Source) -> bool,
+) {
+    let deadline = Instant::now() + TIMEOUT;
+    while !done(state) {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for the compositor"
+        );
+        queue.roundtrip(state).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+impl Source {
+    /// Attaches an opaque buffer of the current size.
+    fn draw(&mut self, qh: &QueueHandle<Self>) {
+        let (width, height) = self.size;
+        let stride = width * 4;
+        let path =
+            std::env::temp_dir().join(format!("rio-dnd-source-{}", std::process::id()));
+        let file = File::create(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        file.set_len((stride * height) as u64).unwrap();
+
+        let pool =
+            self.shm
+                .as_ref()
+                .unwrap()
+                .create_pool(file.as_fd(), stride * height, qh, ());
+        let buffer =
+            pool.create_buffer(0, width, height, stride, Format::Xrgb8888, qh, ());
+        let surface = self.surface.as_ref().unwrap();
+        surface.attach(Some(&buffer), 0, 0);
+        surface.damage(0, 0, width, height);
+        surface.commit();
+        pool.destroy();
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for Source {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgWmBase, ()> for Source {
+    fn event(
+        _: &mut Self,
+        wm_base: &XdgWmBase,
+        event: xdg_wm_base::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<XdgSurface, ()> for Source {
+    fn event(
+        state: &mut Self,
+        xdg_surface: &XdgSurface,
+        event: xdg_surface::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let xdg_surface::Event::Configure { serial } = event {
+            xdg_surface.ack_configure(serial);
+            state.draw(qh);
+            state.mapped = true;
+        }
+    }
+}
+
+impl Dispatch<XdgToplevel, ()> for Source {
+    fn event(
+        state: &mut Self,
+        _: &XdgToplevel,
+        event: xdg_toplevel::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_toplevel::Event::Configure { width, height, .. } = event {
+            if width > 0 && height > 0 {
+                state.size = (width, height);
+            }
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for Source {
+    fn event(
+        state: &mut Self,
+        _: &WlSeat,
+        event: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+        {
+            state.has_pointer = capabilities.contains(Capability::Pointer);
+        }
+    }
+}
+
+impl Dispatch<WlPointer, ()> for Source {
+    fn event(
+        state: &mut Self,
+        _: &WlPointer,
+        event: wl_pointer::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_pointer::Event::Button {
+            serial,
+            state: WEnum::Value(ButtonState::Pressed),
+            ..
+        } = event
+        {
+            state.button_serial = Some(serial);
+        }
+    }
+}
+
+impl Dispatch<WlDataSource, ()> for Source {
+    fn event(
+        state: &mut Self,
+        _: &WlDataSource,
+        event: wl_data_source::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_data_source::Event::Send { mime_type, fd } => {
+                if let Some(drag) = state.drag.as_ref() {
+                    if drag.mime_type == mime_type {
+                        let _ = File::from(fd).write_all(drag.data.as_bytes());
+                    }
+                }
+            }
+            wl_data_source::Event::DndFinished | wl_data_source::Event::Cancelled => {
+                state.finished = true;
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<WlDataDevice, ()> for Source {
+    fn event(
+        _: &mut Self,
+        _: &WlDataDevice,
+        _: wl_data_device::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+
+    event_created_child!(Source, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, ()),
+    ]);
+}
+
+delegate_noop!(Source: WlCompositor);
+delegate_noop!(Source: WlShmPool);
+delegate_noop!(Source: WlDataDeviceManager);
+delegate_noop!(Source: ZwlrVirtualPointerManagerV1);
+delegate_noop!(Source: ZwlrVirtualPointerV1);
+delegate_noop!(Source: ignore WlShm);
+delegate_noop!(Source: ignore WlBuffer);
+delegate_noop!(Source: ignore WlSurface);
+delegate_noop!(Source: ignore WlDataOffer);
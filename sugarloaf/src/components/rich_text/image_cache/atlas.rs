@@ -1,3 +1,5 @@
+use super::{AddImage, ImageId, ImageLocation};
+
 /// Shelf style dynamic atlas allocator.
 pub struct AtlasAllocator {
     width: u16,
@@ -51,6 +53,9 @@ impl AtlasAllocator {
                     if let Some(line_index) = self.allocate_line(padded_height) {
                         (line_index, FreeSlot::Direct(0))
                     } else {
+                        // No room for a new line, so take what is needed
+                        // from an empty line and leave the rest free.
+                        self.split_line(line_index, padded_height);
                         (line_index, slot)
                     }
                 } else {
@@ -83,8 +88,10 @@ impl AtlasAllocator {
                         self.slots[prev as usize].next = slot.next;
                     } else if slot.next == !0 {
                         // We're filling the last slot with no previous
-                        // slot. Revert to the offset state.
-                        self.lines[line_index].state = slot_end;
+                        // slot, so the line is full. Revert to the offset
+                        // state, the slot may end before the line does
+                        // when the space after it is allocated.
+                        self.lines[line_index].state = self.width as u32;
                     } else {
                         self.lines[line_index].state = FRAGMENTED_BIT | slot.next;
                     }
@@ -96,17 +103,74 @@ impl AtlasAllocator {
     }
 
     /// Deallocates the slot with the specified coordinates and width.
-    #[allow(unused)]
     pub fn deallocate(&mut self, x: u16, y: u16, width: u16) -> bool {
         let res = self.deallocate_impl(x, y, width).is_some();
         while self.lines.last().map(|l| l.state) == Some(0) {
             let line = self.lines.pop().unwrap();
             self.y = line.y;
         }
+        self.merge_empty_lines();
         res
     }
 
+    /// Extends the atlas to `height`, existing allocations are kept.
+    pub fn grow(&mut self, height: u16) {
+        self.height = self.height.max(height);
+    }
+
+    /// Removes every allocation.
+    pub fn clear(&mut self) {
+        self.y = 0;
+        self.lines.clear();
+        self.slots.clear();
+        self.free_slot = !0;
+    }
+
+    #[inline]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Number of shelves, empty ones included.
     #[allow(unused)]
+    #[inline]
+    pub fn lines_len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Joins neighbour lines without allocations so the space can be
+    /// reused by taller images.
+    fn merge_empty_lines(&mut self) {
+        let mut index = 0;
+        while index + 1 < self.lines.len() {
+            if self.lines[index].state == 0 && self.lines[index + 1].state == 0 {
+                let next = self.lines.remove(index + 1);
+                self.lines[index].height += next.height;
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Shrinks an empty line to `padded_height`, the remaining height
+    /// becomes a new empty line right below it.
+    fn split_line(&mut self, line_index: usize, padded_height: u16) {
+        let line = self.lines[line_index];
+        if line.state != 0 || line.height <= padded_height {
+            return;
+        }
+
+        self.lines[line_index].height = padded_height;
+        self.lines.insert(
+            line_index + 1,
+            Line {
+                y: line.y + padded_height,
+                height: line.height - padded_height,
+                state: 0,
+            },
+        );
+    }
+
     fn deallocate_impl(&mut self, x: u16, y: u16, width: u16) -> Option<()> {
         let (line_index, &line) = if y == 0 {
             self.lines
@@ -219,7 +283,6 @@ impl AtlasAllocator {
         Some(line_index)
     }
 
-    #[allow(unused)]
     fn allocate_slot(&mut self, x: u16, width: u16) -> Option<u32> {
         let slot = Slot { x, width, next: !0 };
         if self.free_slot != !0 {
@@ -329,3 +392,457 @@ impl Slot {
         self.x as u32 + self.width as u32
     }
 }
+
+#[derive(Default)]
+struct Entry {
+    allocated: bool,
+    /// Bumped every time the entry is reused so stale ids are rejected.
+    generation: u32,
+    /// Frame in which the image was last used.
+    last_used: u64,
+    /// X coordinate of the image in an atlas.
+    x: u16,
+    /// Y coordinate of the image in an atlas.
+    y: u16,
+    /// Width of the image.
+    width: u16,
+    /// Height of the image.
+    height: u16,
+}
+
+/// CPU side of the image atlas. It keeps the pixel data that is uploaded
+/// to the GPU and the entries allocated on it.
+///
+/// The atlas starts small and grows up to `max_height`. Once it can't grow
+/// anymore, the least recently used images are evicted, images used in the
+/// current frame are never evicted since they are already being drawn.
+pub struct Atlas {
+    alloc: AtlasAllocator,
+    pub buffer: Vec<u8>,
+    width: u16,
+    max_height: u16,
+    entries: Vec<Entry>,
+    free_entries: Vec<u32>,
+    epoch: u64,
+    /// The texture has to be created again, e.g. after growing.
+    pub fresh: bool,
+    pub dirty: bool,
+    /// Images were evicted since the last `take_evicted`.
+    evicted: bool,
+    /// Atlas grew since the last `take_resized`, locations computed
+    /// before are no longer valid.
+    resized: bool,
+}
+
+impl Atlas {
+    pub fn new(width: u16, height: u16, max_height: u16) -> Self {
+        let height = height.min(max_height);
+        Self {
+            alloc: AtlasAllocator::new(width, height),
+            buffer: vec![0u8; width as usize * height as usize * 4],
+            width,
+            max_height,
+            entries: Vec::new(),
+            free_entries: Vec::new(),
+            epoch: 0,
+            fresh: true,
+            dirty: true,
+            evicted: false,
+            resized: false,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u16 {
+        self.alloc.height()
+    }
+
+    /// Starts a new frame, images used from now on are protected from
+    /// eviction until the next frame.
+    #[inline]
+    pub fn begin_frame(&mut self) {
+        self.epoch += 1;
+    }
+
+    /// Allocates a new image and optionally fills it with the specified data.
+    pub fn allocate(&mut self, request: AddImage) -> Option<ImageId> {
+        let width = request.width;
+        let height = request.height;
+
+        // Too big to allocate
+        if width > self.width || height > self.width / 4 {
+            return None;
+        }
+
+        let mut candidates: Option<Vec<usize>> = None;
+        let (x, y) = loop {
+            if let Some(position) = self.alloc.allocate(width, height) {
+                break position;
+            }
+
+            if self.grow() {
+                continue;
+            }
+
+            // Least recently used last, so they are popped first.
+            let candidates = candidates.get_or_insert_with(|| {
+                let mut stale: Vec<usize> = (0..self.entries.len())
+                    .filter(|index| {
+                        let entry = &self.entries[*index];
+                        entry.allocated && entry.last_used < self.epoch
+                    })
+                    .collect();
+                stale.sort_by_key(|index| {
+                    std::cmp::Reverse(self.entries[*index].last_used)
+                });
+                stale
+            });
+
+            let index = candidates.pop()?;
+            self.evict(index);
+        };
+
+        let entry = Entry {
+            allocated: true,
+            generation: 0,
+            last_used: self.epoch,
+            x,
+            y,
+            width,
+            height,
+        };
+        let entry_index = match self.free_entries.pop() {
+            Some(index) => {
+                let generation = self.entries[index as usize].generation.wrapping_add(1);
+                self.entries[index as usize] = Entry {
+                    generation,
+                    ..entry
+                };
+                index
+            }
+            None => {
+                self.entries.push(entry);
+                (self.entries.len() - 1) as u32
+            }
+        };
+
+        if let Some(data) = request.data() {
+            fill(x, y, width, height, data, self.width, &mut self.buffer);
+            self.dirty = true;
+        }
+
+        let generation = self.entries[entry_index as usize].generation;
+        ImageId::new(entry_index, generation, request.has_alpha)
+    }
+
+    /// Deallocates the specified image.
+    pub fn deallocate(&mut self, image: ImageId) -> Option<()> {
+        if !self.is_valid(image) {
+            return None;
+        }
+
+        self.release(image.index());
+        Some(())
+    }
+
+    /// Deallocates every image, ids handed out before become invalid.
+    pub fn clear(&mut self) {
+        self.alloc.clear();
+        for index in 0..self.entries.len() {
+            if self.entries[index].allocated {
+                self.entries[index].allocated = false;
+                self.free_entries.push(index as u32);
+            }
+        }
+        self.evicted = true;
+    }
+
+    /// Retrieves the location of the image in the atlas texture.
+    pub fn get(&self, image: &ImageId) -> Option<ImageLocation> {
+        if !self.is_valid(*image) {
+            return None;
+        }
+
+        let entry = &self.entries[image.index()];
+        let sx = 1. / self.width as f32;
+        let sy = 1. / self.height() as f32;
+        Some(ImageLocation {
+            min: (entry.x as f32 * sx, entry.y as f32 * sy),
+            max: (
+                (entry.x + entry.width) as f32 * sx,
+                (entry.y + entry.height) as f32 * sy,
+            ),
+        })
+    }
+
+    /// Marks the image as used in the current frame.
+    #[inline]
+    pub fn touch(&mut self, image: ImageId) {
+        if self.is_valid(image) {
+            self.entries[image.index()].last_used = self.epoch;
+        }
+    }
+
+    /// Returns true if the image is valid.
+    pub fn is_valid(&self, image: ImageId) -> bool {
+        match self.entries.get(image.index()) {
+            Some(entry) => entry.allocated && entry.generation == image.generation(),
+            None => false,
+        }
+    }
+
+    /// Number of images currently in the atlas.
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free_entries.len()
+    }
+
+    #[inline]
+    pub fn take_evicted(&mut self) -> bool {
+        std::mem::take(&mut self.evicted)
+    }
+
+    #[inline]
+    pub fn take_resized(&mut self) -> bool {
+        std::mem::take(&mut self.resized)
+    }
+
+    fn grow(&mut self) -> bool {
+        let height = self.height();
+        if height >= self.max_height {
+            return false;
+        }
+
+        let height = height.saturating_mul(2).min(self.max_height);
+        // Rows keep their offsets since the width doesn't change.
+        self.buffer
+            .resize(self.width as usize * height as usize * 4, 0);
+        self.alloc.grow(height);
+        self.fresh = true;
+        self.dirty = true;
+        self.resized = true;
+        true
+    }
+
+    fn evict(&mut self, index: usize) {
+        self.release(index);
+        self.evicted = true;
+    }
+
+    fn release(&mut self, index: usize) {
+        let entry = &mut self.entries[index];
+        entry.allocated = false;
+        self.alloc.deallocate(entry.x, entry.y, entry.width);
+        self.free_entries.push(index as u32);
+    }
+}
+
+fn fill(
+    x: u16,
+    y: u16,
+    width: u16,
+    _height: u16,
+    image: &[u8],
+    target_width: u16,
+    target: &mut [u8],
+) -> Option<()> {
+    let channels = 4;
+    let image_pitch = width as usize * channels;
+    let buffer_pitch = target_width as usize * channels;
+    let mut offset = y as usize * buffer_pitch + x as usize * channels;
+    for row in image.chunks(image_pitch) {
+        let dest = target.get_mut(offset..offset + image_pitch)?;
+        dest.copy_from_slice(row);
+        offset += buffer_pitch;
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ImageData;
+    use super::*;
+
+    #[test]
+    fn test_allocate_and_reuse() {
+        let mut atlas = AtlasAllocator::new(64, 64);
+        assert_eq!(atlas.allocate(10, 10), Some((0, 0)));
+        assert_eq!(atlas.allocate(10, 10), Some((11, 0)));
+        assert_eq!(atlas.allocate(65, 10), None);
+
+        // Freeing the last allocation of a line folds it back.
+        assert!(atlas.deallocate(11, 0, 10));
+        assert_eq!(atlas.allocate(10, 10), Some((11, 0)));
+
+        // Freeing in the middle of a line leaves a slot to be reused.
+        assert!(atlas.allocate(10, 10).is_some());
+        assert!(atlas.deallocate(11, 0, 10));
+        assert_eq!(atlas.allocate(10, 10), Some((11, 0)));
+    }
+
+    #[test]
+    fn test_allocate_until_full() {
+        let mut atlas = AtlasAllocator::new(32, 32);
+        let mut allocated = Vec::new();
+        while let Some(position) = atlas.allocate(7, 7) {
+            allocated.push(position);
+        }
+        assert_eq!(allocated.len(), 16);
+
+        // Lines are dropped once all their slots are free.
+        for (x, y) in allocated {
+            assert!(atlas.deallocate(x, y, 7));
+        }
+        assert_eq!(atlas.lines_len(), 0);
+        assert_eq!(atlas.allocate(7, 7), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_grow() {
+        let mut atlas = AtlasAllocator::new(32, 16);
+        assert!(atlas.allocate(10, 10).is_some());
+        assert_eq!(atlas.allocate(10, 10), Some((11, 0)));
+        assert_eq!(atlas.allocate(30, 10), None);
+
+        atlas.grow(32);
+        assert_eq!(atlas.height(), 32);
+        assert_eq!(atlas.allocate(30, 10), Some((0, 12)));
+
+        // Growing never shrinks.
+        atlas.grow(8);
+        assert_eq!(atlas.height(), 32);
+    }
+
+    #[test]
+    fn test_empty_lines_are_merged_and_split() {
+        let mut atlas = AtlasAllocator::new(32, 20);
+        assert_eq!(atlas.allocate(30, 5), Some((0, 0)));
+        assert_eq!(atlas.allocate(30, 5), Some((0, 7)));
+        assert_eq!(atlas.allocate(30, 5), Some((0, 13)));
+        assert_eq!(atlas.lines_len(), 3);
+
+        // Two empty neighbour lines become a single taller line.
+        assert!(atlas.deallocate(0, 0, 30));
+        assert!(atlas.deallocate(0, 7, 30));
+        assert_eq!(atlas.lines_len(), 2);
+        assert_eq!(atlas.allocate(30, 10), Some((0, 0)));
+        assert!(atlas.deallocate(0, 0, 30));
+
+        // Without room for a new line, a small image only takes the
+        // height it needs from the empty one.
+        assert_eq!(atlas.allocate(30, 3), Some((0, 0)));
+        assert_eq!(atlas.lines_len(), 3);
+        assert_eq!(atlas.allocate(30, 3), Some((0, 5)));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut atlas = AtlasAllocator::new(16, 16);
+        while atlas.allocate(7, 7).is_some() {}
+        atlas.clear();
+        assert_eq!(atlas.lines_len(), 0);
+        assert_eq!(atlas.allocate(7, 7), Some((0, 0)));
+    }
+
+    fn image(size: u16) -> AddImage<'static> {
+        AddImage {
+            width: size,
+            height: size,
+            has_alpha: true,
+            data: ImageData::Owned(vec![255; size as usize * size as usize * 4]),
+        }
+    }
+
+    #[test]
+    fn test_atlas_grows_up_to_max_height() {
+        let mut atlas = Atlas::new(64, 16, 64);
+        assert_eq!(atlas.height(), 16);
+        atlas.take_resized();
+
+        for _ in 0..4 {
+            assert!(atlas.allocate(image(15)).is_some());
+        }
+        assert_eq!(atlas.height(), 16);
+        assert!(!atlas.take_resized());
+
+        assert!(atlas.allocate(image(15)).is_some());
+        assert_eq!(atlas.height(), 32);
+        assert!(atlas.take_resized());
+        assert_eq!(atlas.buffer.len(), 64 * 32 * 4);
+
+        for _ in 0..11 {
+            assert!(atlas.allocate(image(15)).is_some());
+        }
+        assert_eq!(atlas.height(), 64);
+        assert_eq!(atlas.len(), 16);
+        assert!(!atlas.take_evicted());
+
+        // Images too tall for the atlas are refused.
+        assert!(atlas.allocate(image(17)).is_none());
+    }
+
+    #[test]
+    fn test_atlas_evicts_least_recently_used() {
+        let mut atlas = Atlas::new(64, 16, 16);
+        let first = atlas.allocate(image(15)).unwrap();
+        let second = atlas.allocate(image(15)).unwrap();
+        atlas.begin_frame();
+        let third = atlas.allocate(image(15)).unwrap();
+        let fourth = atlas.allocate(image(15)).unwrap();
+
+        atlas.begin_frame();
+        atlas.touch(first);
+        atlas.touch(third);
+        atlas.touch(fourth);
+
+        // `second` is the only image not used in this frame.
+        let fifth = atlas.allocate(image(15)).unwrap();
+        assert!(atlas.take_evicted());
+        assert!(!atlas.is_valid(second));
+        assert!(atlas.get(&second).is_none());
+        for image in [first, third, fourth, fifth] {
+            assert!(atlas.is_valid(image));
+        }
+
+        // The entry was reused, but the stale id stays invalid.
+        assert_eq!(fifth.index(), second.index());
+        assert_ne!(fifth, second);
+
+        // Everything is in use in this frame, so nothing can be evicted.
+        assert!(atlas.allocate(image(15)).is_none());
+        assert_eq!(atlas.len(), 4);
+
+        atlas.begin_frame();
+        atlas.touch(fourth);
+        atlas.begin_frame();
+        atlas.touch(first);
+        atlas.touch(fifth);
+        assert!(atlas.allocate(image(15)).is_some());
+        assert!(!atlas.is_valid(third));
+        assert!(atlas.is_valid(fourth));
+    }
+
+    #[test]
+    fn test_atlas_location_and_clear() {
+        let mut atlas = Atlas::new(64, 32, 32);
+        let first = atlas.allocate(image(16)).unwrap();
+        let location = atlas.get(&first).unwrap();
+        assert_eq!(location.min, (0., 0.));
+        assert_eq!(location.max, (0.25, 0.5));
+        assert_eq!(&atlas.buffer[..4], &[255, 255, 255, 255]);
+
+        assert_eq!(atlas.deallocate(first), Some(()));
+        assert_eq!(atlas.deallocate(first), None);
+
+        let second = atlas.allocate(image(8)).unwrap();
+        atlas.clear();
+        assert!(!atlas.is_valid(second));
+        assert_eq!(atlas.len(), 0);
+        assert!(atlas.take_evicted());
+    }
+}
@@ -3,32 +3,12 @@ use crate::context::Context;
 use super::atlas::*;
 use super::*;
 
-#[derive(Default)]
-pub struct Entry {
-    allocated: bool,
-    /// X coordinate of the image in an atlas.
-    x: u16,
-    /// Y coordinate of the image in an atlas.
-    y: u16,
-    /// Width of the image.
-    width: u16,
-    /// Height of the image.
-    height: u16,
-}
-
-pub struct Atlas {
-    alloc: AtlasAllocator,
-    buffer: Vec<u8>,
-    fresh: bool,
-    dirty: bool,
-}
-
 pub struct ImageCache {
-    pub entries: Vec<Entry>,
     atlas: Atlas,
-    max_texture_size: u16,
     texture: wgpu::Texture,
     pub texture_view: wgpu::TextureView,
+    /// Bumped every time the texture is created again.
+    texture_version: usize,
 }
 
 #[inline]
@@ -39,146 +19,88 @@ pub fn buffer_size(width: u32, height: u32) -> Option<usize> {
 }
 
 pub const SIZE: u16 = 2048;
+/// Initial height of the atlas, it grows as more images are cached.
+const INITIAL_HEIGHT: u16 = 512;
 
 impl ImageCache {
-    /// Creates a new image cache.
-    pub fn new(context: &Context) -> Self {
+    /// Creates a new image cache using up to `memory_limit` bytes for
+    /// the atlas texture.
+    pub fn new(context: &Context, memory_limit: usize) -> Self {
         let device = &context.device;
-        // let max_texture_size = max_texture_size.clamp(1024, 8192);
-        let max_texture_size = SIZE;
-
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("rich_text create texture"),
-            size: wgpu::Extent3d {
-                width: SIZE as u32,
-                height: SIZE as u32,
-                depth_or_array_layers: 1,
-            },
-            view_formats: &[],
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
-            mip_level_count: 1,
-            sample_count: 1,
-        });
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        let max_height = (memory_limit / (SIZE as usize * 4)).clamp(
+            INITIAL_HEIGHT as usize,
+            max_dimension.min(u16::MAX as u32) as usize,
+        ) as u16;
+
+        let atlas = Atlas::new(SIZE, INITIAL_HEIGHT, max_height);
+        let texture = create_texture(context, &atlas, "rich_text create texture");
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let alloc = AtlasAllocator::new(max_texture_size, max_texture_size);
-
         Self {
-            entries: Vec::new(),
-            atlas: Atlas {
-                alloc,
-                buffer: vec![
-                    0u8;
-                    max_texture_size as usize * max_texture_size as usize * 4
-                ],
-                fresh: true,
-                dirty: true,
-            },
-            max_texture_size,
+            atlas,
             texture_view,
             texture,
+            texture_version: 0,
         }
     }
 
+    #[inline]
+    pub fn begin_frame(&mut self) {
+        self.atlas.begin_frame();
+    }
+
     /// Allocates a new image and optionally fills it with the specified data.
     pub fn allocate(&mut self, request: AddImage) -> Option<ImageId> {
-        let width = request.width;
-        let height = request.height;
-
         // Check buffer size
-        buffer_size(width as u32, height as u32)?;
-
-        // Too big to allocate
-        if !(width <= self.max_texture_size && height <= (self.max_texture_size / 4)) {
-            return None;
-        }
-
-        let atlas_data = self.atlas.alloc.allocate(width, height);
-        // if atlas_data.is_none() {
-        // return None;
-        // Grow atlas to fit
-        // self.max_texture_size += SIZE;
-        // self.atlas.fresh = true;
-        // self.atlas.dirty = true;
-        // self.entries.clear();
-        // println!("{:?}", self.max_texture_size);
-        // self.atlas.alloc = AtlasAllocator::new(self.max_texture_size, self.max_texture_size);
-        // self.atlas.buffer = vec![
-        //     0u8;
-        //     self.max_texture_size as usize * self.max_texture_size as usize * 4
-        // ];
-        // atlas_data = self.atlas.alloc.allocate(width, height);
-        // println!("{:?}", atlas_data);
+        buffer_size(request.width as u32, request.height as u32)?;
 
-        // if self.max_texture_size > MAX_SIZE {
-        //     println!("should try to grow or reset atlas");
-        // }
-        // }
-        let (x, y) = atlas_data?;
-        let entry_index = self.entries.len();
-        self.entries.push(Entry {
-            allocated: true,
-            x,
-            y,
-            width,
-            height,
-        });
-        if let Some(data) = request.data() {
-            fill(
-                x,
-                y,
-                width,
-                height,
-                data,
-                self.max_texture_size,
-                &mut self.atlas.buffer,
-            );
-            self.atlas.dirty = true;
-        }
-        ImageId::new(entry_index as u32, request.has_alpha)
+        self.atlas.allocate(request)
     }
 
-    // Evaluate if does make sense to deallocate from atlas and if yes, which case?
-    // considering that a terminal uses a short/limited of glyphs compared to a wide text editor
-    // if deallocate an image then is necessary to cleanup cache of draw_layout fn
     /// Deallocates the specified image.
     #[allow(unused)]
     pub fn deallocate(&mut self, image: ImageId) -> Option<()> {
-        let entry = self.entries.get_mut(image.index())?;
-        if !entry.allocated {
-            return None;
-        }
+        self.atlas.deallocate(image)
+    }
 
-        self.atlas.alloc.deallocate(entry.x, entry.y, entry.width);
-        entry.allocated = false;
-        Some(())
+    /// Deallocates every image.
+    pub fn clear(&mut self) {
+        self.atlas.clear();
     }
 
-    /// Retrieves the image for the specified handle and updates the epoch.
+    /// Retrieves the location of the image in the atlas texture.
     pub fn get(&self, handle: &ImageId) -> Option<ImageLocation> {
-        let entry = self.entries.get(handle.index())?;
-        if !entry.allocated {
-            return None;
-        }
-        let s = 1. / self.max_texture_size as f32;
-        Some(ImageLocation {
-            min: (entry.x as f32 * s, entry.y as f32 * s),
-            max: (
-                (entry.x + entry.width) as f32 * s,
-                (entry.y + entry.height) as f32 * s,
-            ),
-        })
+        self.atlas.get(handle)
+    }
+
+    /// Marks the image as used by the current frame.
+    #[inline]
+    pub fn touch(&mut self, image: ImageId) {
+        self.atlas.touch(image);
     }
 
     /// Returns true if the image is valid.
     pub fn is_valid(&self, image: ImageId) -> bool {
-        if let Some(entry) = self.entries.get(image.index()) {
-            entry.allocated
-        } else {
-            false
-        }
+        self.atlas.is_valid(image)
+    }
+
+    /// Returns true if images were evicted since the last call.
+    #[inline]
+    pub fn take_evicted(&mut self) -> bool {
+        self.atlas.take_evicted()
+    }
+
+    /// Returns true if the atlas grew since the last call, image
+    /// locations retrieved before are outdated.
+    #[inline]
+    pub fn take_resized(&mut self) -> bool {
+        self.atlas.take_resized()
+    }
+
+    #[inline]
+    pub fn texture_version(&self) -> usize {
+        self.texture_version
     }
 
     /// Updates an image with the specified data.
@@ -207,94 +129,56 @@ impl ImageCache {
             return;
         }
         if self.atlas.fresh {
-            let texture_size = wgpu::Extent3d {
-                width: (self.max_texture_size).into(),
-                height: (self.max_texture_size).into(),
-                depth_or_array_layers: 1,
-            };
-            let new_texture = context.device.create_texture(&wgpu::TextureDescriptor {
-                size: texture_size,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::COPY_DST,
-                label: Some("rich_text::fresh atlas"),
-                view_formats: &[],
-            });
-
-            context.queue.write_texture(
-                // Tells wgpu where to copy the pixel data
-                wgpu::TexelCopyTextureInfo {
-                    texture: &new_texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                // The actual pixel data
-                &self.atlas.buffer,
-                // The layout of the texture
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some((self.max_texture_size * 4).into()),
-                    rows_per_image: Some((self.max_texture_size).into()),
-                },
-                texture_size,
-            );
-
-            self.texture = new_texture;
+            self.texture = create_texture(context, &self.atlas, "rich_text::fresh atlas");
             self.texture_view = self
                 .texture
                 .create_view(&wgpu::TextureViewDescriptor::default());
-        } else {
-            let texture_size = wgpu::Extent3d {
-                width: (self.max_texture_size).into(),
-                height: (self.max_texture_size).into(),
-                depth_or_array_layers: 1,
-            };
-
-            context.queue.write_texture(
-                // Tells wgpu where to copy the pixel data
-                wgpu::TexelCopyTextureInfo {
-                    texture: &self.texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                    aspect: wgpu::TextureAspect::All,
-                },
-                // The actual pixel data
-                &self.atlas.buffer,
-                // The layout of the texture
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some((self.max_texture_size * 4).into()),
-                    rows_per_image: Some((self.max_texture_size).into()),
-                },
-                texture_size,
-            );
+            self.texture_version += 1;
         }
+
+        let texture_size = wgpu::Extent3d {
+            width: self.atlas.width().into(),
+            height: self.atlas.height().into(),
+            depth_or_array_layers: 1,
+        };
+
+        context.queue.write_texture(
+            // Tells wgpu where to copy the pixel data
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            // The actual pixel data
+            &self.atlas.buffer,
+            // The layout of the texture
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some((self.atlas.width() as u32) * 4),
+                rows_per_image: Some(self.atlas.height().into()),
+            },
+            texture_size,
+        );
+
         self.atlas.fresh = false;
         self.atlas.dirty = false;
     }
 }
 
-fn fill(
-    x: u16,
-    y: u16,
-    width: u16,
-    _height: u16,
-    image: &[u8],
-    target_width: u16,
-    target: &mut [u8],
-) -> Option<()> {
-    let channels = 4;
-    let image_pitch = width as usize * channels;
-    let buffer_pitch = target_width as usize * channels;
-    let mut offset = y as usize * buffer_pitch + x as usize * channels;
-    for row in image.chunks(image_pitch) {
-        let dest = target.get_mut(offset..offset + image_pitch)?;
-        dest.copy_from_slice(row);
-        offset += buffer_pitch;
-    }
-    Some(())
+fn create_texture(context: &Context, atlas: &Atlas, label: &str) -> wgpu::Texture {
+    context.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: atlas.width().into(),
+            height: atlas.height().into(),
+            depth_or_array_layers: 1,
+        },
+        view_formats: &[],
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        mip_level_count: 1,
+        sample_count: 1,
+    })
 }
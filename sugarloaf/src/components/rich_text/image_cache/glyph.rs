@@ -66,14 +66,14 @@ impl GlyphCache {
         self.max_height = max_height;
     }
 
-    // pub fn prune(&mut self, images: &mut ImageCache) {
-    //     self.fonts.retain(|_, entry| {
-    //         for glyph in &entry.glyphs {
-    //             images.deallocate(glyph.1.image);
-    //         }
-    //         false
-    //     });
-    // }
+    /// Drops the glyphs whose image was evicted from the atlas, they are
+    /// rasterized again the next time they are needed.
+    pub fn prune(&mut self, images: &ImageCache) {
+        self.fonts.retain(|_, entry| {
+            entry.glyphs.retain(|_, glyph| images.is_valid(glyph.image));
+            !entry.glyphs.is_empty()
+        });
+    }
 }
 
 fn get_entry<'a>(
@@ -120,6 +120,7 @@ impl GlyphCacheSession<'_> {
         };
         if let Some(entry) = self.entry.glyphs.get(&key) {
             if self.images.is_valid(entry.image) {
+                self.images.touch(entry.image);
                 return Some(*entry);
             }
         }
@@ -130,6 +131,7 @@ impl GlyphCacheSession<'_> {
         let font_data = font_library_data.get(&self.font);
         let should_embolden = font_data.should_embolden;
         let should_italicize = font_data.should_italicize;
+        let variations = font_data.variations.clone();
 
         if let Some(data) = font_library_data.get_data(&self.font) {
             let mut scaler = self
@@ -143,6 +145,7 @@ impl GlyphCacheSession<'_> {
                 // .hint(!IS_MACOS)
                 .hint(enable_hint)
                 .size(self.quant_size.into())
+                .variations(variations)
                 // .normalized_coords(coords)
                 .build();
 
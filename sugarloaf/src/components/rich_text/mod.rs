@@ -53,7 +53,7 @@ pub struct RichTextBrush {
 }
 
 impl RichTextBrush {
-    pub fn new(context: &Context) -> Self {
+    pub fn new(context: &Context, atlas_memory_limit: usize) -> Self {
         let device = &context.device;
         let dlist = DisplayList::new();
         let supported_vertex_buffer = 2_000;
@@ -122,7 +122,7 @@ impl RichTextBrush {
                 ],
             });
 
-        let images = ImageCache::new(context);
+        let images = ImageCache::new(context, atlas_memory_limit);
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -262,25 +262,40 @@ impl RichTextBrush {
             return;
         }
 
-        self.comp.begin();
         let library = state.compositors.advanced.font_library();
-        for rich_text in &state.rich_texts {
-            if let Some(rt) = state.compositors.advanced.get_rich_text(&rich_text.id) {
-                let position = (
-                    rich_text.position[0] * state.style.scale_factor,
-                    rich_text.position[1] * state.style.scale_factor,
-                );
+        self.images.begin_frame();
+        loop {
+            self.comp.begin();
+            for rich_text in &state.rich_texts {
+                if let Some(rt) = state.compositors.advanced.get_rich_text(&rich_text.id)
+                {
+                    let position = (
+                        rich_text.position[0] * state.style.scale_factor,
+                        rich_text.position[1] * state.style.scale_factor,
+                    );
+
+                    draw_layout(
+                        &mut self.comp,
+                        (&mut self.images, &mut self.glyphs),
+                        &rt.lines,
+                        position,
+                        library,
+                        &rt.layout,
+                        graphics,
+                    );
+                }
+            }
 
-                draw_layout(
-                    &mut self.comp,
-                    (&mut self.images, &mut self.glyphs),
-                    &rt.lines,
-                    position,
-                    library,
-                    &rt.layout,
-                    graphics,
-                );
+            // Texture coordinates of the glyphs drawn before the atlas
+            // grew are outdated, so the frame is drawn again.
+            if !self.images.take_resized() {
+                break;
             }
+            graphics.clear_top_layer();
+        }
+
+        if self.images.take_evicted() {
+            self.glyphs.prune(&self.images);
         }
 
         self.dlist.clear();
@@ -312,6 +327,7 @@ impl RichTextBrush {
     #[inline]
     pub fn reset(&mut self) {
         self.glyphs = GlyphCache::new();
+        self.images.clear();
     }
 
     #[inline]
@@ -377,8 +393,8 @@ impl RichTextBrush {
             self.index_buffer_size = size;
         }
 
-        if self.textures_version != self.images.entries.len() {
-            self.textures_version = self.images.entries.len();
+        if self.textures_version != self.images.texture_version() {
+            self.textures_version = self.images.texture_version();
             self.layout_bind_group =
                 ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
                     layout: &self.layout_bind_group_layout,
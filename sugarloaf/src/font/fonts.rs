@@ -22,6 +22,13 @@ pub enum SugarloafFontWidth {
     UltraExpanded,
 }
 
+/// Value of a variable font axis, e.g. `{ axis = "wght", value = 450 }`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SugarloafFontVariation {
+    pub axis: String,
+    pub value: f32,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct SugarloafFont {
     #[serde(default = "default_font_family")]
@@ -32,6 +39,11 @@ pub struct SugarloafFont {
     pub style: SugarloafFontStyle,
     #[serde(default = "Option::default")]
     pub width: Option<SugarloafFontWidth>,
+    /// Font file to load instead of looking up `family` in the system.
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub variations: Vec<SugarloafFontVariation>,
 }
 
 impl Default for SugarloafFont {
@@ -41,6 +53,8 @@ impl Default for SugarloafFont {
             weight: None,
             style: SugarloafFontStyle::Normal,
             width: None,
+            path: None,
+            variations: vec![],
         }
     }
 }
@@ -51,6 +65,18 @@ impl SugarloafFont {
         let current = self.family.replace(' ', "").trim().to_lowercase();
         current == default_font_family()
     }
+
+    /// Resolves `path`, expanding a leading `~` to the home directory.
+    pub fn resolved_path(&self) -> Option<std::path::PathBuf> {
+        let path = self.path.as_ref()?;
+        if let Some(rest) = path.strip_prefix("~/") {
+            if let Some(home) = std::env::var_os("HOME") {
+                return Some(std::path::PathBuf::from(home).join(rest));
+            }
+        }
+
+        Some(std::path::PathBuf::from(path))
+    }
 }
 
 #[inline]
@@ -73,6 +99,8 @@ pub fn default_font_regular() -> SugarloafFont {
         weight: Some(400),
         style: SugarloafFontStyle::Normal,
         width: None,
+        path: None,
+        variations: vec![],
     }
 }
 
@@ -82,6 +110,8 @@ pub fn default_font_bold() -> SugarloafFont {
         weight: Some(800),
         style: SugarloafFontStyle::Normal,
         width: None,
+        path: None,
+        variations: vec![],
     }
 }
 
@@ -91,6 +121,8 @@ pub fn default_font_italic() -> SugarloafFont {
         weight: Some(300),
         style: SugarloafFontStyle::Italic,
         width: None,
+        path: None,
+        variations: vec![],
     }
 }
 
@@ -100,6 +132,8 @@ pub fn default_font_bold_italic() -> SugarloafFont {
         weight: Some(800),
         style: SugarloafFontStyle::Italic,
         width: None,
+        path: None,
+        variations: vec![],
     }
 }
 
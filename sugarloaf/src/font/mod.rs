@@ -13,7 +13,7 @@ use crate::font_introspector::text::cluster::Token;
 use crate::font_introspector::text::cluster::{CharCluster, Status};
 use crate::font_introspector::text::Codepoint;
 use crate::font_introspector::text::Script;
-use crate::font_introspector::{CacheKey, FontRef, Synthesis};
+use crate::font_introspector::{CacheKey, FontRef, Setting, Synthesis};
 use crate::layout::FragmentStyle;
 use crate::SugarloafErrors;
 use ab_glyph::FontArc;
@@ -266,7 +266,7 @@ impl FontLibraryData {
                 self.insert(data);
             }
             FindResult::NotFound(spec) => {
-                if !spec.is_default_family() {
+                if !spec.is_default_family() || spec.path.is_some() {
                     fonts_not_fount.push(spec);
                 } else {
                     self.insert(load_fallback_from_memory(&spec));
@@ -279,7 +279,7 @@ impl FontLibraryData {
                 self.insert(data);
             }
             FindResult::NotFound(spec) => {
-                if !spec.is_default_family() {
+                if !spec.is_default_family() || spec.path.is_some() {
                     fonts_not_fount.push(spec);
                 } else {
                     self.insert(load_fallback_from_memory(&spec));
@@ -292,7 +292,7 @@ impl FontLibraryData {
                 self.insert(data);
             }
             FindResult::NotFound(spec) => {
-                if !spec.is_default_family() {
+                if !spec.is_default_family() || spec.path.is_some() {
                     fonts_not_fount.push(spec);
                 } else {
                     self.insert(load_fallback_from_memory(&spec));
@@ -305,7 +305,7 @@ impl FontLibraryData {
                 self.insert(data);
             }
             FindResult::NotFound(spec) => {
-                if !spec.is_default_family() {
+                if !spec.is_default_family() || spec.path.is_some() {
                     fonts_not_fount.push(spec);
                 } else {
                     self.insert(load_fallback_from_memory(&spec));
@@ -340,7 +340,7 @@ impl FontLibraryData {
                 }
                 FindResult::NotFound(spec) => {
                     self.insert(FontData::from_slice(FONT_TWEMOJI_EMOJI, true).unwrap());
-                    if !spec.is_default_family() {
+                    if !spec.is_default_family() || spec.path.is_some() {
                         fonts_not_fount.push(spec);
                     }
                 }
@@ -350,17 +350,7 @@ impl FontLibraryData {
         }
 
         for extra_font in spec.extras {
-            match find_font(
-                &db,
-                SugarloafFont {
-                    family: extra_font.family,
-                    style: extra_font.style,
-                    weight: extra_font.weight,
-                    width: extra_font.width,
-                },
-                true,
-                true,
-            ) {
+            match find_font(&db, extra_font, true, true) {
                 FindResult::Found(data) => {
                     self.insert(data);
                 }
@@ -440,6 +430,8 @@ pub struct FontData {
     pub should_embolden: bool,
     pub should_italicize: bool,
     pub is_emoji: bool,
+    /// Variable font axes applied when shaping and rasterizing.
+    pub variations: Vec<Setting<f32>>,
 }
 
 impl PartialEq for FontData {
@@ -459,7 +451,10 @@ impl FontData {
         is_emoji: bool,
         font_spec: &SugarloafFont,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let font = FontRef::from_index(&data, 0).unwrap();
+        let font = match FontRef::from_index(&data, 0) {
+            Some(font) => font,
+            None => return Err("invalid font data".into()),
+        };
         let (offset, key) = (font.offset, font.key);
 
         // Return our struct with the original file data and copies of the
@@ -494,6 +489,13 @@ impl FontData {
             stretch,
             path: Some(path),
             is_emoji,
+            variations: font_spec
+                .variations
+                .iter()
+                .map(|variation| {
+                    Setting::from((variation.axis.as_str(), variation.value))
+                })
+                .collect(),
         })
     }
 
@@ -524,6 +526,7 @@ impl FontData {
             stretch,
             path: None,
             is_emoji,
+            variations: vec![],
         })
     }
 }
@@ -557,6 +560,36 @@ fn find_font(
 ) -> FindResult {
     use std::io::Read;
 
+    if let Some(path) = font_spec.resolved_path() {
+        return match load_from_font_source(&path) {
+            Some(font_data) => {
+                match FontData::from_data(
+                    font_data,
+                    path.clone(),
+                    evictable,
+                    is_emoji,
+                    &font_spec,
+                ) {
+                    Ok(d) => {
+                        info!("Font loaded from {}", path.display());
+                        FindResult::Found(d)
+                    }
+                    Err(err_message) => {
+                        warn!(
+                            "Failed to load font from {}, {err_message}",
+                            path.display()
+                        );
+                        FindResult::NotFound(font_spec)
+                    }
+                }
+            }
+            None => {
+                warn!("Failed to read font file {}", path.display());
+                FindResult::NotFound(font_spec)
+            }
+        };
+    }
+
     if !font_spec.is_default_family() {
         let family = font_spec.family.to_string();
         let mut query = crate::font::loader::Query {
@@ -485,6 +485,8 @@ impl Content {
                         self.word_cache.font_id = item.style.font_id;
                         self.word_cache.content = item.content.clone();
                         let font_library = { &mut self.fonts.inner.lock() };
+                        let font_vars =
+                            font_library.get(&item.style.font_id).variations.clone();
                         if let Some(data) = font_library.get_data(&item.style.font_id) {
                             let mut shaper = self
                                 .scx
@@ -492,7 +494,9 @@ impl Content {
                                 .script(script)
                                 .size(state.scaled_font_size)
                                 .features(self.font_features.iter().copied())
-                                .variations(vars.iter().copied())
+                                .variations(
+                                    font_vars.into_iter().chain(vars.iter().copied()),
+                                )
                                 .build();
 
                             shaper.add_str(&self.word_cache.content);
@@ -550,6 +554,8 @@ impl Content {
                     self.word_cache.font_id = item.style.font_id;
                     self.word_cache.content = item.content.clone();
                     let font_library = { &mut self.fonts.inner.lock() };
+                    let font_vars =
+                        font_library.get(&item.style.font_id).variations.clone();
                     if let Some(data) = font_library.get_data(&item.style.font_id) {
                         let mut shaper = self
                             .scx
@@ -557,7 +563,7 @@ impl Content {
                             .script(script)
                             .size(state.scaled_font_size)
                             .features(self.font_features.iter().copied())
-                            .variations(vars.iter().copied())
+                            .variations(font_vars.into_iter().chain(vars.iter().copied()))
                             .build();
 
                         shaper.add_str(&self.word_cache.content);
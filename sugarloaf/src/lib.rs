@@ -13,7 +13,7 @@ pub use font_introspector::{Stretch, Style, Weight};
 pub use crate::sugarloaf::{
     compositors::SugarCompositors,
     graphics::{
-        ColorType, Graphic, GraphicData, GraphicId, Graphics, ResizeCommand,
+        Animation, ColorType, Graphic, GraphicData, GraphicId, Graphics, ResizeCommand,
         ResizeParameter, MAX_GRAPHIC_DIMENSIONS,
     },
     primitives::*,
@@ -68,10 +68,16 @@ pub struct SugarloafWindow {
     pub scale: f32,
 }
 
+/// Size of a 2048x2048 RGBA atlas.
+pub const DEFAULT_ATLAS_MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+
 pub struct SugarloafRenderer {
     pub power_preference: wgpu::PowerPreference,
     pub backend: wgpu::Backends,
     pub font_features: Option<Vec<String>>,
+    /// Maximum size in bytes of the glyph atlas texture, the least
+    /// recently used glyphs are evicted once it is reached.
+    pub atlas_memory_limit: usize,
 }
 
 impl Default for SugarloafRenderer {
@@ -85,6 +91,7 @@ impl Default for SugarloafRenderer {
             power_preference: wgpu::PowerPreference::HighPerformance,
             backend: default_backend,
             font_features: None,
+            atlas_memory_limit: DEFAULT_ATLAS_MEMORY_LIMIT,
         }
     }
 }
@@ -124,6 +131,7 @@ impl Sugarloaf<'_> {
         layout: RootStyle,
     ) -> Result<Sugarloaf<'a>, SugarloafWithErrors<'a>> {
         let font_features = renderer.font_features.to_owned();
+        let atlas_memory_limit = renderer.atlas_memory_limit;
         let ctx = Context::new(window, renderer);
 
         let text_brush = {
@@ -135,7 +143,7 @@ impl Sugarloaf<'_> {
         let rect_brush = RectBrush::init(&ctx);
         let layer_brush = LayerBrush::new(&ctx);
         let quad_brush = QuadBrush::new(&ctx);
-        let rich_text_brush = RichTextBrush::new(&ctx);
+        let rich_text_brush = RichTextBrush::new(&ctx, atlas_memory_limit);
         let state = SugarState::new(layout, font_library, &font_features);
         let filters_brush = FiltersBrush::default();
 
@@ -324,6 +332,8 @@ impl Sugarloaf<'_> {
             &mut self.ctx,
             &mut self.graphics,
         );
+        self.graphics
+            .update_visible_animations(std::time::Instant::now());
 
         match self.ctx.surface.get_current_texture() {
             Ok(frame) => {
@@ -6,16 +6,36 @@
 use crate::sugarloaf::types;
 use crate::sugarloaf::Handle;
 use image_rs::DynamicImage;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp;
+use std::time::{Duration, Instant};
 
 /// Max allowed dimensions (width, height) for the graphic, in pixels.
 pub const MAX_GRAPHIC_DIMENSIONS: [usize; 2] = [4096, 4096];
 
 pub struct GraphicDataEntry {
+    /// Current frame of the graphic.
     pub handle: Handle,
     pub width: f32,
     pub height: f32,
+    animation: Option<AnimationState>,
+}
+
+/// Playback of an animated graphic.
+struct AnimationState {
+    frames: Vec<(Handle, Duration)>,
+    current: usize,
+    /// When the current frame was first drawn, `None` while the graphic is
+    /// not visible.
+    shown_at: Option<Instant>,
+}
+
+impl AnimationState {
+    #[inline]
+    fn deadline(&self) -> Option<Instant> {
+        self.shown_at
+            .map(|shown_at| shown_at + self.frames[self.current].1)
+    }
 }
 
 #[derive(Debug)]
@@ -37,6 +57,8 @@ pub struct Graphics {
     inner: FxHashMap<GraphicId, GraphicDataEntry>,
     pub bottom_layer: Option<BottomLayer>,
     pub top_layer: Vec<GraphicRenderRequest>,
+    /// Animated graphics drawn in the last frame.
+    visible_animations: FxHashSet<GraphicId>,
 }
 
 impl Graphics {
@@ -61,16 +83,32 @@ impl Graphics {
             return;
         }
 
+        let width = graphic_data.width as u32;
+        let height = graphic_data.height as u32;
+        let handle = Handle::from_pixels(width, height, graphic_data.pixels);
+        let animation = graphic_data.animation.map(|animation| {
+            let mut frames = Vec::with_capacity(animation.delays.len());
+            frames.push((handle.clone(), animation.delays[0]));
+            for (pixels, delay) in
+                animation.frames.into_iter().zip(&animation.delays[1..])
+            {
+                frames.push((Handle::from_pixels(width, height, pixels), *delay));
+            }
+
+            AnimationState {
+                frames,
+                current: 0,
+                shown_at: None,
+            }
+        });
+
         self.inner.insert(
             graphic_data.id,
             GraphicDataEntry {
-                handle: Handle::from_pixels(
-                    graphic_data.width as u32,
-                    graphic_data.height as u32,
-                    graphic_data.pixels,
-                ),
+                handle,
                 width: graphic_data.width as f32,
                 height: graphic_data.height as f32,
+                animation,
             },
         );
     }
@@ -78,6 +116,71 @@ impl Graphics {
     #[inline]
     pub fn remove(&mut self, graphic_id: &GraphicId) {
         self.inner.remove(graphic_id);
+        self.visible_animations.remove(graphic_id);
+    }
+
+    /// Track the animated graphics in the top layer, the only ones that
+    /// advance their frames.
+    pub fn update_visible_animations(&mut self, now: Instant) {
+        let mut visible = FxHashSet::default();
+        for request in &self.top_layer {
+            if let Some(animation) = self
+                .inner
+                .get_mut(&request.id)
+                .and_then(|entry| entry.animation.as_mut())
+            {
+                animation.shown_at.get_or_insert(now);
+                visible.insert(request.id);
+            }
+        }
+
+        // Hidden animations start over the current frame when drawn again.
+        for id in self.visible_animations.difference(&visible) {
+            if let Some(animation) = self
+                .inner
+                .get_mut(id)
+                .and_then(|entry| entry.animation.as_mut())
+            {
+                animation.shown_at = None;
+            }
+        }
+
+        self.visible_animations = visible;
+    }
+
+    /// Move the visible animations whose frame expired to the next frame.
+    ///
+    /// Returns true if any frame changed.
+    pub fn advance_animations(&mut self, now: Instant) -> bool {
+        let mut changed = false;
+        for id in &self.visible_animations {
+            let entry = match self.inner.get_mut(id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let animation = match entry.animation.as_mut() {
+                Some(animation) => animation,
+                None => continue,
+            };
+
+            if animation.deadline().is_some_and(|deadline| deadline <= now) {
+                animation.current = (animation.current + 1) % animation.frames.len();
+                animation.shown_at = Some(now);
+                entry.handle = animation.frames[animation.current].0.clone();
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Time until a visible animation has to show its next frame.
+    pub fn next_animation_frame(&self, now: Instant) -> Option<Duration> {
+        self.visible_animations
+            .iter()
+            .filter_map(|id| self.inner.get(id)?.animation.as_ref()?.deadline())
+            .min()
+            .map(|deadline| deadline.saturating_duration_since(now))
     }
 }
 
@@ -102,6 +205,16 @@ pub enum ColorType {
     Rgba,
 }
 
+/// Frames of an animated graphic, all with the size of the graphic.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Animation {
+    /// How long each frame is shown, starting with `GraphicData::pixels`.
+    pub delays: Vec<Duration>,
+
+    /// Pixels of the frames after the first one.
+    pub frames: Vec<Vec<u8>>,
+}
+
 /// Defines a single graphic read from the PTY.
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub struct GraphicData {
@@ -125,6 +238,9 @@ pub struct GraphicData {
 
     /// Render graphic in a different size.
     pub resize: Option<ResizeCommand>,
+
+    /// Following frames, if the graphic is animated.
+    pub animation: Option<Animation>,
 }
 
 impl GraphicData {
@@ -132,7 +248,9 @@ impl GraphicData {
     /// `false`, it is guaranteed that there are no transparent pixels.
     #[inline]
     pub fn maybe_transparent(&self) -> bool {
-        !self.is_opaque && self.color_type == ColorType::Rgba
+        // Other frames may be transparent where the first one is not.
+        (!self.is_opaque && self.color_type == ColorType::Rgba)
+            || self.animation.is_some()
     }
 
     /// Check if all pixels under a region are opaque.
@@ -205,6 +323,7 @@ impl GraphicData {
             pixels,
             is_opaque: false,
             resize: None,
+            animation: None,
         }
     }
 
@@ -221,82 +340,80 @@ impl GraphicData {
             None => return Some(self),
         };
 
-        if (resize.width == ResizeParameter::Auto
-            && resize.height == ResizeParameter::Auto)
-            || self.height == 0
-            || self.width == 0
-        {
+        let (width, height) = resize.dimensions(
+            self.width,
+            self.height,
+            cell_width,
+            cell_height,
+            view_width,
+            view_height,
+        )?;
+
+        if width == self.width && height == self.height {
             return Some(self);
         }
 
-        let mut width = match resize.width {
-            ResizeParameter::Auto => 1,
-            ResizeParameter::Pixels(n) => n as usize,
-            ResizeParameter::Cells(n) => n as usize * cell_width,
-            ResizeParameter::WindowPercent(n) => n as usize * view_width / 100,
+        tracing::trace!("Resize new graphic to width={}, height={}", width, height,);
+        self.resize_exact(width, height)
+    }
+
+    /// Scale the pixels of the graphic to exactly `width` x `height`.
+    pub fn resize_exact(self, width: usize, height: usize) -> Option<Self> {
+        let resize = |pixels| {
+            resize_pixels(
+                self.color_type,
+                (self.width, self.height),
+                pixels,
+                (width, height),
+            )
         };
 
-        let mut height = match resize.height {
-            ResizeParameter::Auto => 1,
-            ResizeParameter::Pixels(n) => n as usize,
-            ResizeParameter::Cells(n) => n as usize * cell_height,
-            ResizeParameter::WindowPercent(n) => n as usize * view_height / 100,
+        let animation = match self.animation {
+            Some(animation) => Some(Animation {
+                frames: animation
+                    .frames
+                    .into_iter()
+                    .map(|frame| Some(resize(frame)?.into_raw()))
+                    .collect::<Option<_>>()?,
+                delays: animation.delays,
+            }),
+            None => None,
         };
 
-        if width == 0 || height == 0 {
-            return None;
-        }
+        let image = DynamicImage::ImageRgba8(resize(self.pixels)?);
+        let mut graphic = Self::from_dynamic_image(self.id, image);
+        graphic.animation = animation;
+        Some(graphic)
+    }
+}
 
-        // Compute "auto" dimensions.
-        if resize.width == ResizeParameter::Auto {
-            width = self.width * height / self.height;
+/// Scale the pixels of a `size` image to `new_size`, as RGBA.
+fn resize_pixels(
+    color_type: ColorType,
+    size: (usize, usize),
+    pixels: Vec<u8>,
+    new_size: (usize, usize),
+) -> Option<image_rs::RgbaImage> {
+    let (width, height) = (size.0 as u32, size.1 as u32);
+
+    // Create a new DynamicImage to resize the graphic.
+    let dynimage = match color_type {
+        ColorType::Rgb => {
+            let buffer = image_rs::RgbImage::from_raw(width, height, pixels)?;
+            DynamicImage::ImageRgb8(buffer)
         }
 
-        if resize.height == ResizeParameter::Auto {
-            height = self.height * width / self.width;
+        ColorType::Rgba => {
+            let buffer = image_rs::RgbaImage::from_raw(width, height, pixels)?;
+            DynamicImage::ImageRgba8(buffer)
         }
+    };
 
-        // Limit size to MAX_GRAPHIC_DIMENSIONS.
-        width = cmp::min(width, MAX_GRAPHIC_DIMENSIONS[0]);
-        height = cmp::min(height, MAX_GRAPHIC_DIMENSIONS[1]);
-
-        tracing::trace!("Resize new graphic to width={}, height={}", width, height,);
-
-        // Create a new DynamicImage to resize the graphic.
-        let dynimage = match self.color_type {
-            ColorType::Rgb => {
-                let buffer = image_rs::RgbImage::from_raw(
-                    self.width as u32,
-                    self.height as u32,
-                    self.pixels,
-                )?;
-                DynamicImage::ImageRgb8(buffer)
-            }
-
-            ColorType::Rgba => {
-                let buffer = image_rs::RgbaImage::from_raw(
-                    self.width as u32,
-                    self.height as u32,
-                    self.pixels,
-                )?;
-                DynamicImage::ImageRgba8(buffer)
-            }
-        };
-
-        // Finally, use `resize` or `resize_exact` to make the new image.
-        let width = width as u32;
-        let height = height as u32;
-        // https://doc.servo.org/image/imageops/enum.FilterType.html
-        let filter = image_rs::imageops::FilterType::Triangle;
-
-        let new_image = if resize.preserve_aspect_ratio {
-            dynimage.resize(width, height, filter)
-        } else {
-            dynimage.resize_exact(width, height, filter)
-        };
+    // https://doc.servo.org/image/imageops/enum.FilterType.html
+    let filter = image_rs::imageops::FilterType::Triangle;
+    let new_image = dynimage.resize_exact(new_size.0 as u32, new_size.1 as u32, filter);
 
-        Some(Self::from_dynamic_image(self.id, new_image))
-    }
+    Some(new_image.into_rgba8())
 }
 
 /// Unit to specify a dimension to resize the graphic.
@@ -325,6 +442,70 @@ pub struct ResizeCommand {
     pub preserve_aspect_ratio: bool,
 }
 
+impl ResizeCommand {
+    /// Size of a `width` x `height` graphic after the resize, so it can be
+    /// known before the pixels are decoded.
+    pub fn dimensions(
+        &self,
+        width: usize,
+        height: usize,
+        cell_width: usize,
+        cell_height: usize,
+        view_width: usize,
+        view_height: usize,
+    ) -> Option<(usize, usize)> {
+        if (self.width == ResizeParameter::Auto && self.height == ResizeParameter::Auto)
+            || height == 0
+            || width == 0
+        {
+            return Some((width, height));
+        }
+
+        let mut new_width = match self.width {
+            ResizeParameter::Auto => 1,
+            ResizeParameter::Pixels(n) => n as usize,
+            ResizeParameter::Cells(n) => n as usize * cell_width,
+            ResizeParameter::WindowPercent(n) => n as usize * view_width / 100,
+        };
+
+        let mut new_height = match self.height {
+            ResizeParameter::Auto => 1,
+            ResizeParameter::Pixels(n) => n as usize,
+            ResizeParameter::Cells(n) => n as usize * cell_height,
+            ResizeParameter::WindowPercent(n) => n as usize * view_height / 100,
+        };
+
+        if new_width == 0 || new_height == 0 {
+            return None;
+        }
+
+        // Compute "auto" dimensions.
+        if self.width == ResizeParameter::Auto {
+            new_width = width * new_height / height;
+        }
+
+        if self.height == ResizeParameter::Auto {
+            new_height = height * new_width / width;
+        }
+
+        // Limit size to MAX_GRAPHIC_DIMENSIONS.
+        new_width = cmp::min(new_width, MAX_GRAPHIC_DIMENSIONS[0]);
+        new_height = cmp::min(new_height, MAX_GRAPHIC_DIMENSIONS[1]);
+
+        // Fit inside the box keeping the aspect ratio, as `DynamicImage::resize`.
+        if self.preserve_aspect_ratio {
+            let ratio = f64::min(
+                new_width as f64 / width as f64,
+                new_height as f64 / height as f64,
+            );
+            new_width = cmp::max((width as f64 * ratio).round() as usize, 1);
+            new_height = cmp::max((height as f64 * ratio).round() as usize, 1);
+        }
+
+        Some((new_width, new_height))
+    }
+}
+
 #[test]
 fn check_opaque_region() {
     let graphic = GraphicData {
@@ -335,6 +516,7 @@ fn check_opaque_region() {
         pixels: vec![255; 10 * 10 * 3],
         is_opaque: true,
         resize: None,
+        animation: None,
     };
 
     assert!(graphic.is_filled(1, 1, 3, 3));
@@ -358,8 +540,47 @@ fn check_opaque_region() {
         color_type: ColorType::Rgba,
         is_opaque: false,
         resize: None,
+        animation: None,
     };
 
     assert!(graphic.is_filled(0, 0, 3, 3));
     assert!(!graphic.is_filled(1, 1, 4, 4));
 }
+
+#[test]
+fn check_resize_dimensions() {
+    let resize = |width, height, preserve_aspect_ratio| ResizeCommand {
+        width,
+        height,
+        preserve_aspect_ratio,
+    };
+
+    let auto = resize(ResizeParameter::Auto, ResizeParameter::Auto, true);
+    assert_eq!(
+        auto.dimensions(200, 100, 10, 20, 800, 600),
+        Some((200, 100))
+    );
+
+    let cells = resize(ResizeParameter::Cells(10), ResizeParameter::Auto, true);
+    assert_eq!(
+        cells.dimensions(200, 100, 10, 20, 800, 600),
+        Some((100, 50))
+    );
+
+    let boxed = resize(
+        ResizeParameter::Pixels(50),
+        ResizeParameter::Pixels(50),
+        true,
+    );
+    assert_eq!(boxed.dimensions(200, 100, 10, 20, 800, 600), Some((50, 25)));
+
+    let exact = resize(
+        ResizeParameter::Pixels(50),
+        ResizeParameter::Pixels(50),
+        false,
+    );
+    assert_eq!(exact.dimensions(200, 100, 10, 20, 800, 600), Some((50, 50)));
+
+    let empty = resize(ResizeParameter::Pixels(0), ResizeParameter::Auto, true);
+    assert_eq!(empty.dimensions(200, 100, 10, 20, 800, 600), None);
+}
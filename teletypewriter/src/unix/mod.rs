@@ -392,12 +392,15 @@ impl ShellUser {
 /// which is a command in Unix and Unix-like operating systems to print the file name of the
 /// terminal connected to standard input. tty stands for TeleTYpewriter.
 ///
+/// Variables in `env` are set on the child on top of the inherited environment.
+///
 /// It returns two [`Pty`] along with respective process name [`String`] and process id (`libc::pid_`)
 ///
 pub fn create_pty_with_spawn(
     shell: &str,
     args: Vec<String>,
     working_directory: &Option<String>,
+    env: &[(String, String)],
     columns: u16,
     rows: u16,
 ) -> Result<Pty, Error> {
@@ -475,6 +478,9 @@ pub fn create_pty_with_spawn(
             }
 
             with_args.push("--env=TERM_PROGRAM=rio".to_string());
+            for (key, value) in env {
+                with_args.push(format!("--env={key}={value}"));
+            }
 
             let output = std::process::Command::new("flatpak-spawn")
                 .args(["--host", "sh", "-c", "echo $SHELL"])
@@ -502,6 +508,7 @@ pub fn create_pty_with_spawn(
 
     builder.env("USER", user.user);
     builder.env("HOME", user.home);
+    builder.envs(env.iter().map(|(key, value)| (key, value)));
 
     unsafe {
         builder.pre_exec(move || {
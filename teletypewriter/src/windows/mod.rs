@@ -36,6 +36,7 @@ pub fn create_pty(
     shell: &str,
     args: Vec<String>,
     working_directory: &Option<String>,
+    env: &[(String, String)],
     columns: u16,
     rows: u16,
 ) -> Result<Pty, std::io::Error> {
@@ -45,7 +46,7 @@ pub fn create_pty(
     } else {
         shell
     };
-    conpty::new(exec, working_directory, columns, rows)
+    conpty::new(exec, working_directory, env, columns, rows)
 }
 
 impl Pty {
@@ -246,6 +247,31 @@ pub fn win32_string<S: AsRef<OsStr> + ?Sized>(value: &S) -> Vec<u16> {
     OsStr::new(value).encode_wide().chain(once(0)).collect()
 }
 
+/// Builds a UTF-16 environment block for `CreateProcessW` with the current
+/// process environment plus `env`, which takes precedence.
+pub fn environment_block(env: &[(String, String)]) -> Vec<u16> {
+    let mut vars: Vec<(String, String)> = std::env::vars_os()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().to_string(),
+                value.to_string_lossy().to_string(),
+            )
+        })
+        .filter(|(key, _)| !env.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)))
+        .collect();
+    vars.extend(env.iter().cloned());
+    // Windows expects the block to be sorted by name, case-insensitively.
+    vars.sort_by_key(|(key, _)| key.to_uppercase());
+
+    let mut block = Vec::new();
+    for (key, value) in vars {
+        block.extend(OsStr::new(&format!("{key}={value}")).encode_wide());
+        block.push(0);
+    }
+    block.push(0);
+    block
+}
+
 pub fn spawn_daemon<I, S>(program: &str, args: I) -> io::Result<()>
 where
     I: IntoIterator<Item = S> + Copy,